@@ -0,0 +1,87 @@
+//! Кэш разбора папок между сканами. Запись по пути живёт, пока не изменились
+//! времена модификации самой папки (файл добавили или удалили) и файлов-ключей
+//! (манифесты, lock-файлы): сканер опрашивает каждые несколько секунд, а
+//! package-lock.json или Cargo.lock бывают по несколько мегабайт.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// mtime папки и файлов-ключей; None — файла нет
+type Stamp = Vec<Option<SystemTime>>;
+
+fn stamp(dir: &Path, files: &[&str]) -> Stamp {
+    let modified = |path: &Path| fs::metadata(path).and_then(|m| m.modified()).ok();
+    std::iter::once(modified(dir))
+        .chain(files.iter().map(|f| modified(&dir.join(f))))
+        .collect()
+}
+
+pub struct DirCache<T> {
+    entries: Mutex<HashMap<PathBuf, (Stamp, T)>>,
+}
+
+impl<T> Default for DirCache<T> {
+    fn default() -> Self {
+        DirCache {
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<T: Clone> DirCache<T> {
+    /// Закэшированное значение для `dir`, если файлы не менялись; иначе `compute`
+    pub fn get(&self, dir: &Path, files: &[&str], compute: impl FnOnce() -> T) -> T {
+        let current = stamp(dir, files);
+        if let Ok(entries) = self.entries.lock() {
+            if let Some((stamp, value)) = entries.get(dir) {
+                if *stamp == current {
+                    return value.clone();
+                }
+            }
+        }
+        // Считаем без блокировки: разбор может быть долгим
+        let value = compute();
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(dir.to_path_buf(), (current, value.clone()));
+        }
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::time::Duration;
+
+    #[test]
+    fn test_recomputes_when_key_file_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest = dir.path().join("package.json");
+        fs::write(&manifest, "{}").unwrap();
+        let cache = DirCache::default();
+        let calls = Cell::new(0);
+        let get = || {
+            cache.get(dir.path(), &["package.json"], || {
+                calls.set(calls.get() + 1);
+                calls.get()
+            })
+        };
+
+        assert_eq!(get(), 1);
+        assert_eq!(get(), 1);
+
+        let later = SystemTime::now() + Duration::from_secs(5);
+        fs::File::options()
+            .write(true)
+            .open(&manifest)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+        assert_eq!(get(), 2);
+        assert_eq!(get(), 2);
+    }
+}
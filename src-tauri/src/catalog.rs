@@ -0,0 +1,406 @@
+//! Каталог известных процессов: имя (+ аргументы) → категория, описание, иконка.
+//! Встроенный `catalog/processes.json` + пользовательский `processes.json`
+//! из конфиг-директории (его записи проверяются первыми).
+
+use globset::{Glob, GlobMatcher};
+use regex::Regex;
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+const BUILTIN: &str = include_str!("../catalog/processes.json");
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessInfo {
+    pub category: String,
+    pub description: String,
+    pub icon: Option<String>,
+}
+
+impl ProcessInfo {
+    /// Процесс, которого нет в каталоге
+    fn unknown() -> Self {
+        ProcessInfo {
+            category: "system".to_string(),
+            description: String::new(),
+            icon: None,
+        }
+    }
+}
+
+/// Запись файла каталога. Имена — без `.exe` и без учёта регистра;
+/// `windows`/`linux`/`macos` — имена, которые действуют только на этой ОС.
+/// `args` — последовательности аргументов ("next dev"), `args_regex` — regex по
+/// командной строке; если заданы, запись требует совпадения и по ним.
+/// Запись без имён, но с аргументами подходит к любому процессу.
+#[derive(Debug, Deserialize)]
+struct RawEntry {
+    #[serde(default)]
+    names: Vec<String>,
+    #[serde(default)]
+    windows: Vec<String>,
+    #[serde(default)]
+    linux: Vec<String>,
+    #[serde(default)]
+    macos: Vec<String>,
+    glob: Option<String>,
+    regex: Option<String>,
+    #[serde(default)]
+    args: Vec<String>,
+    args_regex: Option<String>,
+    category: String,
+    #[serde(default)]
+    description: String,
+    icon: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawCatalog {
+    processes: Vec<RawEntry>,
+}
+
+#[derive(Debug)]
+struct Entry {
+    names: Vec<String>,
+    glob: Option<GlobMatcher>,
+    regex: Option<Regex>,
+    /// Каждая последовательность — уже разбитые по пробелам токены
+    args: Vec<Vec<String>>,
+    args_regex: Option<Regex>,
+    info: ProcessInfo,
+}
+
+impl Entry {
+    /// None — некорректный glob/regex: такую запись пропускаем целиком,
+    /// чтобы опечатка не превращалась в «совпадает со всем»
+    fn compile(raw: RawEntry) -> Option<Entry> {
+        let os_names = if cfg!(windows) {
+            raw.windows
+        } else if cfg!(target_os = "macos") {
+            raw.macos
+        } else {
+            raw.linux
+        };
+        let glob = match raw.glob {
+            Some(pattern) => Some(Glob::new(&normalize(&pattern)).ok()?.compile_matcher()),
+            None => None,
+        };
+        let regex = match raw.regex {
+            Some(pattern) => Some(Regex::new(&format!("(?i){}", pattern)).ok()?),
+            None => None,
+        };
+        let args_regex = match raw.args_regex {
+            Some(pattern) => Some(Regex::new(&format!("(?i){}", pattern)).ok()?),
+            None => None,
+        };
+        let args: Vec<Vec<String>> = raw
+            .args
+            .iter()
+            .map(|seq| {
+                seq.split_whitespace()
+                    .map(str::to_lowercase)
+                    .collect::<Vec<_>>()
+            })
+            .filter(|seq| !seq.is_empty())
+            .collect();
+        Some(Entry {
+            names: raw
+                .names
+                .iter()
+                .chain(&os_names)
+                .map(|n| normalize(n))
+                .collect(),
+            glob,
+            regex,
+            args,
+            args_regex,
+            info: ProcessInfo {
+                category: raw.category,
+                description: raw.description,
+                icon: raw.icon,
+            },
+        })
+    }
+
+    fn matches(&self, name: &str, command_line: &[String]) -> bool {
+        let has_name_rule = !self.names.is_empty() || self.glob.is_some() || self.regex.is_some();
+        let has_args_rule = !self.args.is_empty() || self.args_regex.is_some();
+        if !has_name_rule && !has_args_rule {
+            return false;
+        }
+        let name_ok = !has_name_rule
+            || self.names.iter().any(|n| n == name)
+            || self.glob.as_ref().is_some_and(|g| g.is_match(name))
+            || self.regex.as_ref().is_some_and(|r| r.is_match(name));
+        name_ok && (!has_args_rule || self.matches_args(name, command_line))
+    }
+
+    fn matches_args(&self, name: &str, command_line: &[String]) -> bool {
+        if let Some(re) = &self.args_regex {
+            if re.is_match(&command_line.join(" ")) {
+                return true;
+            }
+        }
+        // Без командной строки (нет доступа к процессу) — хотя бы имя как argv[0]
+        let tokens: Vec<String> = if command_line.is_empty() {
+            vec![name.to_string()]
+        } else {
+            command_line.iter().map(|a| arg_token(a)).collect()
+        };
+        self.args.iter().any(|seq| {
+            tokens
+                .windows(seq.len())
+                .any(|w| w.iter().zip(seq).all(|(t, s)| t == s))
+        })
+    }
+}
+
+/// Аргумент для сравнения с `args`: путь → имя файла без расширения скрипта.
+/// "C:\\app\\node_modules\\vite\\bin\\vite.js" → "vite", "manage.py" → "manage"
+pub(crate) fn arg_token(arg: &str) -> String {
+    let file = arg.rsplit(['/', '\\']).next().unwrap_or(arg);
+    let lower = file.to_lowercase();
+    for ext in [".js", ".cjs", ".mjs", ".ts", ".py", ".exe", ".cmd", ".bat"] {
+        if let Some(stem) = lower.strip_suffix(ext) {
+            return stem.to_string();
+        }
+    }
+    lower
+}
+
+/// "Node.EXE" → "node": имена в каталоге и от сканера сравниваются в одном виде
+pub(crate) fn normalize(name: &str) -> String {
+    let lower = name.trim().to_lowercase();
+    match lower.strip_suffix(".exe") {
+        Some(stem) => stem.to_string(),
+        None => lower,
+    }
+}
+
+#[derive(Debug)]
+pub struct Catalog {
+    entries: Vec<Entry>,
+}
+
+impl Catalog {
+    fn parse(json: &str) -> Result<Vec<Entry>, String> {
+        let raw: RawCatalog = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        Ok(raw
+            .processes
+            .into_iter()
+            .filter_map(Entry::compile)
+            .collect())
+    }
+
+    pub fn builtin() -> Catalog {
+        Catalog {
+            entries: Catalog::parse(BUILTIN).expect("built-in process catalog is valid"),
+        }
+    }
+
+    /// Встроенный каталог + пользовательский файл. Файла нет или он битый —
+    /// только встроенный.
+    pub fn with_overrides(path: &Path) -> Catalog {
+        let mut entries = fs::read_to_string(path)
+            .ok()
+            .and_then(|json| Catalog::parse(&json).ok())
+            .unwrap_or_default();
+        entries.extend(Catalog::builtin().entries);
+        Catalog { entries }
+    }
+
+    /// Первая подходящая запись; пользовательские стоят раньше встроенных,
+    /// правила по аргументам — раньше правил только по имени
+    pub fn lookup(&self, name: &str, command_line: &[String]) -> ProcessInfo {
+        let name = normalize(name);
+        self.entries
+            .iter()
+            .find(|e| e.matches(&name, command_line))
+            .map(|e| e.info.clone())
+            .unwrap_or_else(ProcessInfo::unknown)
+    }
+}
+
+static CATALOG: OnceLock<Catalog> = OnceLock::new();
+
+/// Загрузить каталог с пользовательским файлом (один раз, при старте)
+pub fn init(user_path: &Path) {
+    let _ = CATALOG.set(Catalog::with_overrides(user_path));
+}
+
+/// Каталог приложения; до `init` (в тестах) — встроенный
+pub fn global() -> &'static Catalog {
+    CATALOG.get_or_init(Catalog::builtin)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_catalog_parses_every_entry() {
+        let raw: RawCatalog = serde_json::from_str(BUILTIN).unwrap();
+        assert_eq!(Catalog::builtin().entries.len(), raw.processes.len());
+    }
+
+    #[test]
+    fn test_lookup_with_and_without_exe() {
+        let catalog = Catalog::builtin();
+        for name in ["node", "node.exe", "NODE.EXE", "Node"] {
+            let info = catalog.lookup(name, &[]);
+            assert_eq!(info.category, "dev", "{}", name);
+            assert_eq!(info.description, "Node.js");
+        }
+        assert_eq!(catalog.lookup("Code.exe", &[]).category, "app");
+    }
+
+    #[test]
+    fn test_lookup_unknown_is_system_without_description() {
+        let info = Catalog::builtin().lookup("mystery.exe", &[]);
+        assert_eq!(info, ProcessInfo::unknown());
+    }
+
+    #[test]
+    fn test_lookup_glob_and_regex() {
+        let catalog = Catalog::builtin();
+        assert_eq!(catalog.lookup("python3.12", &[]).description, "Python");
+        assert_eq!(catalog.lookup("php-fpm8.2", &[]).description, "PHP");
+        assert_eq!(catalog.lookup("phpstorm", &[]).description, "");
+    }
+
+    #[test]
+    fn test_os_specific_names() {
+        let catalog = Catalog::builtin();
+        let svchost = catalog.lookup("svchost.exe", &[]);
+        let avahi = catalog.lookup("avahi-daemon", &[]);
+        if cfg!(windows) {
+            assert_eq!(svchost.description, "Windows Service Host");
+            assert_eq!(avahi.description, "");
+        } else if cfg!(target_os = "linux") {
+            assert_eq!(svchost.description, "");
+            assert_eq!(avahi.description, "Avahi (mDNS)");
+        }
+    }
+
+    #[test]
+    fn test_user_overrides_take_precedence() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("processes.json");
+        fs::write(
+            &path,
+            r#"{ "processes": [
+                { "names": ["postgres"], "category": "dev", "description": "Dev DB" },
+                { "glob": "acme-gw-*", "category": "gateway", "description": "ACME gateway", "icon": "🚪" },
+                { "regex": "([", "category": "dev" }
+            ] }"#,
+        )
+        .unwrap();
+
+        let catalog = Catalog::with_overrides(&path);
+        assert_eq!(catalog.lookup("postgres.exe", &[]).category, "dev");
+        assert_eq!(catalog.lookup("postgres.exe", &[]).description, "Dev DB");
+        let gw = catalog.lookup("acme-gw-eu1", &[]);
+        assert_eq!(gw.category, "gateway");
+        assert_eq!(gw.icon.as_deref(), Some("🚪"));
+        // Встроенные записи никуда не делись
+        assert_eq!(catalog.lookup("node", &[]).description, "Node.js");
+        // Битая regex-запись пропущена, а не совпала со всем подряд
+        assert_eq!(catalog.lookup("mystery", &[]).description, "");
+    }
+
+    #[test]
+    fn test_corrupt_override_file_falls_back_to_builtin() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("processes.json");
+        fs::write(&path, "{ processes: ").unwrap();
+        let catalog = Catalog::with_overrides(&path);
+        assert_eq!(catalog.entries.len(), Catalog::builtin().entries.len());
+    }
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_lookup_by_arguments() {
+        let catalog = Catalog::builtin();
+        let vite = catalog.lookup(
+            "node",
+            &args("node /app/node_modules/vite/bin/vite.js --port 5173"),
+        );
+        assert_eq!(vite.category, "dev");
+        assert_eq!(vite.description, "Vite");
+
+        let next = catalog.lookup(
+            "node.exe",
+            &args(r"node.exe C:\app\node_modules\next\dist\bin\next dev"),
+        );
+        assert_eq!(next.description, "Next.js");
+        // Токены сравниваются целиком, а не подстрокой
+        let other = catalog.lookup("node", &args("node server.js --next dev-ish"));
+        assert_eq!(other.description, "Node.js");
+
+        let uvicorn = catalog.lookup(
+            "python3",
+            &args("/venv/bin/python3 /venv/bin/uvicorn main:app"),
+        );
+        assert_eq!(uvicorn.description, "Uvicorn");
+        let jupyter = catalog.lookup("python3.12", &args("python3.12 -m jupyter lab"));
+        assert_eq!(jupyter.description, "Jupyter");
+    }
+
+    #[test]
+    fn test_args_rule_without_command_line_uses_name() {
+        // Скрипт с shebang: comm = имя скрипта, cmdline недоступен
+        let info = Catalog::builtin().lookup("uvicorn", &[]);
+        assert_eq!(info.description, "Uvicorn");
+    }
+
+    #[test]
+    fn test_python_rules_need_python_process() {
+        let catalog = Catalog::builtin();
+        // Путь-аргумент с тем же именем у браузера или редактора — не сервер
+        let chrome = catalog.lookup("chrome", &args("chrome --user-data-dir=/home/u/jupyter"));
+        assert_eq!(chrome.description, "Google Chrome");
+        let code = catalog.lookup("code", &args("code /home/u/src/uvicorn"));
+        assert_eq!(code.description, "Visual Studio Code");
+        let vim = catalog.lookup("vim", &args("vim gunicorn.py"));
+        assert_ne!(vim.description, "Gunicorn");
+
+        let lab = catalog.lookup("jupyter-lab", &args("/venv/bin/jupyter-lab --port 8888"));
+        assert_eq!(lab.description, "Jupyter");
+        let django = catalog.lookup("python3.11", &args("python3.11 manage.py runserver"));
+        assert_eq!(django.description, "Django");
+        let flask = catalog.lookup("flask", &args("/venv/bin/flask run"));
+        assert_eq!(flask.description, "Flask");
+    }
+
+    #[test]
+    fn test_user_args_regex_reclassifies_postgres() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("processes.json");
+        fs::write(
+            &path,
+            r#"{ "processes": [
+                { "names": ["postgres"], "args_regex": "-D\\s+\\S*[\\\\/]dev[\\\\/]", "category": "dev", "description": "Dev DB" }
+            ] }"#,
+        )
+        .unwrap();
+        let catalog = Catalog::with_overrides(&path);
+
+        let dev = catalog.lookup("postgres.exe", &args(r"postgres.exe -D C:\dev\db -p 5433"));
+        assert_eq!(dev.category, "dev");
+        let prod = catalog.lookup("postgres", &args("postgres -D /var/lib/postgresql/16/main"));
+        assert_eq!(prod.category, "system");
+        assert_eq!(prod.description, "PostgreSQL");
+    }
+
+    #[test]
+    fn test_arg_token() {
+        assert_eq!(arg_token(r"C:\app\node_modules\vite\bin\vite.js"), "vite");
+        assert_eq!(arg_token("/srv/manage.py"), "manage");
+        assert_eq!(arg_token("--port"), "--port");
+        assert_eq!(arg_token("main:app"), "main:app");
+    }
+}
@@ -0,0 +1,236 @@
+//! Анализ конфликтов портов по готовому списку серверов:
+//! один порт у нескольких процессов, dev-сервер, съехавший с занятого
+//! стандартного порта, и стандартный dev-порт у постороннего процесса.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::scanner::{IpFamily, Protocol};
+use crate::Server;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WarningKind {
+    /// Тот же порт и протокол слушает ещё один процесс (IPv4/IPv6 раздельно, SO_REUSEPORT)
+    SharedPort,
+    /// Dev-сервер на порту чуть выше стандартного, а стандартный занят другим процессом
+    PortFallback,
+    /// Стандартный dev-порт занят не dev-процессом
+    UnexpectedOwner,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Warning {
+    pub kind: WarningKind,
+    pub message: String,
+}
+
+/// Порты по умолчанию популярных dev-серверов
+const DEV_PORTS: &[(u16, &str)] = &[
+    (1420, "Tauri"),
+    (3000, "Next.js / Express"),
+    (4200, "Angular CLI"),
+    (4321, "Astro"),
+    (5000, "Flask"),
+    (5173, "Vite"),
+    (6006, "Storybook"),
+    (8000, "Django / Uvicorn"),
+    (8080, "webpack-dev-server"),
+    (8888, "Jupyter"),
+];
+
+/// Насколько выше стандартного порта dev-серверы ищут свободный
+const FALLBACK_RANGE: u16 = 10;
+
+fn family_label(server: &Server) -> &'static str {
+    let v4 = server.addresses.iter().any(|a| a.family == IpFamily::Ipv4);
+    let v6 = server.addresses.iter().any(|a| a.family == IpFamily::Ipv6);
+    match (v4, v6) {
+        (true, false) => "IPv4",
+        (false, true) => "IPv6",
+        _ => "IPv4 + IPv6",
+    }
+}
+
+/// Заполнить `warnings` у каждого сервера
+pub fn annotate(servers: &mut [Server]) {
+    let mut by_port: BTreeMap<(Protocol, u16), Vec<usize>> = BTreeMap::new();
+    for (i, s) in servers.iter().enumerate() {
+        by_port.entry((s.protocol, s.port)).or_default().push(i);
+    }
+
+    let mut warnings: Vec<Vec<Warning>> = vec![Vec::new(); servers.len()];
+
+    for (i, server) in servers.iter().enumerate() {
+        // 1. Общий порт
+        let key = (server.protocol, server.port);
+        for &j in &by_port[&key] {
+            let other = &servers[j];
+            if other.pid == server.pid {
+                continue;
+            }
+            warnings[i].push(Warning {
+                kind: WarningKind::SharedPort,
+                message: format!(
+                    "Порт {} також слухає {} (PID {}, {})",
+                    server.port,
+                    other.process_name,
+                    other.pid,
+                    family_label(other)
+                ),
+            });
+        }
+
+        if server.protocol != Protocol::Tcp {
+            continue;
+        }
+
+        // 2. Стандартный dev-порт у не-dev процесса
+        if let Some((_, owner)) = DEV_PORTS.iter().find(|(p, _)| *p == server.port) {
+            if server.category != "dev" {
+                warnings[i].push(Warning {
+                    kind: WarningKind::UnexpectedOwner,
+                    message: format!(
+                        "Порт {} зазвичай займає {}, а тут {}",
+                        server.port, owner, server.process_name
+                    ),
+                });
+            }
+        }
+
+        // 3. Dev-сервер съехал на соседний порт, потому что стандартный занят
+        if server.category == "dev" {
+            let fallback_from = DEV_PORTS.iter().map(|(p, _)| *p).find(|&p| {
+                server.port > p
+                    && server.port - p <= FALLBACK_RANGE
+                    && by_port
+                        .get(&(Protocol::Tcp, p))
+                        .is_some_and(|owners| owners.iter().any(|&j| servers[j].pid != server.pid))
+            });
+            if let Some(default_port) = fallback_from {
+                let holder = by_port[&(Protocol::Tcp, default_port)]
+                    .iter()
+                    .map(|&j| &servers[j])
+                    .find(|s| s.pid != server.pid)
+                    .expect("owner checked above");
+                warnings[i].push(Warning {
+                    kind: WarningKind::PortFallback,
+                    message: format!(
+                        "Ймовірно перейшов на {}: порт {} зайнятий {} (PID {})",
+                        server.port, default_port, holder.process_name, holder.pid
+                    ),
+                });
+            }
+        }
+    }
+
+    for (server, w) in servers.iter_mut().zip(warnings) {
+        server.warnings = w;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::BindAddress;
+
+    fn server(pid: u32, port: u16, name: &str, category: &str, ips: &[&str]) -> Server {
+        Server {
+            project_name: name.to_string(),
+            process_name: name.to_string(),
+            category: category.to_string(),
+            addresses: ips
+                .iter()
+                .map(|ip| BindAddress::from(ip.parse::<std::net::IpAddr>().unwrap()))
+                .collect(),
+            ..Server::sample(pid, port)
+        }
+    }
+
+    fn kinds(server: &Server) -> Vec<WarningKind> {
+        server.warnings.iter().map(|w| w.kind).collect()
+    }
+
+    #[test]
+    fn test_no_warnings_for_regular_servers() {
+        let mut servers = vec![
+            server(1, 5173, "node", "dev", &["127.0.0.1"]),
+            server(2, 5432, "postgres", "system", &["0.0.0.0"]),
+        ];
+        annotate(&mut servers);
+        assert!(servers.iter().all(|s| s.warnings.is_empty()));
+    }
+
+    #[test]
+    fn test_split_families_on_one_port() {
+        let mut servers = vec![
+            server(1, 5173, "node", "dev", &["127.0.0.1"]),
+            server(2, 5173, "python", "dev", &["::1"]),
+        ];
+        annotate(&mut servers);
+        assert_eq!(kinds(&servers[0]), vec![WarningKind::SharedPort]);
+        assert_eq!(
+            servers[0].warnings[0].message,
+            "Порт 5173 також слухає python (PID 2, IPv6)"
+        );
+        assert_eq!(kinds(&servers[1]), vec![WarningKind::SharedPort]);
+    }
+
+    #[test]
+    fn test_tcp_and_udp_on_same_port_is_not_shared() {
+        let mut servers = vec![
+            server(1, 4433, "node", "dev", &["0.0.0.0"]),
+            Server {
+                protocol: Protocol::Udp,
+                ..server(2, 4433, "quic-proxy", "system", &["0.0.0.0"])
+            },
+        ];
+        annotate(&mut servers);
+        assert!(servers.iter().all(|s| s.warnings.is_empty()));
+    }
+
+    #[test]
+    fn test_dev_server_fallback_port() {
+        let mut servers = vec![
+            server(1, 3000, "node", "dev", &["0.0.0.0"]),
+            server(2, 3001, "node", "dev", &["0.0.0.0"]),
+            server(3, 3020, "node", "dev", &["0.0.0.0"]),
+        ];
+        annotate(&mut servers);
+        assert!(servers[0].warnings.is_empty());
+        assert_eq!(kinds(&servers[1]), vec![WarningKind::PortFallback]);
+        assert_eq!(
+            servers[1].warnings[0].message,
+            "Ймовірно перейшов на 3001: порт 3000 зайнятий node (PID 1)"
+        );
+        // Слишком далеко от 3000 — это не fallback
+        assert!(servers[2].warnings.is_empty());
+    }
+
+    #[test]
+    fn test_same_process_on_neighbour_ports_is_not_fallback() {
+        // Vite + HMR одного процесса
+        let mut servers = vec![
+            server(1, 5173, "node", "dev", &["0.0.0.0"]),
+            server(1, 5174, "node", "dev", &["0.0.0.0"]),
+        ];
+        annotate(&mut servers);
+        assert!(servers.iter().all(|s| s.warnings.is_empty()));
+    }
+
+    #[test]
+    fn test_unexpected_owner_of_dev_port() {
+        // macOS: AirPlay Receiver (ControlCenter) держит :5000
+        let mut servers = vec![
+            server(1, 5000, "ControlCenter", "system", &["0.0.0.0"]),
+            server(2, 5001, "python3", "dev", &["127.0.0.1"]),
+        ];
+        annotate(&mut servers);
+        assert_eq!(kinds(&servers[0]), vec![WarningKind::UnexpectedOwner]);
+        assert_eq!(
+            servers[0].warnings[0].message,
+            "Порт 5000 зазвичай займає Flask, а тут ControlCenter"
+        );
+        assert_eq!(kinds(&servers[1]), vec![WarningKind::PortFallback]);
+    }
+}
@@ -1,84 +1,478 @@
+//! Фреймворк и имя проекта по файлам в папке (CWD процесса). Фреймворк
+//! определяет реестр детекторов `FrameworkDetector`: встроенные правила +
+//! пользовательские из `frameworks.json` в конфиг-директории; побеждает
+//! самая высокая уверенность, при равной — кто зарегистрирован раньше.
+
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::cache::DirCache;
+use crate::versions::{self, Declared, Ecosystem, Installed};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Framework {
+    pub name: String,
+    /// Установленная (lock-файл, node_modules), иначе объявленный диапазон
+    /// ("^14.0.0"); None — не известна
+    pub version: Option<String>,
+    /// 0.0–1.0: прямая зависимость — высокая, «есть package.json» — низкая
+    pub confidence: f32,
+    /// Что сработало: "package.json: devDependencies.vite", "manage.py"
+    pub evidence: Vec<String>,
+}
 
-/// Читает и парсит package.json из папки проекта
-fn read_package_json(project_path: &Path) -> Option<Value> {
-    let pkg_path = project_path.join("package.json");
-    let content = fs::read_to_string(&pkg_path).ok()?;
-    serde_json::from_str(&content).ok()
+/// Папка проекта; манифесты читаются один раз на все детекторы
+pub struct ProjectDir {
+    path: PathBuf,
+    /// Корень npm/yarn/pnpm/Cargo workspace, если папка — его пакет
+    workspace_root: Option<PathBuf>,
+    dependencies: Vec<Declared>,
 }
 
-/// Проверяет наличие пакета в dependencies или devDependencies
-fn has_dependency(json: &Value, package: &str) -> bool {
-    let check = |field: &str| -> bool {
-        json.get(field)
-            .and_then(|v| v.as_object())
-            .map_or(false, |deps| deps.contains_key(package))
-    };
-    check("dependencies") || check("devDependencies")
+impl ProjectDir {
+    pub fn open(path: &Path, workspace_root: Option<&Path>) -> ProjectDir {
+        ProjectDir {
+            path: path.to_path_buf(),
+            workspace_root: workspace_root.map(Path::to_path_buf),
+            dependencies: versions::declared(path),
+        }
+    }
+
+    /// Зависимости из package.json, Cargo.toml, go.mod, requirements.txt, pyproject.toml
+    pub fn dependencies(&self) -> &[Declared] {
+        &self.dependencies
+    }
+
+    /// Установленная версия зависимости — из node_modules или lock-файла;
+    /// у пакета монорепо они обычно в корне workspace (hoisting)
+    pub fn installed(&self, dependency: &Declared) -> Option<Installed> {
+        let lookup = |dir: &Path| versions::installed(dir, dependency.ecosystem, &dependency.name);
+        lookup(&self.path).or_else(|| {
+            let root = self.workspace_root.as_deref()?;
+            (root != self.path).then(|| lookup(root)).flatten()
+        })
+    }
+
+    /// Файл по имени или шаблону "*.csproj"; найденное имя — для улик
+    pub fn find_file(&self, pattern: &str) -> Option<String> {
+        match pattern.strip_prefix("*.") {
+            Some(extension) => versions::files_with_extension(&self.path, extension)
+                .into_iter()
+                .next(),
+            None => self
+                .path
+                .join(pattern)
+                .exists()
+                .then(|| pattern.to_string()),
+        }
+    }
 }
 
-/// Определить фреймворк по файлам в папке проекта
-pub fn detect_framework(project_path: &str) -> String {
-    let path = Path::new(project_path);
+/// Детектор фреймворка. None — не узнал; иначе фреймворк с уверенностью
+/// и уликами, реестр выберет лучший из всех.
+pub trait FrameworkDetector: Send + Sync {
+    fn detect(&self, dir: &ProjectDir) -> Option<Framework>;
 
-    // Проверяем package.json
-    if let Some(json) = read_package_json(path) {
-        // Порядок важен: более специфичные фреймворки сначала
-        let frameworks: &[(&str, &str)] = &[
-            ("next", "Next.js"),
-            ("nuxt", "Nuxt"),
-            ("svelte", "Svelte"),
-            ("astro", "Astro"),
-            ("react-scripts", "CRA"),
-            ("express", "Express"),
-            ("fastify", "Fastify"),
-        ];
-
-        for (package, name) in frameworks {
-            if has_dependency(&json, package) {
-                return name.to_string();
-            }
+    /// Уточнить результат-победитель дорогими проверками (установленная
+    /// версия из lock-файла) — только у него, не у всех кандидатов
+    fn refine(&self, _dir: &ProjectDir, _framework: &mut Framework) {}
+}
+
+fn default_confidence() -> f32 {
+    0.9
+}
+
+/// Правило: любая из зависимостей package.json или любой из файлов.
+/// Встроенные детекторы — такие же правила, что и в `frameworks.json`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Rule {
+    pub name: String,
+    /// Имена пакетов npm; "@vitejs/*" — любой пакет с этим префиксом.
+    /// Других экосистем — с префиксом: "cargo:axum", "go:github.com/gin-gonic/gin",
+    /// "pypi:fastapi".
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+    /// Файлы относительно папки проекта; "*.csproj" — любой с расширением
+    #[serde(default)]
+    pub files: Vec<String>,
+    #[serde(default = "default_confidence")]
+    pub confidence: f32,
+}
+
+impl Rule {
+    fn new(name: &str, dependencies: &[&str], files: &[&str], confidence: f32) -> Rule {
+        Rule {
+            name: name.to_string(),
+            dependencies: dependencies.iter().map(|d| d.to_string()).collect(),
+            files: files.iter().map(|f| f.to_string()).collect(),
+            confidence,
+        }
+    }
+
+    fn matches_package(pattern: &str, package: &str) -> bool {
+        match pattern.strip_suffix('*') {
+            Some(prefix) => package.starts_with(prefix),
+            None => package == pattern,
+        }
+    }
+
+    /// Объявленные зависимости, совпавшие с шаблонами, в порядке шаблонов
+    fn matched<'a>(&self, dir: &'a ProjectDir) -> Vec<&'a Declared> {
+        let mut matched = Vec::new();
+        for pattern in &self.dependencies {
+            let (ecosystem, pattern) = Ecosystem::split(pattern);
+            let pattern = match ecosystem {
+                Ecosystem::Python => versions::normalize_python(pattern),
+                _ => pattern.to_string(),
+            };
+            matched.extend(
+                dir.dependencies().iter().filter(|d| {
+                    d.ecosystem == ecosystem && Rule::matches_package(&pattern, &d.name)
+                }),
+            );
         }
+        matched
+    }
+}
 
-        // Vite: основной пакет или любой @vitejs/* плагин
-        if has_dependency(&json, "vite") || has_vitejs_plugin(&json) {
-            return "Vite".to_string();
+impl FrameworkDetector for Rule {
+    fn detect(&self, dir: &ProjectDir) -> Option<Framework> {
+        let matched = self.matched(dir);
+        // Порядок шаблонов важен: версия — от первого совпавшего
+        let version = matched
+            .first()
+            .map(|d| d.range.clone())
+            .filter(|r| !r.is_empty());
+        let mut evidence: Vec<String> = matched.iter().map(|d| d.evidence.clone()).collect();
+        evidence.extend(self.files.iter().filter_map(|f| dir.find_file(f)));
+        if evidence.is_empty() {
+            return None;
         }
+        Some(Framework {
+            name: self.name.clone(),
+            version,
+            confidence: self.confidence.clamp(0.0, 1.0),
+            evidence,
+        })
+    }
 
-        return "Node.js".to_string();
+    /// Установленная версия точнее объявленного диапазона
+    fn refine(&self, dir: &ProjectDir, framework: &mut Framework) {
+        let matched = self.matched(dir);
+        let Some(dependency) = matched.first() else {
+            return;
+        };
+        if let Some(installed) = dir.installed(dependency) {
+            // Источник — сразу за уликой самой зависимости
+            let at = framework
+                .evidence
+                .iter()
+                .position(|e| *e == dependency.evidence)
+                .map_or(framework.evidence.len(), |i| i + 1);
+            framework
+                .evidence
+                .insert(at, format!("{}: {}", installed.source, installed.version));
+            framework.version = Some(installed.version);
+        }
     }
+}
+
+/// Встроенные правила. Мета-фреймворки уверенней сборщиков и серверов:
+/// у Next.js-приложения может быть и express, у SvelteKit — и vite,
+/// у Leptos — axum; у FastAPI — uvicorn.
+fn builtin_rules() -> Vec<Rule> {
+    vec![
+        Rule::new("Next.js", &["next"], &[], 0.95),
+        Rule::new("Nuxt", &["nuxt"], &[], 0.95),
+        Rule::new("Svelte", &["svelte"], &[], 0.95),
+        Rule::new("Astro", &["astro"], &[], 0.95),
+        Rule::new("CRA", &["react-scripts"], &[], 0.9),
+        Rule::new("Express", &["express"], &[], 0.85),
+        Rule::new("Fastify", &["fastify"], &[], 0.85),
+        Rule::new("Vite", &["vite", "@vitejs/*"], &[], 0.8),
+        Rule::new("Django", &["pypi:django"], &["manage.py"], 0.9),
+        Rule::new("FastAPI", &["pypi:fastapi"], &[], 0.9),
+        Rule::new("Flask", &["pypi:flask"], &[], 0.9),
+        Rule::new(
+            "Streamlit",
+            &["pypi:streamlit"],
+            &[".streamlit/config.toml"],
+            0.9,
+        ),
+        Rule::new(
+            "Jupyter",
+            &["pypi:jupyterlab", "pypi:notebook", "pypi:jupyter"],
+            &["*.ipynb"],
+            0.7,
+        ),
+        // Серверы приложений: фреймворк за ними, если известен, уверенней
+        Rule::new("Uvicorn", &["pypi:uvicorn"], &[], 0.6),
+        Rule::new("Gunicorn", &["pypi:gunicorn"], &[], 0.6),
+        Rule::new("Rails", &["gem:rails"], &["bin/rails"], 0.9),
+        Rule::new("Sinatra", &["gem:sinatra"], &[], 0.85),
+        Rule::new(
+            "Laravel",
+            &["composer:laravel/framework"],
+            &["artisan"],
+            0.9,
+        ),
+        Rule::new(
+            "Symfony",
+            &["composer:symfony/framework-bundle"],
+            &["symfony.lock"],
+            0.85,
+        ),
+        Rule::new(
+            "Spring Boot",
+            &[
+                "maven:spring-boot-starter*",
+                "maven:org.springframework.boot",
+            ],
+            &[],
+            0.9,
+        ),
+        Rule::new(
+            "ASP.NET",
+            &[
+                "nuget:Microsoft.NET.Sdk.Web",
+                "nuget:Microsoft.AspNetCore.*",
+            ],
+            &[],
+            0.9,
+        ),
+        Rule::new("Phoenix", &["hex:phoenix"], &[], 0.9),
+        Rule::new("Leptos", &["cargo:leptos"], &[], 0.95),
+        Rule::new("Axum", &["cargo:axum"], &[], 0.9),
+        Rule::new("Actix", &["cargo:actix-web"], &[], 0.9),
+        Rule::new("Rocket", &["cargo:rocket"], &[], 0.9),
+        // Рантайм: deno.json рядом с package.json — всё равно Deno
+        Rule::new("Deno", &[], &["deno.json", "deno.jsonc"], 0.5),
+        // Только язык: package.json раньше прочих, как и было
+        Rule::new("Node.js", &[], &["package.json"], 0.3),
+        Rule::new("Python", &[], &["requirements.txt", "pyproject.toml"], 0.3),
+        Rule::new("Go", &[], &["go.mod"], 0.3),
+        Rule::new("Rust", &[], &["Cargo.toml"], 0.3),
+        Rule::new("Ruby", &[], &["Gemfile"], 0.3),
+        Rule::new("PHP", &[], &["composer.json"], 0.3),
+        Rule::new(
+            "Java",
+            &[],
+            &["pom.xml", "build.gradle", "build.gradle.kts"],
+            0.3,
+        ),
+        Rule::new(".NET", &[], &["*.csproj"], 0.3),
+        Rule::new("Elixir", &[], &["mix.exs"], 0.3),
+    ]
+}
 
-    // Python
-    if path.join("requirements.txt").exists() || path.join("pyproject.toml").exists() {
-        if path.join("manage.py").exists() {
-            return "Django".to_string();
+#[derive(Deserialize)]
+struct RawRules {
+    #[serde(default)]
+    frameworks: Vec<Rule>,
+}
+
+pub struct Registry {
+    detectors: Vec<Box<dyn FrameworkDetector>>,
+}
+
+impl Registry {
+    pub fn builtin() -> Registry {
+        let mut registry = Registry {
+            detectors: Vec::new(),
+        };
+        for rule in builtin_rules() {
+            registry.register(Box::new(rule));
         }
-        return "Python".to_string();
+        registry
     }
 
-    // Go
-    if path.join("go.mod").exists() {
-        return "Go".to_string();
+    /// Правила из `frameworks.json` + встроенные. Пользовательские стоят
+    /// раньше и побеждают при равной уверенности; файла нет или он битый —
+    /// только встроенные.
+    pub fn with_user_rules(path: &Path) -> Registry {
+        let rules = fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str::<RawRules>(&json).ok())
+            .map(|raw| raw.frameworks)
+            .unwrap_or_default();
+        let mut registry = Registry {
+            detectors: Vec::new(),
+        };
+        for rule in rules.into_iter().filter(|r| !r.name.is_empty()) {
+            registry.register(Box::new(rule));
+        }
+        registry.detectors.extend(Registry::builtin().detectors);
+        registry
     }
 
-    // Rust
-    if path.join("Cargo.toml").exists() {
-        return "Rust".to_string();
+    pub fn register(&mut self, detector: Box<dyn FrameworkDetector>) {
+        self.detectors.push(detector);
     }
 
-    "Unknown".to_string()
+    /// Лучший из ответивших детекторов. `workspace_root` — корень монорепо,
+    /// если `path` — его пакет: установленные версии ищутся и там
+    pub fn detect(&self, path: &Path, workspace_root: Option<&Path>) -> Option<Framework> {
+        let dir = ProjectDir::open(path, workspace_root);
+        let mut best: Option<(&dyn FrameworkDetector, Framework)> = None;
+        for detector in &self.detectors {
+            let Some(found) = detector.detect(&dir) else {
+                continue;
+            };
+            if best
+                .as_ref()
+                .is_none_or(|(_, b)| found.confidence > b.confidence)
+            {
+                best = Some((detector.as_ref(), found));
+            }
+        }
+        let (detector, mut framework) = best?;
+        detector.refine(&dir, &mut framework);
+        Some(framework)
+    }
+}
+
+static REGISTRY: OnceLock<Registry> = OnceLock::new();
+
+/// Загрузить реестр с пользовательским файлом (один раз, при старте)
+pub fn init(user_path: &Path) {
+    let _ = REGISTRY.set(Registry::with_user_rules(user_path));
+}
+
+/// Реестр приложения; до `init` (в тестах) — встроенный
+pub fn global() -> &'static Registry {
+    REGISTRY.get_or_init(Registry::builtin)
+}
+
+/// Файлы, по которым папка считается корнем проекта
+const MANIFESTS: &[&str] = &[
+    "package.json",
+    "Cargo.toml",
+    "go.mod",
+    "pyproject.toml",
+    "requirements.txt",
+    "setup.py",
+    "manage.py",
+    "Gemfile",
+    "composer.json",
+    "pom.xml",
+    "build.gradle",
+    "build.gradle.kts",
+    "mix.exs",
+    "deno.json",
+    "deno.jsonc",
+];
+
+pub fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .filter(|h| !h.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Папки вверх от `cwd`, где ищем корень проекта или монорепо: до репозитория
+/// (папка с `.git` — последняя) и не доходя до домашней папки. Общая граница
+/// для `project_root` и `Workspace::find`.
+pub fn search_dirs<'a>(cwd: &'a Path, home: Option<&'a Path>) -> impl Iterator<Item = &'a Path> {
+    let mut repository_passed = false;
+    cwd.ancestors()
+        // package.json в домашней папке — почти всегда случайный
+        .take_while(move |dir| Some(*dir) != home)
+        .take_while(move |dir| {
+            let inside = !repository_passed;
+            repository_passed = dir.join(".git").exists();
+            inside
+        })
+}
+
+/// Корень проекта для CWD: ближайшая вверх папка с манифестом (см. `search_dirs`);
+/// внутри node_modules манифесты чужих пакетов пропускаются. None — манифеста нет.
+pub fn project_root(cwd: &Path) -> Option<PathBuf> {
+    find_root(cwd, home_dir().as_deref())
+}
+
+fn has_manifest(dir: &Path) -> bool {
+    MANIFESTS.iter().any(|m| dir.join(m).is_file())
+        || !versions::files_with_extension(dir, "csproj").is_empty()
+}
+
+fn find_root(cwd: &Path, home: Option<&Path>) -> Option<PathBuf> {
+    search_dirs(cwd, home)
+        .find(|dir| {
+            let vendored = dir.components().any(|c| c.as_os_str() == "node_modules");
+            !vendored && has_manifest(dir)
+        })
+        .map(Path::to_path_buf)
 }
 
-/// Проверяет наличие @vitejs/* плагинов в dependencies/devDependencies
-fn has_vitejs_plugin(json: &Value) -> bool {
-    let check = |field: &str| -> bool {
-        json.get(field)
-            .and_then(|v| v.as_object())
-            .map_or(false, |deps| deps.keys().any(|k| k.starts_with("@vitejs/")))
-    };
-    check("dependencies") || check("devDependencies")
+/// Читает и парсит package.json из папки проекта
+fn read_package_json(project_path: &Path) -> Option<Value> {
+    let pkg_path = project_path.join("package.json");
+    let content = fs::read_to_string(&pkg_path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Файлы, от которых зависит результат детекторов: манифесты, lock-файлы,
+/// node_modules (установленные версии). Остальное — через mtime самой папки.
+const DETECT_KEYS: &[&str] = &[
+    "package.json",
+    "package-lock.json",
+    "pnpm-lock.yaml",
+    "yarn.lock",
+    "bun.lock",
+    "node_modules",
+    "Cargo.toml",
+    "Cargo.lock",
+    "go.mod",
+    "requirements.txt",
+    "pyproject.toml",
+    "poetry.lock",
+    "Gemfile",
+    "Gemfile.lock",
+    "composer.json",
+    "composer.lock",
+    "pom.xml",
+    "build.gradle",
+    "build.gradle.kts",
+    "mix.exs",
+    "mix.lock",
+    "deno.json",
+    "deno.jsonc",
+];
+
+static DETECTED: OnceLock<DirCache<Option<Framework>>> = OnceLock::new();
+
+/// Определить фреймворк по файлам в папке проекта. Результат кэшируется,
+/// пока не изменились манифесты и lock-файлы: скан идёт каждые несколько секунд.
+pub fn detect(project_path: &str) -> Option<Framework> {
+    detect_in(project_path, None)
+}
+
+/// `detect` для пакета монорепо: версии — и из lock-файлов корня `workspace_root`
+pub fn detect_in(project_path: &str, workspace_root: Option<&str>) -> Option<Framework> {
+    let path = Path::new(project_path);
+    let root = workspace_root.map(Path::new);
+    // Абсолютные пути ключей кэш берёт как есть: следим и за lock-файлами корня
+    let root_keys: Vec<String> = root
+        .into_iter()
+        .flat_map(|root| DETECT_KEYS.iter().map(|key| root.join(key)))
+        .map(|key| key.to_string_lossy().into_owned())
+        .collect();
+    let keys: Vec<&str> = DETECT_KEYS
+        .iter()
+        .copied()
+        .chain(root_keys.iter().map(String::as_str))
+        .collect();
+    DETECTED
+        .get_or_init(DirCache::default)
+        .get(path, &keys, || global().detect(path, root))
+}
+
+/// Только имя фреймворка; "Unknown", если не определён
+pub fn detect_framework(project_path: &str) -> String {
+    detect(project_path).map_or_else(|| "Unknown".to_string(), |f| f.name)
 }
 
 /// Определить имя проекта из package.json или имени папки
@@ -192,6 +586,168 @@ mod tests {
         assert_eq!(detect_framework(dir.path().to_str().unwrap()), "Node.js");
     }
 
+    #[test]
+    fn test_framework_version_confidence_evidence() {
+        let dir = create_temp_project(r#"{
+            "dependencies": { "express": "^4.18.0" },
+            "devDependencies": { "vite": "5.0.0", "@vitejs/plugin-react": "4.0.0" }
+        }"#);
+        let found = detect(dir.path().to_str().unwrap()).unwrap();
+        assert_eq!(found.name, "Express");
+        assert_eq!(found.version.as_deref(), Some("^4.18.0"));
+        assert_eq!(found.evidence, vec!["package.json: dependencies.express"]);
+
+        fs::write(
+            dir.path().join("package.json"),
+            r#"{ "devDependencies": { "@vitejs/plugin-react": "4.0.0", "vite": "5.0.0" } }"#,
+        )
+        .unwrap();
+        // Грубые метки времени ФС: сдвигаем mtime, чтобы кэш точно увидел правку
+        let later = std::time::SystemTime::now() + std::time::Duration::from_secs(5);
+        fs::File::options()
+            .write(true)
+            .open(dir.path().join("package.json"))
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+        let vite = detect(dir.path().to_str().unwrap()).unwrap();
+        // Версия — от первого шаблона правила ("vite"), улики — все
+        assert_eq!(vite.version.as_deref(), Some("5.0.0"));
+        assert_eq!(vite.evidence.len(), 2);
+        assert!(vite.confidence > 0.5);
+    }
+
+    #[test]
+    fn test_only_winner_is_refined() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        struct Counted(&'static str, f32, Arc<AtomicUsize>);
+        impl FrameworkDetector for Counted {
+            fn detect(&self, _: &ProjectDir) -> Option<Framework> {
+                Some(Framework {
+                    name: self.0.to_string(),
+                    version: None,
+                    confidence: self.1,
+                    evidence: Vec::new(),
+                })
+            }
+            fn refine(&self, _: &ProjectDir, _: &mut Framework) {
+                self.2.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+        let (weak, strong) = (Arc::new(AtomicUsize::new(0)), Arc::new(AtomicUsize::new(0)));
+        let mut registry = Registry {
+            detectors: Vec::new(),
+        };
+        registry.register(Box::new(Counted("Weak", 0.5, weak.clone())));
+        registry.register(Box::new(Counted("Strong", 0.9, strong.clone())));
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(registry.detect(dir.path(), None).unwrap().name, "Strong");
+        assert_eq!(
+            (weak.load(Ordering::SeqCst), strong.load(Ordering::SeqCst)),
+            (0, 1)
+        );
+    }
+
+    #[test]
+    fn test_user_rules_and_custom_detectors() {
+        let dir = create_temp_project(
+            r#"{
+            "dependencies": { "@remix-run/dev": "2.8.0", "express": "4.18.0" }
+        }"#,
+        );
+        let config = tempfile::tempdir().unwrap();
+        let rules = config.path().join("frameworks.json");
+        fs::write(
+            &rules,
+            r#"{ "frameworks": [
+                { "name": "Remix", "dependencies": ["@remix-run/*"] },
+                { "name": "Deno Fresh", "files": ["fresh.gen.ts"], "confidence": 0.99 }
+            ] }"#,
+        )
+        .unwrap();
+        let mut registry = Registry::with_user_rules(&rules);
+        let found = registry.detect(dir.path(), None).unwrap();
+        assert_eq!((found.name.as_str(), found.confidence), ("Remix", 0.9));
+
+        struct Always;
+        impl FrameworkDetector for Always {
+            fn detect(&self, _: &ProjectDir) -> Option<Framework> {
+                Some(Framework {
+                    name: "Custom".to_string(),
+                    version: None,
+                    confidence: 1.0,
+                    evidence: Vec::new(),
+                })
+            }
+        }
+        registry.register(Box::new(Always));
+        assert_eq!(registry.detect(dir.path(), None).unwrap().name, "Custom");
+
+        // Битый файл — только встроенные правила
+        fs::write(&rules, "{ not json").unwrap();
+        let builtin = Registry::with_user_rules(&rules)
+            .detect(dir.path(), None)
+            .unwrap();
+        assert_eq!(builtin.name, "Express");
+    }
+
+    #[test]
+    fn test_installed_version_from_lockfile() {
+        let dir = create_temp_project(r#"{ "dependencies": { "next": "^13.4.0" } }"#);
+        fs::write(
+            dir.path().join("package-lock.json"),
+            r#"{ "packages": { "node_modules/next": { "version": "13.5.6" } } }"#,
+        )
+        .unwrap();
+        let found = detect(dir.path().to_str().unwrap()).unwrap();
+        assert_eq!(found.version.as_deref(), Some("13.5.6"));
+        assert_eq!(
+            found.evidence,
+            vec![
+                "package.json: dependencies.next",
+                "package-lock.json: 13.5.6"
+            ]
+        );
+
+        let django = tempfile::tempdir().unwrap();
+        fs::write(django.path().join("manage.py"), "").unwrap();
+        fs::write(django.path().join("requirements.txt"), "Django==4.2.11\n").unwrap();
+        let found = detect(django.path().to_str().unwrap()).unwrap();
+        assert_eq!(found.name, "Django");
+        assert_eq!(found.version.as_deref(), Some("4.2.11"));
+    }
+
+    #[test]
+    fn test_installed_version_from_workspace_root() {
+        // yarn workspaces: lock-файл и node_modules подняты в корень
+        let root = tempfile::tempdir().unwrap();
+        let web = root.path().join("apps/web");
+        fs::create_dir_all(&web).unwrap();
+        fs::write(
+            root.path().join("package.json"),
+            r#"{ "workspaces": ["apps/*"] }"#,
+        )
+        .unwrap();
+        fs::write(
+            root.path().join("yarn.lock"),
+            "\"vite@^5.0.0\":\n  version \"5.2.8\"\n",
+        )
+        .unwrap();
+        fs::write(
+            web.join("package.json"),
+            r#"{ "devDependencies": { "vite": "^5.0.0" } }"#,
+        )
+        .unwrap();
+
+        let (web, root) = (web.to_str().unwrap(), root.path().to_str().unwrap());
+        assert_eq!(detect(web).unwrap().version.as_deref(), Some("^5.0.0"));
+        let vite = detect_in(web, Some(root)).unwrap();
+        assert_eq!(vite.version.as_deref(), Some("5.2.8"));
+        assert!(vite.evidence.contains(&"yarn.lock: 5.2.8".to_string()));
+    }
+
     #[test]
     fn test_detect_project_name() {
         let dir = create_temp_project(r#"{ "name": "cool-project" }"#);
@@ -228,9 +784,194 @@ mod tests {
         assert_eq!(detect_framework(dir.path().to_str().unwrap()), "Go");
     }
 
+    /// Фреймворк папки с файлами (путь → содержимое)
+    fn framework_for(files: &[(&str, &str)]) -> Option<Framework> {
+        let dir = tempfile::tempdir().unwrap();
+        for (name, text) in files {
+            let path = dir.path().join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, text).unwrap();
+        }
+        detect(dir.path().to_str().unwrap())
+    }
+
+    fn name_for(files: &[(&str, &str)]) -> String {
+        framework_for(files).map_or_else(|| "Unknown".to_string(), |f| f.name)
+    }
+
+    #[test]
+    fn test_python_frameworks() {
+        let fastapi = framework_for(&[(
+            "requirements.txt",
+            "fastapi==0.110.0\nuvicorn[standard]>=0.27\n",
+        )])
+        .unwrap();
+        assert_eq!(fastapi.name, "FastAPI");
+        assert_eq!(fastapi.version.as_deref(), Some("0.110.0"));
+        assert_eq!(
+            name_for(&[(
+                "pyproject.toml",
+                "[project]\ndependencies = [\"Flask>=3\"]\n"
+            )]),
+            "Flask"
+        );
+        assert_eq!(
+            name_for(&[("requirements.txt", "streamlit\npandas\n")]),
+            "Streamlit"
+        );
+        assert_eq!(name_for(&[("analysis.ipynb", "{}")]), "Jupyter");
+        assert_eq!(
+            name_for(&[("requirements.txt", "jupyterlab==4.1.0\n")]),
+            "Jupyter"
+        );
+        // Сервер без известного фреймворка — сам сервер
+        assert_eq!(name_for(&[("requirements.txt", "gunicorn\n")]), "Gunicorn");
+        assert_eq!(name_for(&[("requirements.txt", "uvicorn\n")]), "Uvicorn");
+        assert_eq!(
+            name_for(&[("requirements.txt", "gunicorn\ndjango\n")]),
+            "Django"
+        );
+    }
+
+    #[test]
+    fn test_ruby_and_php_frameworks() {
+        let rails = framework_for(&[
+            ("Gemfile", "gem \"rails\", \"~> 7.1\"\n"),
+            ("Gemfile.lock", "GEM\n  specs:\n    rails (7.1.3)\n"),
+        ])
+        .unwrap();
+        assert_eq!(rails.name, "Rails");
+        assert_eq!(rails.version.as_deref(), Some("7.1.3"));
+        assert_eq!(name_for(&[("Gemfile", "gem 'sinatra'\n")]), "Sinatra");
+        assert_eq!(name_for(&[("Gemfile", "gem 'rack'\n")]), "Ruby");
+        assert_eq!(
+            name_for(&[
+                (
+                    "composer.json",
+                    r#"{ "require": { "laravel/framework": "^11.0" } }"#
+                ),
+                ("artisan", ""),
+            ]),
+            "Laravel"
+        );
+        assert_eq!(
+            name_for(&[(
+                "composer.json",
+                r#"{ "require": { "symfony/framework-bundle": "7.0.*" } }"#,
+            )]),
+            "Symfony"
+        );
+        assert_eq!(name_for(&[("composer.json", "{}")]), "PHP");
+    }
+
+    #[test]
+    fn test_jvm_dotnet_elixir_deno() {
+        let pom = "<project><parent><artifactId>spring-boot-starter-parent</artifactId><version>3.2.2</version></parent></project>";
+        let spring = framework_for(&[("pom.xml", pom)]).unwrap();
+        assert_eq!(spring.name, "Spring Boot");
+        assert_eq!(spring.version.as_deref(), Some("3.2.2"));
+        assert_eq!(
+            name_for(&[(
+                "build.gradle",
+                "plugins {\n  id 'org.springframework.boot' version '3.2.0'\n}\n",
+            )]),
+            "Spring Boot"
+        );
+        assert_eq!(name_for(&[("pom.xml", "<project></project>")]), "Java");
+
+        let aspnet = framework_for(&[(
+            "Api.csproj",
+            "<Project Sdk=\"Microsoft.NET.Sdk.Web\"></Project>",
+        )])
+        .unwrap();
+        assert_eq!(aspnet.name, "ASP.NET");
+        assert_eq!(
+            name_for(&[(
+                "Tool.csproj",
+                "<Project Sdk=\"Microsoft.NET.Sdk\"></Project>"
+            )]),
+            ".NET"
+        );
+
+        assert_eq!(
+            name_for(&[("mix.exs", "defp deps, do: [{:phoenix, \"~> 1.7\"}]")]),
+            "Phoenix"
+        );
+        assert_eq!(name_for(&[("mix.exs", "defp deps, do: []")]), "Elixir");
+        assert_eq!(
+            name_for(&[("deno.json", "{}"), ("package.json", "{}")]),
+            "Deno"
+        );
+    }
+
+    #[test]
+    fn test_rust_frameworks() {
+        let cargo = |deps: &str| format!("[package]\nname = \"api\"\n\n[dependencies]\n{}", deps);
+        let axum = framework_for(&[
+            ("Cargo.toml", &cargo("axum = \"0.7\"\ntokio = \"1\"\n")),
+            (
+                "Cargo.lock",
+                "[[package]]\nname = \"axum\"\nversion = \"0.7.4\"\n",
+            ),
+        ])
+        .unwrap();
+        assert_eq!(axum.name, "Axum");
+        assert_eq!(axum.version.as_deref(), Some("0.7.4"));
+        assert_eq!(
+            name_for(&[("Cargo.toml", &cargo("actix-web = \"4\"\n"))]),
+            "Actix"
+        );
+        assert_eq!(
+            name_for(&[("Cargo.toml", &cargo("rocket = { version = \"0.5\" }\n"))]),
+            "Rocket"
+        );
+        // Leptos поверх axum — Leptos
+        assert_eq!(
+            name_for(&[("Cargo.toml", &cargo("leptos = \"0.6\"\naxum = \"0.7\"\n"))]),
+            "Leptos"
+        );
+    }
+
     #[test]
     fn test_unknown_project() {
         let dir = tempfile::tempdir().unwrap();
         assert_eq!(detect_framework(dir.path().to_str().unwrap()), "Unknown");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_project_root_from_subdirectory() {
+        let dir = create_temp_project(r#"{ "name": "api" }"#);
+        let root = dir.path();
+        fs::create_dir(root.join(".git")).unwrap();
+        fs::create_dir_all(root.join("apps/api/dist")).unwrap();
+        fs::write(root.join("apps/api/go.mod"), "module api").unwrap();
+        fs::create_dir_all(root.join("node_modules/vite/bin")).unwrap();
+        fs::write(root.join("node_modules/vite/package.json"), "{}").unwrap();
+
+        let find = |sub: &str| find_root(&root.join(sub), None);
+        assert_eq!(find("apps/api/dist"), Some(root.join("apps/api")));
+        assert_eq!(find("src"), Some(root.to_path_buf()));
+        // vite/package.json — не корень проекта
+        assert_eq!(find("node_modules/vite/bin"), Some(root.to_path_buf()));
+    }
+
+    #[test]
+    fn test_project_root_boundaries() {
+        let dir = tempfile::tempdir().unwrap();
+        let outer = dir.path();
+        fs::write(outer.join("package.json"), "{}").unwrap();
+        fs::create_dir_all(outer.join("home/user/scratch")).unwrap();
+        fs::write(outer.join("home/user/package.json"), "{}").unwrap();
+        fs::create_dir_all(outer.join("repo/.git")).unwrap();
+        fs::create_dir_all(outer.join("repo/src")).unwrap();
+
+        let home = outer.join("home/user");
+        // Выше .git и домашней папки не поднимаемся
+        assert_eq!(find_root(&outer.join("repo/src"), None), None);
+        assert_eq!(find_root(&home.join("scratch"), Some(&home)), None);
+        assert_eq!(
+            find_root(&outer.join("home"), Some(&home)),
+            Some(outer.to_path_buf())
+        );
+    }
+}
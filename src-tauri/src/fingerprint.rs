@@ -0,0 +1,536 @@
+//! Определение протокола неизвестных слушателей по баннеру или рукопожатию.
+//! Каталог не знает exe — но порт сам скажет, кто он: SSH и MySQL здороваются
+//! первыми, остальным шлём минимальный запрос их протокола (HTTP, TLS,
+//! PostgreSQL, Redis, MongoDB, AMQP, HTTP/2) и смотрим на ответ. Один раз на
+//! (PID, порт), результат — `Server.fingerprint`.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::task::JoinSet;
+use tokio::time::timeout;
+
+use crate::health::{client_hello, http_request, is_tls, probe_addr};
+use crate::scanner::Protocol;
+use crate::Server;
+
+/// Таймаут соединения и ответа на каждый запрос
+pub const SNIFF_TIMEOUT: Duration = Duration::from_secs(1);
+/// Сколько ждать баннера, прежде чем спрашивать самим
+const BANNER_WAIT: Duration = Duration::from_millis(500);
+/// Заголовков HTTP и приветствия MySQL хватает с запасом
+const MAX_REPLY_BYTES: usize = 4096;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Service {
+    Http,
+    /// Ответ на ClientHello; что внутри — не расшифровываем
+    Tls,
+    /// HTTP/2 без TLS (h2c) — обычно gRPC
+    Http2,
+    Postgres,
+    Mysql,
+    Redis,
+    Mongodb,
+    Ssh,
+    Amqp,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Fingerprint {
+    pub service: Service,
+    /// Что удалось узнать сверх протокола: `Server` / `X-Powered-By`,
+    /// версия SSH или MySQL, продукт AMQP
+    pub detail: Option<String>,
+}
+
+fn found(service: Service, detail: Option<String>) -> Option<Fingerprint> {
+    Some(Fingerprint { service, detail })
+}
+
+/// Сервер заговорил первым: SSH ("SSH-2.0-OpenSSH_9.6") или MySQL (handshake v10)
+fn from_banner(banner: &[u8]) -> Option<Fingerprint> {
+    if let Some(rest) = banner.strip_prefix(b"SSH-") {
+        let line = rest.split(|&b| b == b'\r' || b == b'\n').next()?;
+        let line = String::from_utf8_lossy(line);
+        // "2.0-OpenSSH_9.6p1 Ubuntu-3" → "OpenSSH_9.6p1"
+        let software = line.split_once('-').map(|(_, s)| s).unwrap_or_default();
+        let software = software.split_whitespace().next().map(str::to_string);
+        return found(Service::Ssh, software);
+    }
+    // Пакет MySQL: длина (3 байта LE), номер 0, затем версия протокола 10
+    // и версия сервера до нуля — или сразу ошибка 0xff (хост не пущен)
+    let [a, b, c, 0, kind, payload @ ..] = banner else {
+        return None;
+    };
+    let len = u32::from_le_bytes([*a, *b, *c, 0]) as usize;
+    if len == 0 || len > payload.len() + 1 {
+        return None;
+    }
+    match kind {
+        0x0a => {
+            let end = payload.iter().position(|&b| b == 0)?;
+            let version = String::from_utf8_lossy(&payload[..end]).into_owned();
+            found(Service::Mysql, Some(version).filter(|v| !v.is_empty()))
+        }
+        0xff => found(Service::Mysql, None),
+        _ => None,
+    }
+}
+
+/// Ответ на HTTP-запрос: имя сервера и фреймворка из заголовков
+fn from_http(reply: &[u8]) -> Option<Fingerprint> {
+    let text = String::from_utf8_lossy(reply);
+    let mut lines = text.lines();
+    if !lines.next()?.starts_with("HTTP/") {
+        return None;
+    }
+    let mut detail = Vec::new();
+    for line in lines.take_while(|l| !l.is_empty()) {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        if name.eq_ignore_ascii_case("server") || name.eq_ignore_ascii_case("x-powered-by") {
+            detail.push(value.trim().to_string());
+        }
+    }
+    found(
+        Service::Http,
+        Some(detail.join(" · ")).filter(|d| !d.is_empty()),
+    )
+}
+
+fn from_tls(reply: &[u8]) -> Option<Fingerprint> {
+    is_tls(reply).then_some(Fingerprint {
+        service: Service::Tls,
+        detail: None,
+    })
+}
+
+/// Кадр HTTP/2 на потоке 0: SETTINGS в ответ на preface или GOAWAY на HTTP/1
+fn from_h2(reply: &[u8]) -> Option<Fingerprint> {
+    let [_, _, _, kind, _, s0, s1, s2, s3, ..] = reply else {
+        return None;
+    };
+    let on_stream_zero = [*s0, *s1, *s2, *s3] == [0; 4];
+    (on_stream_zero && matches!(kind, 0x04 | 0x07)).then_some(Fingerprint {
+        service: Service::Http2,
+        detail: None,
+    })
+}
+
+/// SSLRequest PostgreSQL: длина 8 и код 80877103
+const PG_SSL_REQUEST: &[u8] = &[0, 0, 0, 8, 0x04, 0xd2, 0x16, 0x2f];
+
+fn from_postgres(reply: &[u8]) -> Option<Fingerprint> {
+    // Ровно один байт: 'S' — TLS поддерживается, 'N' — нет
+    matches!(reply, b"S" | b"N").then_some(Fingerprint {
+        service: Service::Postgres,
+        detail: None,
+    })
+}
+
+fn from_redis(reply: &[u8]) -> Option<Fingerprint> {
+    let known = [&b"+PONG"[..], b"-NOAUTH", b"-DENIED"];
+    known
+        .iter()
+        .any(|prefix| reply.starts_with(prefix))
+        .then_some(Fingerprint {
+            service: Service::Redis,
+            detail: None,
+        })
+}
+
+/// Номер нашего запроса MongoDB — ответ должен ссылаться на него
+const MONGO_REQUEST_ID: i32 = 0x4c44;
+const OP_MSG: i32 = 2013;
+const OP_REPLY: i32 = 1;
+
+/// OP_MSG `{hello: 1, $db: "admin"}`
+fn mongo_hello() -> Vec<u8> {
+    let mut doc = Vec::new();
+    doc.push(0x10);
+    doc.extend_from_slice(b"hello\0");
+    doc.extend_from_slice(&1i32.to_le_bytes());
+    doc.push(0x02);
+    doc.extend_from_slice(b"$db\0");
+    doc.extend_from_slice(&6i32.to_le_bytes());
+    doc.extend_from_slice(b"admin\0");
+    doc.push(0x00);
+    let doc_len = (doc.len() + 4) as i32;
+
+    // заголовок (16) + flagBits (4) + вид секции (1) + документ
+    let total = 16 + 4 + 1 + doc_len;
+    let mut msg = Vec::new();
+    msg.extend_from_slice(&total.to_le_bytes());
+    msg.extend_from_slice(&MONGO_REQUEST_ID.to_le_bytes());
+    msg.extend_from_slice(&0i32.to_le_bytes());
+    msg.extend_from_slice(&OP_MSG.to_le_bytes());
+    msg.extend_from_slice(&0u32.to_le_bytes());
+    msg.push(0x00);
+    msg.extend_from_slice(&doc_len.to_le_bytes());
+    msg.extend_from_slice(&doc);
+    msg
+}
+
+fn from_mongo(reply: &[u8]) -> Option<Fingerprint> {
+    let header = reply.get(..16)?;
+    let field = |i: usize| i32::from_le_bytes(header[i..i + 4].try_into().unwrap());
+    let response_to = field(8);
+    let op_code = field(12);
+    (response_to == MONGO_REQUEST_ID && matches!(op_code, OP_MSG | OP_REPLY)).then_some(
+        Fingerprint {
+            service: Service::Mongodb,
+            detail: None,
+        },
+    )
+}
+
+/// Заголовок AMQP 0-9-1
+const AMQP_HEADER: &[u8] = b"AMQP\x00\x00\x09\x01";
+
+fn from_amqp(reply: &[u8]) -> Option<Fingerprint> {
+    // Другая версия — сервер отвечает своим заголовком и закрывает
+    if reply.starts_with(b"AMQP") {
+        return found(Service::Amqp, None);
+    }
+    // Кадр метода на канале 0: Connection.Start (класс 10, метод 10)
+    if reply.len() < 11 || reply[..3] != [1, 0, 0] || reply[7..11] != [0, 10, 0, 10] {
+        return None;
+    }
+    let product = ["RabbitMQ", "ActiveMQ", "Qpid", "LavinMQ"]
+        .into_iter()
+        .find(|name| reply.windows(name.len()).any(|w| w == name.as_bytes()));
+    found(Service::Amqp, product.map(str::to_string))
+}
+
+/// Preface HTTP/2 (prior knowledge) + пустой SETTINGS
+fn h2_preface() -> Vec<u8> {
+    let mut preface = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n".to_vec();
+    preface.extend_from_slice(&[0, 0, 0, 0x04, 0, 0, 0, 0, 0]);
+    preface
+}
+
+/// Ответ прочитан: HTTP — до конца заголовков, остальное — первый кусок
+fn complete(reply: &[u8]) -> bool {
+    if reply.starts_with(b"HTTP/") {
+        return reply.windows(4).any(|w| w == b"\r\n\r\n");
+    }
+    !reply.is_empty()
+}
+
+/// Читать, пока ответ не полон, соединение не закрыто или не вышло время
+async fn read_reply(stream: &mut TcpStream, wait: Duration) -> Vec<u8> {
+    let deadline = Instant::now() + wait;
+    let mut reply = Vec::new();
+    let mut buf = [0u8; 1024];
+    while reply.len() < MAX_REPLY_BYTES && !complete(&reply) {
+        let left = deadline.saturating_duration_since(Instant::now());
+        match timeout(left, stream.read(&mut buf)).await {
+            Ok(Ok(n)) if n > 0 => reply.extend_from_slice(&buf[..n]),
+            _ => break,
+        }
+    }
+    reply
+}
+
+/// Новое соединение, запрос, ответ; пусто — ошибка или молчание
+async fn exchange(addr: SocketAddr, request: &[u8], limit: Duration) -> Vec<u8> {
+    let Ok(Ok(mut stream)) = timeout(limit, TcpStream::connect(addr)).await else {
+        return Vec::new();
+    };
+    if stream.write_all(request).await.is_err() {
+        return Vec::new();
+    }
+    read_reply(&mut stream, limit).await
+}
+
+type Parser = fn(&[u8]) -> Option<Fingerprint>;
+
+/// Определить протокол порта. None — не открыт или ни на что не похож.
+pub async fn sniff(addr: SocketAddr, limit: Duration) -> Option<Fingerprint> {
+    let Ok(Ok(mut stream)) = timeout(limit, TcpStream::connect(addr)).await else {
+        return None;
+    };
+    let banner = read_reply(&mut stream, BANNER_WAIT).await;
+    if !banner.is_empty() {
+        // Незнакомый баннер — чужие запросы такому серверу ни к чему
+        return from_banner(&banner);
+    }
+    // Молчит — чаще всего это HTTP; спрашиваем в том же соединении
+    if stream
+        .write_all(&http_request("HEAD", "/", addr))
+        .await
+        .is_ok()
+    {
+        let reply = read_reply(&mut stream, limit).await;
+        let parsers: [Parser; 3] = [from_http, from_tls, from_h2];
+        if let Some(fingerprint) = parsers.iter().find_map(|parse| parse(&reply)) {
+            return Some(fingerprint);
+        }
+    }
+    drop(stream);
+
+    let probes: [(Vec<u8>, Parser); 6] = [
+        (client_hello(), from_tls),
+        (PG_SSL_REQUEST.to_vec(), from_postgres),
+        (b"PING\r\n".to_vec(), from_redis),
+        (mongo_hello(), from_mongo),
+        (AMQP_HEADER.to_vec(), from_amqp),
+        (h2_preface(), from_h2),
+    ];
+    for (request, parse) in probes {
+        if let Some(fingerprint) = parse(&exchange(addr, &request, limit).await) {
+            return Some(fingerprint);
+        }
+    }
+    None
+}
+
+/// Все порты параллельно
+pub async fn sniff_all(
+    targets: Vec<((u32, u16), SocketAddr)>,
+    limit: Duration,
+) -> Vec<((u32, u16), Option<Fingerprint>)> {
+    let mut tasks = JoinSet::new();
+    for (key, addr) in targets {
+        tasks.spawn(async move { (key, sniff(addr, limit).await) });
+    }
+    let mut results = Vec::new();
+    while let Some(result) = tasks.join_next().await {
+        if let Ok(result) = result {
+            results.push(result);
+        }
+    }
+    results
+}
+
+/// Отпечатки по (PID, порт) в managed state. Протокол процесса не меняется —
+/// каждый порт проверяется один раз, пока процесс его слушает.
+#[derive(Default)]
+pub struct Fingerprinter {
+    /// None — проверка идёт или ничего не нашла
+    known: Mutex<HashMap<(u32, u16), Option<Fingerprint>>>,
+}
+
+impl Fingerprinter {
+    /// Проставить `Server.fingerprint`; забыть исчезнувшие порты
+    pub fn annotate(&self, servers: &mut [Server]) {
+        let Ok(mut known) = self.known.lock() else {
+            return;
+        };
+        known.retain(|key, _| servers.iter().any(|s| (s.pid, s.port) == *key));
+        for server in servers.iter_mut() {
+            server.fingerprint = known.get(&(server.pid, server.port)).cloned().flatten();
+        }
+    }
+
+    /// TCP-порты неизвестных процессов (нет в каталоге — пустое описание),
+    /// которые ещё не проверяли; помечаются как проверяемые. Известные базы,
+    /// браузеры и системные службы рукопожатиями не беспокоим.
+    pub fn claim(&self, servers: &[Server]) -> Vec<((u32, u16), SocketAddr)> {
+        let Ok(mut known) = self.known.lock() else {
+            return Vec::new();
+        };
+        servers
+            .iter()
+            .filter(|s| s.protocol == Protocol::Tcp && s.description.is_empty())
+            .filter_map(|s| {
+                let key = (s.pid, s.port);
+                if known.contains_key(&key) {
+                    return None;
+                }
+                let addr = probe_addr(s)?;
+                known.insert(key, None);
+                Some((key, addr))
+            })
+            .collect()
+    }
+
+    /// Записать результат, если порт за время проверки не пропал
+    pub fn record(&self, results: Vec<((u32, u16), Option<Fingerprint>)>) {
+        if let Ok(mut known) = self.known.lock() {
+            for (key, fingerprint) in results {
+                if let Some(entry) = known.get_mut(&key) {
+                    *entry = fingerprint;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::BindAddress;
+    use std::io::{Read, Write};
+    use std::net::{IpAddr, TcpListener};
+    use std::thread;
+
+    #[test]
+    fn test_banners() {
+        let ssh = from_banner(b"SSH-2.0-OpenSSH_9.6p1 Ubuntu-3ubuntu13\r\n").unwrap();
+        assert_eq!(ssh.service, Service::Ssh);
+        assert_eq!(ssh.detail.as_deref(), Some("OpenSSH_9.6p1"));
+
+        let mut mysql = vec![0x4a, 0, 0, 0, 0x0a];
+        mysql.extend_from_slice(b"8.0.36\0");
+        mysql.extend_from_slice(&[0x08; 66]);
+        let mysql = from_banner(&mysql).unwrap();
+        assert_eq!(mysql.service, Service::Mysql);
+        assert_eq!(mysql.detail.as_deref(), Some("8.0.36"));
+
+        // ERR 1130: хост не пущен — всё равно MySQL
+        let denied = b"\x2e\x00\x00\x00\xff\x6a\x04Host '172.17.0.1' is not allowed to connect";
+        assert_eq!(from_banner(denied).unwrap().service, Service::Mysql);
+
+        assert_eq!(from_banner(b"220 smtp.local ESMTP\r\n"), None);
+    }
+
+    #[test]
+    fn test_http_headers() {
+        let reply = b"HTTP/1.1 200 OK\r\nserver: nginx/1.25.3\r\nX-Powered-By: Express\r\n\r\n";
+        let http = from_http(reply).unwrap();
+        assert_eq!(http.service, Service::Http);
+        assert_eq!(http.detail.as_deref(), Some("nginx/1.25.3 · Express"));
+        assert_eq!(
+            from_http(b"HTTP/1.0 404 Not Found\r\n\r\n").unwrap().detail,
+            None
+        );
+        assert_eq!(from_http(b"-ERR unknown command\r\n"), None);
+    }
+
+    #[test]
+    fn test_handshake_replies() {
+        assert_eq!(from_postgres(b"N").unwrap().service, Service::Postgres);
+        assert_eq!(from_postgres(b"NO"), None);
+        assert_eq!(from_redis(b"+PONG\r\n").unwrap().service, Service::Redis);
+        assert!(from_redis(b"-NOAUTH Authentication required.\r\n").is_some());
+        assert_eq!(from_redis(b"ERROR\r\n"), None);
+
+        // SETTINGS в ответ на preface, GOAWAY на HTTP/1 — HTTP/2
+        assert!(from_h2(&[0, 0, 6, 0x04, 0, 0, 0, 0, 0, 0, 3, 0, 0, 0, 100]).is_some());
+        assert!(from_h2(&[0, 0, 8, 0x07, 0, 0, 0, 0, 0]).is_some());
+        assert_eq!(from_h2(b"HTTP/1.1 400 Bad"), None);
+
+        let mut rabbit = vec![1, 0, 0, 0, 0, 1, 0xf4, 0, 10, 0, 10, 0, 9];
+        rabbit.extend_from_slice(b"\x07productS\x00\x00\x00\x08RabbitMQ");
+        let amqp = from_amqp(&rabbit).unwrap();
+        assert_eq!(amqp.detail.as_deref(), Some("RabbitMQ"));
+        assert!(from_amqp(b"AMQP\x00\x00\x09\x01").is_some());
+
+        let mut mongo = vec![0u8; 16];
+        mongo[8..12].copy_from_slice(&MONGO_REQUEST_ID.to_le_bytes());
+        mongo[12..16].copy_from_slice(&OP_MSG.to_le_bytes());
+        assert_eq!(from_mongo(&mongo).unwrap().service, Service::Mongodb);
+        mongo[8] = 0;
+        assert_eq!(from_mongo(&mongo), None);
+    }
+
+    #[test]
+    fn test_mongo_hello_lengths() {
+        let hello = mongo_hello();
+        let total = i32::from_le_bytes(hello[..4].try_into().unwrap()) as usize;
+        assert_eq!(total, hello.len());
+        let doc = i32::from_le_bytes(hello[21..25].try_into().unwrap()) as usize;
+        assert_eq!(doc, hello.len() - 21);
+        assert_eq!(hello.last(), Some(&0));
+    }
+
+    /// Сервер, который молчит до запроса и отвечает `reply` на запрос `expect`
+    fn serve(expect: &'static [u8], reply: &'static [u8]) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            for conn in listener.incoming() {
+                let Ok(mut conn) = conn else {
+                    return;
+                };
+                let mut buf = [0u8; 2048];
+                let n = conn.read(&mut buf).unwrap_or(0);
+                if buf[..n].starts_with(expect) {
+                    let _ = conn.write_all(reply);
+                }
+            }
+        });
+        addr
+    }
+
+    fn run(addr: SocketAddr) -> Option<Fingerprint> {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(sniff(addr, Duration::from_millis(500)))
+    }
+
+    #[test]
+    fn test_sniff_tries_protocols_in_turn() {
+        // На HEAD молчит, на PING отвечает — до Redis доходим через TLS и PostgreSQL
+        let redis = run(serve(b"PING", b"+PONG\r\n")).unwrap();
+        assert_eq!(redis.service, Service::Redis);
+
+        let http = run(serve(
+            b"HEAD / ",
+            b"HTTP/1.1 204 No Content\r\nServer: uvicorn\r\n\r\n",
+        ));
+        assert_eq!(http.unwrap().detail.as_deref(), Some("uvicorn"));
+
+        let closed = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        assert_eq!(run(closed), None);
+    }
+
+    fn server(pid: u32, port: u16) -> Server {
+        Server {
+            process_name: "mystery".to_string(),
+            category: "system".to_string(),
+            addresses: vec![BindAddress::from(IpAddr::from([0, 0, 0, 0]))],
+            ..Server::sample(pid, port)
+        }
+    }
+
+    #[test]
+    fn test_fingerprinter_claims_once() {
+        let fingerprinter = Fingerprinter::default();
+        let mut servers = vec![server(10, 5432)];
+        let claimed = fingerprinter.claim(&servers);
+        assert_eq!(claimed.len(), 1);
+        assert!(fingerprinter.claim(&servers).is_empty());
+
+        let postgres = Fingerprint {
+            service: Service::Postgres,
+            detail: None,
+        };
+        fingerprinter.record(vec![((10, 5432), Some(postgres.clone()))]);
+        fingerprinter.annotate(&mut servers);
+        assert_eq!(servers[0].fingerprint, Some(postgres));
+
+        // Порт пропал — забыли; поздний результат не воскрешает запись
+        fingerprinter.annotate(&mut []);
+        fingerprinter.record(vec![((10, 5432), None)]);
+        assert_eq!(fingerprinter.claim(&servers).len(), 1);
+    }
+
+    #[test]
+    fn test_fingerprinter_skips_catalog_processes() {
+        let fingerprinter = Fingerprinter::default();
+        let postgres = Server {
+            process_name: "postgres".to_string(),
+            description: "PostgreSQL".to_string(),
+            ..server(20, 5432)
+        };
+        let servers = vec![postgres, server(21, 6379)];
+        let claimed = fingerprinter.claim(&servers);
+        assert_eq!(claimed.len(), 1);
+        assert_eq!(claimed[0].0, (21, 6379));
+    }
+}
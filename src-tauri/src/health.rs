@@ -0,0 +1,535 @@
+//! HTTP-проверка здоровья найденных серверов. Порт в LISTEN ещё не значит,
+//! что сервер отвечает: Next.js может висеть на компиляции, API — отдавать 500.
+//! Асинхронный проб (tokio) шлёт `HEAD` (при 405/501 — `GET`) на путь проекта
+//! или `/`, записывает код, задержку и TLS/plain; результат — `Server.health`
+//! со скользящей историей задержек.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::task::JoinSet;
+use tokio::time::timeout;
+
+use crate::scanner::{BindScope, Protocol};
+use crate::Server;
+
+/// Сколько ждать соединения и первой строки ответа
+pub const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+/// Ответ медленнее — `Degraded`
+const SLOW_MS: u64 = 1000;
+/// Сколько последних задержек хранить
+const HISTORY_LEN: usize = 20;
+/// Первой строки ответа хватает — дальше не читаем
+const MAX_HEAD_BYTES: usize = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    /// Ответ < 500 за `SLOW_MS`
+    Up,
+    /// 5xx, медленный ответ или соединение принято, но ответа нет
+    Degraded,
+    /// Соединиться не удалось
+    Down,
+    /// Порт говорит не по HTTP (и не TLS) — больше не проверяем
+    NotHttp,
+}
+
+/// Результат одной проверки
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Probe {
+    pub status: HealthStatus,
+    /// HTTP-код; None для TLS (ответ зашифрован) и ошибок
+    pub code: Option<u16>,
+    pub tls: bool,
+    pub latency_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Health {
+    pub status: HealthStatus,
+    pub code: Option<u16>,
+    pub tls: bool,
+    pub latency_ms: Option<u64>,
+    /// Задержки последних успешных проверок, старые первыми
+    pub history: Vec<u64>,
+}
+
+impl Health {
+    /// Без задержек — они меняются каждую проверку; для diff наблюдателя
+    pub fn state_only(&self) -> Health {
+        Health {
+            latency_ms: None,
+            history: Vec::new(),
+            ..self.clone()
+        }
+    }
+}
+
+/// Куда стучаться: loopback своего семейства для any/loopback, иначе сам адрес
+pub fn probe_addr(server: &Server) -> Option<SocketAddr> {
+    let ip = server
+        .addresses
+        .iter()
+        .filter_map(|a| {
+            let ip: IpAddr = a.ip.parse().ok()?;
+            Some(match (a.scope, ip) {
+                (BindScope::Specific, ip) => ip,
+                (_, IpAddr::V4(_)) => IpAddr::V4(Ipv4Addr::LOCALHOST),
+                (_, IpAddr::V6(_)) => IpAddr::V6(Ipv6Addr::LOCALHOST),
+            })
+        })
+        .min()?;
+    Some(SocketAddr::new(ip, server.port))
+}
+
+/// Начало ответа — TLS-запись (handshake или alert)
+pub fn is_tls(reply: &[u8]) -> bool {
+    matches!(reply, [0x15 | 0x16, 0x03, ..])
+}
+
+/// Код из строки статуса "HTTP/1.1 200 OK"
+fn parse_status(reply: &[u8]) -> Option<u16> {
+    let line = reply.split(|&b| b == b'\n').next()?;
+    let line = std::str::from_utf8(line).ok()?;
+    let mut parts = line.split_whitespace();
+    if !parts.next()?.starts_with("HTTP/") {
+        return None;
+    }
+    parts.next()?.parse().ok()
+}
+
+enum Exchange {
+    Refused,
+    /// Соединение приняли и закрыли, ничего не ответив
+    Closed,
+    /// Соединение есть, ответа нет за таймаут
+    Timeout,
+    Reply(Vec<u8>, Duration),
+}
+
+/// Отправить `request` и прочитать начало ответа
+async fn exchange(addr: SocketAddr, request: &[u8], limit: Duration) -> Exchange {
+    let started = Instant::now();
+    let mut stream = match timeout(limit, TcpStream::connect(addr)).await {
+        Ok(Ok(stream)) => stream,
+        _ => return Exchange::Refused,
+    };
+    let read = async {
+        stream.write_all(request).await?;
+        let mut reply = Vec::new();
+        let mut buf = [0u8; 512];
+        while reply.len() < MAX_HEAD_BYTES && !reply.contains(&b'\n') {
+            let n = stream.read(&mut buf).await?;
+            if n == 0 {
+                break;
+            }
+            reply.extend_from_slice(&buf[..n]);
+        }
+        Ok::<_, std::io::Error>(reply)
+    };
+    match timeout(limit.saturating_sub(started.elapsed()), read).await {
+        Ok(Ok(reply)) if !reply.is_empty() => Exchange::Reply(reply, started.elapsed()),
+        Ok(_) => Exchange::Closed,
+        Err(_) => Exchange::Timeout,
+    }
+}
+
+pub fn http_request(method: &str, path: &str, addr: SocketAddr) -> Vec<u8> {
+    format!(
+        "{} {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: LocalDock\r\nAccept: */*\r\nConnection: close\r\n\r\n",
+        method, path, addr
+    )
+    .into_bytes()
+}
+
+/// Минимальный TLS 1.2 ClientHello с SNI "localhost": в ответ сервер пришлёт
+/// ServerHello или alert — и то и другое TLS-запись
+pub fn client_hello() -> Vec<u8> {
+    let host = b"localhost";
+    let mut ext = Vec::new();
+    // server_name
+    ext.extend_from_slice(&[0x00, 0x00]);
+    ext.extend_from_slice(&(host.len() as u16 + 5).to_be_bytes());
+    ext.extend_from_slice(&(host.len() as u16 + 3).to_be_bytes());
+    ext.push(0x00);
+    ext.extend_from_slice(&(host.len() as u16).to_be_bytes());
+    ext.extend_from_slice(host);
+    // supported_groups: x25519, secp256r1, secp384r1
+    ext.extend_from_slice(&[
+        0x00, 0x0a, 0x00, 0x08, 0x00, 0x06, 0x00, 0x1d, 0x00, 0x17, 0x00, 0x18,
+    ]);
+    // ec_point_formats: uncompressed
+    ext.extend_from_slice(&[0x00, 0x0b, 0x00, 0x02, 0x01, 0x00]);
+    // signature_algorithms
+    ext.extend_from_slice(&[
+        0x00, 0x0d, 0x00, 0x0e, 0x00, 0x0c, 0x04, 0x03, 0x08, 0x04, 0x04, 0x01, 0x05, 0x03, 0x08,
+        0x05, 0x05, 0x01,
+    ]);
+
+    let suites: &[u8] = &[
+        0xc0, 0x2f, 0xc0, 0x2b, 0xc0, 0x30, 0xc0, 0x2c, 0xcc, 0xa8, 0xcc, 0xa9, 0x00, 0x9c, 0x00,
+        0x2f,
+    ];
+    let mut body = vec![0x03, 0x03];
+    body.extend_from_slice(&[0u8; 32]);
+    body.push(0x00);
+    body.extend_from_slice(&(suites.len() as u16).to_be_bytes());
+    body.extend_from_slice(suites);
+    body.extend_from_slice(&[0x01, 0x00]);
+    body.extend_from_slice(&(ext.len() as u16).to_be_bytes());
+    body.extend_from_slice(&ext);
+
+    let mut handshake = vec![0x01];
+    handshake.extend_from_slice(&(body.len() as u32).to_be_bytes()[1..]);
+    handshake.extend_from_slice(&body);
+
+    let mut record = vec![0x16, 0x03, 0x01];
+    record.extend_from_slice(&(handshake.len() as u16).to_be_bytes());
+    record.extend_from_slice(&handshake);
+    record
+}
+
+fn latency_status(code: u16, took: Duration) -> HealthStatus {
+    if code >= 500 || took > Duration::from_millis(SLOW_MS) {
+        HealthStatus::Degraded
+    } else {
+        HealthStatus::Up
+    }
+}
+
+fn probe_result(
+    status: HealthStatus,
+    code: Option<u16>,
+    tls: bool,
+    took: Option<Duration>,
+) -> Probe {
+    Probe {
+        status,
+        code,
+        tls,
+        latency_ms: took.map(|d| d.as_millis() as u64),
+    }
+}
+
+/// Проверить один адрес: HTTP, при 405/501 на HEAD — GET, иначе — не TLS ли это
+pub async fn probe(addr: SocketAddr, path: &str, limit: Duration) -> Probe {
+    let mut reply = exchange(addr, &http_request("HEAD", path, addr), limit).await;
+    if let Exchange::Reply(head, _) = &reply {
+        if matches!(parse_status(head), Some(405 | 501)) {
+            reply = exchange(addr, &http_request("GET", path, addr), limit).await;
+        }
+    }
+    let reply = match reply {
+        Exchange::Refused => return probe_result(HealthStatus::Down, None, false, None),
+        Exchange::Timeout => return probe_result(HealthStatus::Degraded, None, false, None),
+        Exchange::Reply(reply, took) => match parse_status(&reply) {
+            Some(code) => {
+                let status = latency_status(code, took);
+                return probe_result(status, Some(code), false, Some(took));
+            }
+            None if is_tls(&reply) => Exchange::Reply(reply, took),
+            None => Exchange::Closed,
+        },
+        Exchange::Closed => Exchange::Closed,
+    };
+    // HTTPS-сервер мог молча закрыть соединение или ответить не-HTTP — спросим по-TLS
+    let reply = match reply {
+        Exchange::Closed => exchange(addr, &client_hello(), limit).await,
+        tls => tls,
+    };
+    match reply {
+        Exchange::Reply(reply, took) if is_tls(&reply) => {
+            probe_result(latency_status(0, took), None, true, Some(took))
+        }
+        _ => probe_result(HealthStatus::NotHttp, None, false, None),
+    }
+}
+
+/// Что проверить: (PID, порт) → адрес и путь
+pub type Target = ((u32, u16), SocketAddr, String);
+
+/// Все проверки параллельно
+pub async fn probe_all(targets: Vec<Target>, limit: Duration) -> Vec<((u32, u16), Probe)> {
+    let mut tasks = JoinSet::new();
+    for (key, addr, path) in targets {
+        tasks.spawn(async move { (key, probe(addr, &path, limit).await) });
+    }
+    let mut results = Vec::new();
+    while let Some(result) = tasks.join_next().await {
+        if let Ok(result) = result {
+            results.push(result);
+        }
+    }
+    results
+}
+
+/// Событие `server-health`: новое здоровье одного сервера
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthUpdate {
+    pub pid: u32,
+    pub port: u16,
+    pub health: Health,
+}
+
+/// Последние результаты по (PID, порт) в managed state
+#[derive(Default)]
+pub struct HealthMonitor {
+    health: Mutex<HashMap<(u32, u16), Health>>,
+    /// Раунд проверок ещё идёт — следующий скан новый не запускает
+    busy: AtomicBool,
+}
+
+impl HealthMonitor {
+    /// Проставить `Server.health` из последних результатов; забыть исчезнувшие
+    pub fn annotate(&self, servers: &mut [Server]) {
+        let Ok(mut health) = self.health.lock() else {
+            return;
+        };
+        health.retain(|key, _| servers.iter().any(|s| (s.pid, s.port) == *key));
+        for server in servers.iter_mut().filter(|s| s.protocol == Protocol::Tcp) {
+            server.health = health.get(&(server.pid, server.port)).cloned();
+        }
+    }
+
+    /// TCP dev-серверы и серверы проектов для нового раунда; None — прошлый ещё
+    /// не закончился. Базы, браузеры и системные службы не трогаем: HTTP-запрос
+    /// и ClientHello к ним — мусор в их логах (Redis пишет «SECURITY ATTACK»).
+    /// `path` — путь проверки сервера (из его проекта).
+    pub fn begin(
+        &self,
+        servers: &[Server],
+        path: impl Fn(&Server) -> String,
+    ) -> Option<Vec<Target>> {
+        let health = self.health.lock().ok()?;
+        if self.busy.swap(true, Ordering::SeqCst) {
+            return None;
+        }
+        let targets = servers
+            .iter()
+            .filter(|s| s.protocol == Protocol::Tcp)
+            .filter(|s| s.category == "dev" || s.project_id.is_some())
+            .filter(|s| {
+                health
+                    .get(&(s.pid, s.port))
+                    .is_none_or(|h| h.status != HealthStatus::NotHttp)
+            })
+            .filter_map(|s| Some(((s.pid, s.port), probe_addr(s)?, path(s))))
+            .collect();
+        Some(targets)
+    }
+
+    /// Держать на время раунда: при выходе (в том числе по панике проверки)
+    /// снимает `busy`, иначе проверки больше не запустятся
+    pub fn round(&self) -> Round<'_> {
+        Round(self)
+    }
+
+    /// Записать результаты раунда, вернуть обновлённое здоровье
+    pub fn finish(&self, results: Vec<((u32, u16), Probe)>) -> Vec<HealthUpdate> {
+        let mut updated = Vec::new();
+        if let Ok(mut health) = self.health.lock() {
+            for (key, probe) in results {
+                let entry = health.entry(key).or_insert_with(|| Health {
+                    status: probe.status,
+                    code: None,
+                    tls: false,
+                    latency_ms: None,
+                    history: Vec::new(),
+                });
+                entry.status = probe.status;
+                entry.code = probe.code;
+                entry.tls = probe.tls;
+                entry.latency_ms = probe.latency_ms;
+                if let Some(latency) = probe.latency_ms {
+                    if entry.history.len() == HISTORY_LEN {
+                        entry.history.remove(0);
+                    }
+                    entry.history.push(latency);
+                }
+                updated.push(HealthUpdate {
+                    pid: key.0,
+                    port: key.1,
+                    health: entry.clone(),
+                });
+            }
+        }
+        self.busy.store(false, Ordering::SeqCst);
+        updated
+    }
+}
+
+/// Идущий раунд проверок, см. [`HealthMonitor::round`]
+pub struct Round<'a>(&'a HealthMonitor);
+
+impl Drop for Round<'_> {
+    fn drop(&mut self) {
+        self.0.busy.store(false, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::BindAddress;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Локальный сервер: на каждое соединение — ответ из `replies` по очереди
+    fn serve(replies: Vec<&'static [u8]>) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            for reply in replies {
+                let Ok((mut conn, _)) = listener.accept() else {
+                    return;
+                };
+                let mut buf = [0u8; 2048];
+                let _ = conn.read(&mut buf);
+                let _ = conn.write_all(reply);
+            }
+        });
+        addr
+    }
+
+    fn run(addr: SocketAddr) -> Probe {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(probe(addr, "/", Duration::from_millis(500)))
+    }
+
+    #[test]
+    fn test_http_status() {
+        let up = run(serve(vec![b"HTTP/1.1 200 OK\r\n\r\n"]));
+        assert_eq!(up.status, HealthStatus::Up);
+        assert_eq!(up.code, Some(200));
+        assert!(!up.tls && up.latency_ms.is_some());
+
+        let failing = run(serve(vec![b"HTTP/1.1 503 Service Unavailable\r\n\r\n"]));
+        assert_eq!(failing.status, HealthStatus::Degraded);
+        assert_eq!(failing.code, Some(503));
+
+        // HEAD не поддерживается — повтор через GET
+        let get = run(serve(vec![
+            b"HTTP/1.1 405 Method Not Allowed\r\n\r\n",
+            b"HTTP/1.0 204 No Content\r\n",
+        ]));
+        assert_eq!(get.code, Some(204));
+        assert_eq!(get.status, HealthStatus::Up);
+    }
+
+    #[test]
+    fn test_tls_and_other_protocols() {
+        // На HEAD — alert, сразу ясно, что TLS
+        let tls = run(serve(vec![b"\x15\x03\x01\x00\x02\x02\x46"]));
+        assert!(tls.tls);
+        assert_eq!((tls.status, tls.code), (HealthStatus::Up, None));
+
+        // На HEAD молча закрыл, на ClientHello — ServerHello
+        let tls = run(serve(vec![b"", b"\x16\x03\x03\x00\x4a\x02"]));
+        assert!(tls.tls);
+
+        let redis = run(serve(vec![b"-ERR unknown command 'HEAD'\r\n", b"-ERR\r\n"]));
+        assert_eq!(redis.status, HealthStatus::NotHttp);
+    }
+
+    #[test]
+    fn test_down_and_hanging() {
+        // Порт свободен — соединения нет
+        let addr = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        assert_eq!(run(addr).status, HealthStatus::Down);
+
+        // Соединение принято, ответа нет (Next.js компилирует)
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let hanging = run(listener.local_addr().unwrap());
+        assert_eq!(hanging.status, HealthStatus::Degraded);
+        assert_eq!(hanging.code, None);
+    }
+
+    #[test]
+    fn test_client_hello_lengths() {
+        let hello = client_hello();
+        assert_eq!(&hello[..3], &[0x16, 0x03, 0x01]);
+        let record_len = u16::from_be_bytes([hello[3], hello[4]]) as usize;
+        assert_eq!(record_len, hello.len() - 5);
+        assert_eq!(hello[5], 0x01);
+        let body_len = u32::from_be_bytes([0, hello[6], hello[7], hello[8]]) as usize;
+        assert_eq!(body_len, hello.len() - 9);
+    }
+
+    #[test]
+    fn test_monitor_probes_only_dev_and_project_servers() {
+        let monitor = HealthMonitor::default();
+        let local = vec![BindAddress::from(IpAddr::from([127, 0, 0, 1]))];
+        let servers = vec![
+            Server {
+                addresses: local.clone(),
+                ..Server::sample(1, 5173)
+            },
+            Server {
+                process_name: "redis-server".to_string(),
+                category: "system".to_string(),
+                addresses: local.clone(),
+                ..Server::sample(2, 6379)
+            },
+            Server {
+                process_name: "caddy".to_string(),
+                category: "system".to_string(),
+                addresses: local.clone(),
+                project_id: Some("proxy".to_string()),
+                ..Server::sample(3, 8080)
+            },
+        ];
+        let targets = monitor.begin(&servers, |_| "/".to_string()).unwrap();
+        let keys: Vec<(u32, u16)> = targets.iter().map(|t| t.0).collect();
+        assert_eq!(keys, vec![(1, 5173), (3, 8080)]);
+    }
+
+    #[test]
+    fn test_monitor_history() {
+        let monitor = HealthMonitor::default();
+        let up = |ms| Probe {
+            status: HealthStatus::Up,
+            code: Some(200),
+            tls: false,
+            latency_ms: Some(ms),
+        };
+        for ms in 0..25 {
+            assert!(monitor.begin(&[], |_| "/".to_string()).is_some());
+            // Раунд не закончен — второй не начинается
+            assert!(monitor.begin(&[], |_| "/".to_string()).is_none());
+            monitor.finish(vec![((1, 3000), up(ms))]);
+        }
+        let updated = monitor.finish(vec![(
+            (2, 5432),
+            Probe {
+                status: HealthStatus::NotHttp,
+                code: None,
+                tls: false,
+                latency_ms: None,
+            },
+        )]);
+        assert_eq!(updated[0].health.status, HealthStatus::NotHttp);
+
+        let health = monitor.health.lock().unwrap()[&(1, 3000)].clone();
+        assert_eq!(health.history, (5..25).collect::<Vec<u64>>());
+
+        // Раунд оборвался без finish — следующий всё равно начинается
+        assert!(monitor.begin(&[], |_| "/".to_string()).is_some());
+        drop(monitor.round());
+        assert!(monitor.begin(&[], |_| "/".to_string()).is_some());
+        assert_eq!(health.latency_ms, Some(24));
+    }
+}
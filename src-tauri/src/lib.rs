@@ -1,198 +1,744 @@
+mod cache;
+mod catalog;
+mod conflicts;
 mod detector;
+mod fingerprint;
+mod health;
+mod logs;
+mod notify;
+mod probe;
 mod process;
+mod projects;
+mod restart;
 mod scanner;
+mod settings;
+mod supervise;
+mod tree;
+mod versions;
+mod watcher;
+mod workspace;
 
+use catalog::ProcessInfo;
+use conflicts::Warning;
+use detector::Framework;
+use fingerprint::{Fingerprint, Fingerprinter};
+use health::{Health, HealthMonitor};
+use logs::{LogLine, LogStore};
+use notify::Notifier;
+use probe::SystemProbe;
+use process::StopOutcome;
+use projects::{Project, ProjectState, ProjectStore, Supervisor};
+use restart::{LaunchSpec, RestartOutcome, RestartRegistry};
+use scanner::{BindAddress, Listener, Protocol};
 use serde::Serialize;
-use tauri::Manager;
+use settings::{PortFilter, Settings, SettingsStore};
+use tree::ProcessTree;
+use watcher::Watcher;
+use workspace::Membership;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct Server {
     pub pid: u32,
     pub port: u16,
+    pub protocol: Protocol,
     pub project_name: String,
-    pub framework: String,
+    /// Корень проекта (папка с манифестом) выше CWD dev-сервера; None — не нашёлся
+    pub project_root: Option<String>,
+    /// Фреймворк dev-сервера по папке проекта; None — не dev или не определён
+    pub framework: Option<Framework>,
     pub uptime_seconds: u64,
     pub process_name: String,
     pub category: String,
     pub description: String,
+    /// Иконка из каталога процессов (emoji), если задана
+    pub icon: Option<String>,
+    /// Командная строка процесса (argv); пустая, если ОС не дала её прочитать
+    pub command_line: Vec<String>,
+    /// Адреса, на которых процесс слушает этот порт (IPv4 раньше IPv6)
+    pub addresses: Vec<BindAddress>,
+    /// Конфликты портов (общий порт, fallback с занятого, чужой dev-порт)
+    pub warnings: Vec<Warning>,
+    /// Управляемый проект, из которого запущен сервер (см. `start_project`)
+    pub project_id: Option<String>,
+    /// Последняя HTTP-проверка; None — ещё не проверяли или не TCP
+    pub health: Option<Health>,
+    /// Протокол порта по баннеру или рукопожатию; None — не определён
+    pub fingerprint: Option<Fingerprint>,
+    /// Пакет монорепозитория, к которому отнесён процесс (имя и фреймворк — его)
+    pub workspace: Option<Membership>,
 }
 
+#[cfg(test)]
+impl Server {
+    /// TCP dev-сервер node без подробностей — основа для `..Server::sample(..)` в тестах
+    pub fn sample(pid: u32, port: u16) -> Server {
+        Server {
+            pid,
+            port,
+            protocol: Protocol::Tcp,
+            project_name: String::new(),
+            project_root: None,
+            framework: None,
+            uptime_seconds: 0,
+            process_name: "node".to_string(),
+            category: "dev".to_string(),
+            description: String::new(),
+            icon: None,
+            command_line: Vec::new(),
+            addresses: Vec::new(),
+            warnings: Vec::new(),
+            project_id: None,
+            health: None,
+            fingerprint: None,
+            workspace: None,
+        }
+    }
+}
+
+/// Последний снимок фонового наблюдателя; до первого скана — сканируем сами
+#[tauri::command]
+fn get_servers(app: AppHandle, watcher: State<'_, Watcher>) -> Vec<Server> {
+    match watcher.snapshot() {
+        Some(servers) => servers,
+        None => rescan(&app),
+    }
+}
+
+/// Просканировать сейчас, не дожидаясь интервала (кнопка «Оновити», после kill)
 #[tauri::command]
-fn get_servers() -> Vec<Server> {
-    // 1. Порты — один вызов netstat
-    let pid_ports = scanner::scan_listening_ports();
-    if pid_ports.is_empty() {
+fn refresh_servers(app: AppHandle) -> Vec<Server> {
+    rescan(&app)
+}
+
+/// Скан + обновление снимка + события `server-*` по отличиям
+fn rescan(app: &AppHandle) -> Vec<Server> {
+    let watcher = app.state::<Watcher>();
+    let _scan = watcher.lock_scan();
+    let settings = app.state::<SettingsStore>().get();
+    let mut servers = collect_servers(&probe::NATIVE, &settings.port_filter);
+    supervise_projects(app, &mut servers);
+    app.state::<HealthMonitor>().annotate(&mut servers);
+    app.state::<Fingerprinter>().annotate(&mut servers);
+    let diff = watcher.update(servers.clone());
+    watcher::emit(app, &diff);
+    check_health(app, &servers);
+    fingerprint_servers(app, &servers);
+
+    // Команду запуска снимаем, пока процесс жив — после остановки её уже не узнать
+    app.state::<RestartRegistry>()
+        .record(&servers, |pid| capture_launch(&probe::NATIVE, pid));
+
+    // Живы ли исчезнувшие процессы — спрашиваем ОС, только если кто-то исчез
+    let alive = if diff.removed.is_empty() {
+        HashMap::new()
+    } else {
+        probe::NATIVE.process_names()
+    };
+    app.state::<Notifier>()
+        .notify(app, &settings.notifications, &diff, &servers, |pid| {
+            alive.contains_key(&pid)
+        });
+    servers
+}
+
+/// Надзор за проектами на каждом скане: забрать завершившиеся, пометить
+/// серверы запущенных, остановить не прошедшие проверку здоровья,
+/// перезапустить те, чья задержка истекла
+fn supervise_projects(app: &AppHandle, servers: &mut [Server]) {
+    let supervisor = app.state::<Supervisor>();
+    let projects = app.state::<ProjectStore>().list();
+    let now = Instant::now();
+    let mut changed = !supervisor.reap(&projects, now).is_empty();
+
+    let running = supervisor.running();
+    if !running.is_empty() {
+        let processes = ProcessTree::new(probe::NATIVE.parent_pids());
+        projects::link(servers, &projects, &running, &processes);
+    }
+
+    for pid in supervisor.unhealthy(&projects, servers, now) {
+        // Мягкая остановка ждёт до stop_timeout_ms — не задерживаем скан;
+        // выход заберёт следующий скан и запланирует перезапуск
+        let app = app.clone();
+        std::thread::spawn(move || stop_process_tree(&app, pid));
+    }
+
+    for id in supervisor.take_due(now) {
+        if let Some(project) = projects.iter().find(|p| p.id == id) {
+            if launch_project(app, project).is_err() {
+                supervisor.spawn_failed(project, now);
+            }
+            changed = true;
+        }
+    }
+    if changed {
+        let _ = app.emit("projects-changed", ());
+    }
+}
+
+/// Раунд HTTP-проверок в фоне (не чаще одного одновременно); результаты —
+/// в `Server.health` следующего скана и сразу событием `server-health`
+fn check_health(app: &AppHandle, servers: &[Server]) {
+    let monitor = app.state::<HealthMonitor>();
+    let projects = app.state::<ProjectStore>().list();
+    let path = |server: &Server| {
+        server
+            .project_id
+            .as_ref()
+            .and_then(|id| projects.iter().find(|p| &p.id == id))
+            .and_then(|p| p.health_path.clone())
+            .unwrap_or_else(|| "/".to_string())
+    };
+    let Some(targets) = monitor.begin(servers, path) else {
+        return;
+    };
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let monitor = app.state::<HealthMonitor>();
+        let _round = monitor.round();
+        let results = health::probe_all(targets, health::PROBE_TIMEOUT).await;
+        let updated = monitor.finish(results);
+        if !updated.is_empty() {
+            let _ = app.emit("server-health", updated);
+        }
+    });
+}
+
+/// Определить протокол новых портов в фоне; результат попадёт в
+/// `Server.fingerprint` на следующем скане (и придёт как `server-changed`)
+fn fingerprint_servers(app: &AppHandle, servers: &[Server]) {
+    let targets = app.state::<Fingerprinter>().claim(servers);
+    if targets.is_empty() {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let results = fingerprint::sniff_all(targets, fingerprint::SNIFF_TIMEOUT).await;
+        app.state::<Fingerprinter>().record(results);
+    });
+}
+
+/// Собрать список серверов из данных, полученных от `probe`
+fn collect_servers(probe: &dyn SystemProbe, filter: &PortFilter) -> Vec<Server> {
+    // 1. Порты — один вызов netstat (или /proc/net/{tcp,udp}), затем фильтр диапазонов
+    let pid_listeners = visible_listeners(probe, filter);
+    if pid_listeners.is_empty() {
         return Vec::new();
     }
 
-    // 2. Имена — один вызов tasklist (ВСЕ процессы)
-    let all_names = scanner::get_all_process_names();
+    // 2. Имена — один вызов tasklist или обход /proc (ВСЕ процессы)
+    let all_names = probe.process_names();
 
     // 3. Классификация — in-memory, мгновенно
-    let pids: Vec<u32> = pid_ports.keys().copied().collect();
+    let pids: Vec<u32> = pid_listeners.keys().copied().collect();
 
-    // 4. Uptime — нативный Windows API (GetProcessTimes)
-    let all_uptimes = scanner::get_all_uptimes(&pids);
+    // 4. Uptime — нативный API (GetProcessTimes / /proc/<pid>/stat)
+    let all_uptimes = probe.uptimes(&pids);
 
-    // 5. CWD для dev-процессов — batch native вызов (без PowerShell)
-    let dev_pids: Vec<u32> = pid_ports
+    // 5. Командные строки — классификация по аргументам (vite, uvicorn, ...)
+    let all_command_lines = probe.command_lines(&pids);
+    let command_line = |pid: &u32| all_command_lines.get(pid).map_or(&[][..], |a| a.as_slice());
+
+    // 6. CWD для dev-процессов — batch native вызов (без PowerShell)
+    let dev_pids: Vec<u32> = pid_listeners
         .keys()
         .filter(|pid| {
             all_names
                 .get(pid)
-                .map(|name| lookup_process(name).category == "dev")
+                .map(|name| lookup_process(name, command_line(pid)).category == "dev")
                 .unwrap_or(false)
         })
         .copied()
         .collect();
-    let all_cwds = scanner::get_all_process_cwds(&dev_pids);
+    let all_cwds = probe.cwds(&dev_pids);
 
-    // 6. Собираем серверы
+    // 7. Собираем серверы
     let mut servers: Vec<Server> = Vec::new();
 
-    for (pid, ports) in &pid_ports {
+    for (pid, listeners) in &pid_listeners {
         let process_name = match all_names.get(pid) {
-            Some(name) => name.clone(),
-            None => continue,
+            Some(name) if filter.allows_process(name) => name.clone(),
+            _ => continue,
         };
 
-        let info = lookup_process(&process_name);
-        let category = info.category.to_string();
+        let info = lookup_process(&process_name, command_line(pid));
+        let category = info.category.clone();
 
-        let (project_name, framework) = if info.category == "dev" {
+        let (project_name, project_root, framework, workspace) = if info.category == "dev" {
             match all_cwds.get(pid) {
-                Some(path) => (
-                    detector::detect_project_name(path),
-                    detector::detect_framework(path),
-                ),
-                None => (process_name.clone(), "Unknown".to_string()),
+                Some(cwd) => {
+                    // В монорепо — папка пакета, а не корень, из которого запущен turbo/nx;
+                    // иначе — корень проекта выше CWD (сервер мог стартовать из src/ или dist/)
+                    let member = workspace::attribute(cwd, command_line(pid));
+                    let root = match &member {
+                        Some(m) => Some(m.dir.clone()),
+                        None => detector::project_root(std::path::Path::new(cwd))
+                            .map(|p| p.to_string_lossy().into_owned()),
+                    };
+                    let dir = root.as_deref().unwrap_or(cwd);
+                    let name = member
+                        .as_ref()
+                        .map_or_else(|| detector::detect_project_name(dir), |m| m.package.clone());
+                    let framework =
+                        detector::detect_in(dir, member.as_ref().map(|m| m.root.as_str()));
+                    (name, root, framework, member)
+                }
+                None => (process_name.clone(), None, None, None),
             }
         } else {
-            (process_name.clone(), String::new())
+            (process_name.clone(), None, None, None)
         };
 
         let uptime = all_uptimes.get(pid).copied().unwrap_or(0);
 
-        let description = info.description.to_string();
+        let description = info.description.clone();
+
+        // (протокол, порт) → адреса: IPv4 и IPv6 одного процесса — одна запись
+        let mut ports: BTreeMap<(Protocol, u16), Vec<IpAddr>> = BTreeMap::new();
+        for l in listeners {
+            ports
+                .entry((l.protocol, l.addr.port()))
+                .or_default()
+                .push(l.addr.ip());
+        }
 
-        for port in ports {
+        for ((protocol, port), mut ips) in ports {
+            ips.sort();
             servers.push(Server {
                 pid: *pid,
-                port: *port,
+                port,
+                protocol,
                 project_name: project_name.clone(),
+                project_root: project_root.clone(),
                 framework: framework.clone(),
                 uptime_seconds: uptime,
                 process_name: process_name.clone(),
                 category: category.clone(),
                 description: description.clone(),
+                icon: info.icon.clone(),
+                command_line: command_line(pid).to_vec(),
+                addresses: ips.into_iter().map(BindAddress::from).collect(),
+                warnings: Vec::new(),
+                project_id: None,
+                health: None,
+                fingerprint: None,
+                workspace: workspace.clone(),
             });
         }
     }
 
-    // Сортировка: dev первые, потом apps, потом system
+    // Сортировка: dev первые, потом apps, пользовательские категории, потом system
     servers.sort_by(|a, b| {
         let cat_order = |c: &str| match c {
             "dev" => 0,
             "app" => 1,
+            "system" => 3,
             _ => 2,
         };
         cat_order(&a.category)
             .cmp(&cat_order(&b.category))
+            .then(a.category.cmp(&b.category))
             .then(a.port.cmp(&b.port))
+            .then(a.protocol.cmp(&b.protocol))
     });
 
+    conflicts::annotate(&mut servers);
     servers
 }
 
-#[derive(Debug, Clone, Copy)]
-struct ProcessInfo {
-    category: &'static str,
-    description: &'static str,
-}
-
-/// Единая таблица: имя процесса (lowercase) → категория + описание.
-/// Одно место для добавления новых процессов.
-const KNOWN_PROCESSES: &[(&str, ProcessInfo)] = &[
-    // Dev
-    ("node.exe", ProcessInfo { category: "dev", description: "Node.js" }),
-    ("python.exe", ProcessInfo { category: "dev", description: "Python" }),
-    ("python3.exe", ProcessInfo { category: "dev", description: "Python" }),
-    ("ruby.exe", ProcessInfo { category: "dev", description: "Ruby" }),
-    ("java.exe", ProcessInfo { category: "dev", description: "Java" }),
-    ("go.exe", ProcessInfo { category: "dev", description: "Go" }),
-    ("deno.exe", ProcessInfo { category: "dev", description: "Deno" }),
-    ("bun.exe", ProcessInfo { category: "dev", description: "Bun" }),
-    ("cargo.exe", ProcessInfo { category: "dev", description: "Rust" }),
-    ("rustc.exe", ProcessInfo { category: "dev", description: "Rust" }),
-    ("php.exe", ProcessInfo { category: "dev", description: "PHP" }),
-    ("dotnet.exe", ProcessInfo { category: "dev", description: ".NET" }),
-    // Apps
-    ("code.exe", ProcessInfo { category: "app", description: "Visual Studio Code" }),
-    ("spotify.exe", ProcessInfo { category: "app", description: "Spotify" }),
-    ("discord.exe", ProcessInfo { category: "app", description: "Discord" }),
-    ("slack.exe", ProcessInfo { category: "app", description: "Slack" }),
-    ("telegram.exe", ProcessInfo { category: "app", description: "Telegram" }),
-    ("chrome.exe", ProcessInfo { category: "app", description: "Google Chrome" }),
-    ("firefox.exe", ProcessInfo { category: "app", description: "Mozilla Firefox" }),
-    ("msedge.exe", ProcessInfo { category: "app", description: "Microsoft Edge" }),
-    ("brave.exe", ProcessInfo { category: "app", description: "Brave Browser" }),
-    ("opera.exe", ProcessInfo { category: "app", description: "Opera Browser" }),
-    ("postman.exe", ProcessInfo { category: "app", description: "Postman — API" }),
-    ("docker desktop.exe", ProcessInfo { category: "app", description: "Docker Desktop" }),
-    ("githubdesktop.exe", ProcessInfo { category: "app", description: "GitHub Desktop" }),
-    ("figma.exe", ProcessInfo { category: "app", description: "Figma" }),
-    ("notion.exe", ProcessInfo { category: "app", description: "Notion" }),
-    ("obsidian.exe", ProcessInfo { category: "app", description: "Obsidian" }),
-    ("windowsterminal.exe", ProcessInfo { category: "app", description: "Windows Terminal" }),
-    ("localdock.exe", ProcessInfo { category: "app", description: "LocalDock" }),
-    // System
-    ("svchost.exe", ProcessInfo { category: "system", description: "Windows Service Host" }),
-    ("vmms.exe", ProcessInfo { category: "system", description: "Hyper-V Manager" }),
-    ("system", ProcessInfo { category: "system", description: "Windows System" }),
-    ("lsass.exe", ProcessInfo { category: "system", description: "Windows Security" }),
-    ("services.exe", ProcessInfo { category: "system", description: "Windows Services" }),
-    ("spoolsv.exe", ProcessInfo { category: "system", description: "Print Spooler" }),
-    ("searchhost.exe", ProcessInfo { category: "system", description: "Windows Search" }),
-    ("runtimebroker.exe", ProcessInfo { category: "system", description: "Runtime Broker" }),
-    ("dwm.exe", ProcessInfo { category: "system", description: "Desktop Window Manager" }),
-    ("explorer.exe", ProcessInfo { category: "system", description: "Windows Explorer" }),
-    ("dllhost.exe", ProcessInfo { category: "system", description: "COM Surrogate" }),
-    ("conhost.exe", ProcessInfo { category: "system", description: "Console Host" }),
-    ("wmiprvse.exe", ProcessInfo { category: "system", description: "WMI Provider" }),
-    ("mdnsresponder.exe", ProcessInfo { category: "system", description: "Bonjour (mDNS)" }),
-    ("battle.net.exe", ProcessInfo { category: "system", description: "Battle.net (Blizzard)" }),
-    ("agent.exe", ProcessInfo { category: "system", description: "Battle.net Agent" }),
-    ("httpd.exe", ProcessInfo { category: "system", description: "Apache HTTP Server" }),
-    ("nginx.exe", ProcessInfo { category: "system", description: "Nginx" }),
-    ("mysqld.exe", ProcessInfo { category: "system", description: "MySQL" }),
-    ("postgres.exe", ProcessInfo { category: "system", description: "PostgreSQL" }),
-];
-
-fn lookup_process(name: &str) -> ProcessInfo {
-    let lower = name.to_lowercase();
-    KNOWN_PROCESSES
-        .iter()
-        .find(|(n, _)| *n == lower.as_str())
-        .map(|(_, info)| *info)
-        .unwrap_or(ProcessInfo { category: "system", description: "" })
+/// Категория и описание процесса из каталога (встроенный + пользовательский)
+fn lookup_process(name: &str, command_line: &[String]) -> ProcessInfo {
+    catalog::global().lookup(name, command_line)
+}
+
+/// Слушающие сокеты, порты которых проходят фильтр; PID без таких сокетов отбрасывается
+fn visible_listeners(
+    probe: &dyn SystemProbe,
+    filter: &PortFilter,
+) -> HashMap<u32, HashSet<Listener>> {
+    let mut pid_listeners = probe.listeners();
+    pid_listeners.retain(|_, listeners| {
+        listeners.retain(|l| filter.allows_port(l.addr.port()));
+        !listeners.is_empty()
+    });
+    pid_listeners
+}
+
+/// Мягкая остановка с эскалацией; ждёт до `stop_timeout_ms`, поэтому не в главном потоке.
+/// `tree` — вместе с запускалкой (`npm run dev`) и всеми потомками, см. `preview_kill_tree`.
+#[tauri::command]
+async fn kill_server(app: AppHandle, pid: u32, tree: bool) -> StopOutcome {
+    tauri::async_runtime::spawn_blocking(move || {
+        let store = app.state::<SettingsStore>();
+        let notifier = app.state::<Notifier>();
+        let timeout = Duration::from_millis(store.get().stop_timeout_ms);
+        let targets = stop_targets(&probe::NATIVE, &store.port_filter(), pid, tree);
+        // Отметить до kill: фоновый скан может увидеть исчезновение раньше, чем kill вернётся
+        for &target in &targets {
+            notifier.mark_killed(target);
+        }
+        let outcome = stop_pids(&probe::NATIVE, &targets, timeout);
+        if matches!(outcome, StopOutcome::Failed | StopOutcome::AccessDenied) {
+            for &target in &targets {
+                notifier.unmark_killed(target);
+            }
+        }
+        outcome
+    })
+    .await
+    .unwrap_or(StopOutcome::Failed)
+}
+
+/// Процесс, который остановит «Стоп дерево»
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TreeProcess {
+    pub pid: u32,
+    pub name: String,
+    /// Все слушающие порты процесса, без учёта фильтра
+    pub ports: Vec<u16>,
+}
+
+/// Ровно те процессы, которые остановит `kill_server(pid, tree: true)`
+#[tauri::command]
+fn preview_kill_tree(pid: u32, store: State<'_, SettingsStore>) -> Vec<TreeProcess> {
+    let targets = stop_targets(&probe::NATIVE, &store.port_filter(), pid, true);
+    describe_targets(&probe::NATIVE, &targets)
+}
+
+/// Whitelist: останавливаем только процессы, слушающие порт из фильтра; пусто — нельзя.
+/// В режиме дерева — корень цепочки запускалок и все его потомки, корень первым.
+fn stop_targets(
+    probe: &dyn SystemProbe,
+    filter: &PortFilter,
+    pid: u32,
+    whole_tree: bool,
+) -> Vec<u32> {
+    if !visible_listeners(probe, filter).contains_key(&pid) {
+        return Vec::new();
+    }
+    if !whole_tree {
+        return vec![pid];
+    }
+    let processes = ProcessTree::new(probe.parent_pids());
+    let root = launcher_root(probe, &processes, pid);
+    // Себя не останавливаем, даже если LocalDock запущен из того же дерева
+    let own = std::process::id();
+    processes.subtree(root).into_iter().filter(|&p| p != own).collect()
+}
+
+/// Верх цепочки запускалок над `pid` (`npm run dev` над `node vite`)
+fn launcher_root(probe: &dyn SystemProbe, processes: &ProcessTree, pid: u32) -> u32 {
+    let names = probe.process_names();
+    let command_lines = probe.command_lines(&processes.ancestors(pid));
+    processes.launcher_root(pid, |p| {
+        names.get(&p).is_some_and(|name| {
+            tree::is_launcher(name, command_lines.get(&p).map_or(&[][..], |a| a.as_slice()))
+        })
+    })
+}
+
+/// Команда запуска сервера — с корня цепочки запускалок, чтобы перезапуск
+/// поднял `npm run dev` целиком, а не только `node vite`
+fn capture_launch(probe: &dyn SystemProbe, pid: u32) -> Option<LaunchSpec> {
+    let processes = ProcessTree::new(probe.parent_pids());
+    restart::capture(probe, launcher_root(probe, &processes, pid))
 }
 
+/// Остановить дерево сервера и запустить заново записанной командой,
+/// ждать, пока `port` снова заслушают
 #[tauri::command]
-fn kill_server(pid: u32) -> bool {
-    // Whitelist: разрешаем kill только для процессов, слушающих порт >= 1024
-    let listening = scanner::scan_listening_ports();
-    if !listening.contains_key(&pid) {
-        return false;
+async fn restart_server(app: AppHandle, pid: u32, port: u16) -> RestartOutcome {
+    let handle = app.clone();
+    let outcome = tauri::async_runtime::spawn_blocking(move || {
+        let store = app.state::<SettingsStore>();
+        let registry = app.state::<RestartRegistry>();
+        let notifier = app.state::<Notifier>();
+        let Some(spec) = registry.get(pid) else {
+            return RestartOutcome::NotRecorded;
+        };
+        let timeout = Duration::from_millis(store.get().stop_timeout_ms);
+        let targets = stop_targets(&probe::NATIVE, &store.port_filter(), pid, true);
+        for &target in &targets {
+            notifier.mark_killed(target);
+        }
+        let outcome = restart_with(
+            &probe::NATIVE,
+            &spec,
+            &targets,
+            port,
+            timeout,
+            restart::START_TIMEOUT,
+        );
+        match &outcome {
+            RestartOutcome::Restarted { pid } => registry.insert(*pid, spec),
+            RestartOutcome::StopFailed { .. } => {
+                for &target in &targets {
+                    notifier.unmark_killed(target);
+                }
+            }
+            _ => {}
+        }
+        outcome
+    })
+    .await
+    .unwrap_or(RestartOutcome::SpawnFailed {
+        error: "внутрішня помилка".to_string(),
+    });
+    rescan(&handle);
+    outcome
+}
+
+fn restart_with(
+    probe: &dyn SystemProbe,
+    spec: &LaunchSpec,
+    targets: &[u32],
+    port: u16,
+    stop_timeout: Duration,
+    start_timeout: Duration,
+) -> RestartOutcome {
+    match stop_pids(probe, targets, stop_timeout) {
+        StopOutcome::Graceful | StopOutcome::Forced => {}
+        outcome => return RestartOutcome::StopFailed { outcome },
+    }
+    let spawned = match probe.spawn(spec) {
+        Ok(pid) => pid,
+        Err(error) => return RestartOutcome::SpawnFailed { error },
+    };
+    match restart::wait_for_port(probe, spawned, port, start_timeout) {
+        Some(pid) => RestartOutcome::Restarted { pid },
+        None => RestartOutcome::Timeout,
+    }
+}
+
+fn stop_pids(probe: &dyn SystemProbe, targets: &[u32], timeout: Duration) -> StopOutcome {
+    match targets {
+        [] => StopOutcome::Failed,
+        [pid] => process::stop(probe, *pid, timeout),
+        _ => process::stop_tree(probe, targets, timeout),
     }
-    process::kill_process(pid)
+}
+
+fn describe_targets(probe: &dyn SystemProbe, targets: &[u32]) -> Vec<TreeProcess> {
+    let names = probe.process_names();
+    let listeners = probe.listeners();
+    targets
+        .iter()
+        .map(|&pid| {
+            let mut ports: Vec<u16> = listeners
+                .get(&pid)
+                .into_iter()
+                .flatten()
+                .map(|l| l.addr.port())
+                .collect();
+            ports.sort_unstable();
+            ports.dedup();
+            TreeProcess {
+                pid,
+                name: names.get(&pid).cloned().unwrap_or_default(),
+                ports,
+            }
+        })
+        .collect()
 }
 
 #[tauri::command]
-fn open_in_browser(port: u16) {
-    if port < 1024 {
+fn open_in_browser(pid: u32, port: u16, store: State<'_, SettingsStore>) {
+    if !store.port_filter().allows_port(port) {
         return;
     }
-    let _ = open::that(format!("http://localhost:{}", port));
+    if let Some(url) = browser_url(&probe::NATIVE, pid, port) {
+        let _ = open::that(url);
+    }
+}
+
+/// URL сервера: хост выбирается по адресам, на которых PID реально слушает TCP-порт
+fn browser_url(probe: &dyn SystemProbe, pid: u32, port: u16) -> Option<String> {
+    let listeners = probe.listeners();
+    let ips: Vec<IpAddr> = listeners
+        .get(&pid)?
+        .iter()
+        .filter(|l| l.protocol == Protocol::Tcp && l.addr.port() == port)
+        .map(|l| l.addr.ip())
+        .collect();
+    browser_host(&ips).map(|host| format!("http://{}:{}", host, port))
+}
+
+/// Loopback или any → localhost (браузер сам попробует и 127.0.0.1, и ::1).
+/// Если слушает только конкретный интерфейс — его адрес.
+fn browser_host(ips: &[IpAddr]) -> Option<String> {
+    if ips.iter().any(|ip| ip.is_loopback() || ip.is_unspecified()) {
+        return Some("localhost".to_string());
+    }
+    match ips.iter().min()? {
+        IpAddr::V4(ip) => Some(ip.to_string()),
+        IpAddr::V6(ip) => Some(format!("[{}]", ip)),
+    }
+}
+
+#[tauri::command]
+fn get_settings(store: State<'_, SettingsStore>) -> Settings {
+    store.get()
+}
+
+/// Сохранить настройки и разослать их всем окнам событием `settings-changed`
+#[tauri::command]
+fn update_settings(
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+    settings: Settings,
+) -> Result<Settings, String> {
+    let saved = store.update(settings)?;
+    let _ = app.emit("settings-changed", &saved);
+    // Фильтр портов мог измениться — пересчитать список сразу
+    rescan(&app);
+    Ok(saved)
+}
+
+fn project_states(
+    store: &ProjectStore,
+    supervisor: &Supervisor,
+    logs: &LogStore,
+) -> Vec<ProjectState> {
+    store
+        .list()
+        .into_iter()
+        .map(|project| {
+            let (restarts, restart_pending) = supervisor.restarts(&project.id);
+            ProjectState {
+                pid: supervisor.pid(&project.id),
+                log_pid: logs.pid_for(&project.id),
+                restarts,
+                restart_pending,
+                project,
+            }
+        })
+        .collect()
+}
+
+#[tauri::command]
+fn get_projects(
+    store: State<'_, ProjectStore>,
+    supervisor: State<'_, Supervisor>,
+    logs: State<'_, LogStore>,
+) -> Vec<ProjectState> {
+    supervisor.reap(&store.list(), Instant::now());
+    project_states(&store, &supervisor, &logs)
+}
+
+/// Добавить папку: имя, команда и порт — по фреймворку, их можно поправить
+#[tauri::command]
+fn add_project(
+    app: AppHandle,
+    path: String,
+    store: State<'_, ProjectStore>,
+) -> Result<Project, String> {
+    let saved = store.upsert(projects::new_project(path.trim())?)?;
+    let _ = app.emit("projects-changed", ());
+    Ok(saved)
+}
+
+#[tauri::command]
+fn update_project(
+    app: AppHandle,
+    project: Project,
+    store: State<'_, ProjectStore>,
+) -> Result<Project, String> {
+    if store.get(&project.id).is_none() {
+        return Err(format!("Проєкт не знайдено: {}", project.id));
+    }
+    let saved = store.upsert(project)?;
+    let _ = app.emit("projects-changed", ());
+    Ok(saved)
+}
+
+/// Запущенный проект остаётся работать — удаляем только запись
+#[tauri::command]
+fn remove_project(
+    app: AppHandle,
+    id: String,
+    store: State<'_, ProjectStore>,
+) -> Result<(), String> {
+    store.remove(&id)?;
+    let _ = app.emit("projects-changed", ());
+    Ok(())
+}
+
+/// Запустить команду проекта; вернуть PID оболочки
+#[tauri::command]
+fn start_project(app: AppHandle, id: String) -> Result<u32, String> {
+    let project = app
+        .state::<ProjectStore>()
+        .get(&id)
+        .ok_or_else(|| format!("Проєкт не знайдено: {}", id))?;
+    let pid = launch_project(&app, &project)?;
+    let _ = app.emit("projects-changed", ());
+    rescan(&app);
+    Ok(pid)
+}
+
+/// Запустить проект и читать его вывод в `LogStore` (вручную и по политике перезапуска)
+fn launch_project(app: &AppHandle, project: &Project) -> Result<u32, String> {
+    let started = app.state::<Supervisor>().start(project)?;
+    let pid = started.pid;
+    let settings = app.state::<SettingsStore>().get();
+    app.state::<LogStore>().open(pid, &project.id, &settings.logs);
+    for (stream, reader) in started.output {
+        let app = app.clone();
+        logs::capture(reader, move |text| {
+            if let Some(line) = app.state::<LogStore>().push(pid, stream, text) {
+                let _ = app.emit("log-line", &line);
+            }
+        });
+    }
+    Ok(pid)
+}
+
+/// Вывод процесса, запущенного из LocalDock: строки с `seq > since` (все, если не задан)
+#[tauri::command]
+fn get_logs(pid: u32, since: Option<u64>, logs: State<'_, LogStore>) -> Vec<LogLine> {
+    logs.since(pid, since)
+}
+
+/// Остановить проект вместе со всеми потомками запущенной оболочки;
+/// запланированный перезапуск отменяется
+#[tauri::command]
+async fn stop_project(app: AppHandle, id: String) -> StopOutcome {
+    let handle = app.clone();
+    let outcome = tauri::async_runtime::spawn_blocking(move || {
+        let supervisor = app.state::<Supervisor>();
+        let cancelled = supervisor.stop_requested(&id);
+        match supervisor.pid(&id) {
+            Some(pid) => stop_process_tree(&app, pid),
+            None if cancelled => StopOutcome::Graceful,
+            None => StopOutcome::Failed,
+        }
+    })
+    .await
+    .unwrap_or(StopOutcome::Failed);
+    let _ = handle.emit("projects-changed", ());
+    rescan(&handle);
+    outcome
+}
+
+/// Остановить процесс, запущенный из LocalDock, со всеми потомками
+fn stop_process_tree(app: &AppHandle, pid: u32) -> StopOutcome {
+    let notifier = app.state::<Notifier>();
+    let timeout = Duration::from_millis(app.state::<SettingsStore>().get().stop_timeout_ms);
+    let targets = ProcessTree::new(probe::NATIVE.parent_pids()).subtree(pid);
+    for &target in &targets {
+        notifier.mark_killed(target);
+    }
+    let outcome = stop_pids(&probe::NATIVE, &targets, timeout);
+    if matches!(outcome, StopOutcome::Failed | StopOutcome::AccessDenied) {
+        for &target in &targets {
+            notifier.unmark_killed(target);
+        }
+    }
+    outcome
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -206,11 +752,33 @@ pub fn run() {
                 let _ = window.set_focus();
             }
         }))
+        .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_autostart::init(
             tauri_plugin_autostart::MacosLauncher::LaunchAgent,
             None,
         ))
         .setup(|app| {
+            let config_dir = app.path().app_config_dir()?;
+            catalog::init(&config_dir.join("processes.json"));
+            detector::init(&config_dir.join("frameworks.json"));
+            app.manage(SettingsStore::open(config_dir.join("settings.json")));
+            app.manage(Watcher::default());
+            app.manage(Notifier::default());
+            app.manage(RestartRegistry::default());
+            app.manage(ProjectStore::open(config_dir.join("projects.json")));
+            app.manage(Supervisor::default());
+            app.manage(LogStore::new(config_dir.join("logs")));
+            app.manage(HealthMonitor::default());
+            app.manage(Fingerprinter::default());
+
+            // Один фоновый скан на интервал — сколько бы окон ни слушало события
+            let handle = app.handle().clone();
+            std::thread::spawn(move || loop {
+                rescan(&handle);
+                let interval = handle.state::<SettingsStore>().get().poll_interval_ms;
+                std::thread::sleep(Duration::from_millis(interval));
+            });
+
             // Tray icon with context menu
             use tauri::menu::{Menu, MenuItem};
             use tauri::tray::TrayIconBuilder;
@@ -252,14 +820,30 @@ pub fn run() {
         })
         .on_window_event(|window, event| {
             if let tauri::WindowEvent::CloseRequested { api, .. } = event {
-                api.prevent_close();
-                let _ = window.hide();
+                if window.state::<SettingsStore>().get().close_to_tray {
+                    api.prevent_close();
+                    let _ = window.hide();
+                } else {
+                    window.app_handle().exit(0);
+                }
             }
         })
         .invoke_handler(tauri::generate_handler![
             get_servers,
+            refresh_servers,
             kill_server,
-            open_in_browser
+            preview_kill_tree,
+            restart_server,
+            open_in_browser,
+            get_settings,
+            update_settings,
+            get_projects,
+            add_project,
+            update_project,
+            remove_project,
+            start_project,
+            stop_project,
+            get_logs
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -268,19 +852,495 @@ pub fn run() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use probe::FakeProbe;
+    use std::fs;
     use std::time::Instant;
 
+    #[test]
+    fn test_collect_servers_empty_when_nothing_listens() {
+        let probe = FakeProbe::default().process(10, "node.exe");
+        assert!(collect_servers(&probe, &PortFilter::default()).is_empty());
+    }
+
+    #[test]
+    fn test_collect_servers_sorted_by_category_then_port() {
+        let probe = FakeProbe::default()
+            .server(1, "svchost.exe", &[5040])
+            .server(2, "Code.exe", &[9229])
+            .server(3, "node.exe", &[5173])
+            .server(4, "python.exe", &[3000])
+            .server(5, "mystery.exe", &[1234]);
+
+        let servers = collect_servers(&probe, &PortFilter::default());
+        let order: Vec<(&str, u16)> = servers
+            .iter()
+            .map(|s| (s.category.as_str(), s.port))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                ("dev", 3000),
+                ("dev", 5173),
+                ("app", 9229),
+                ("system", 1234),
+                ("system", 5040),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collect_servers_one_entry_per_port() {
+        let probe = FakeProbe::default().server(7, "node.exe", &[3000, 3001]);
+        let servers = collect_servers(&probe, &PortFilter::default());
+        assert_eq!(servers.len(), 2);
+        assert!(servers.iter().all(|s| s.pid == 7));
+    }
+
+    #[test]
+    fn test_collect_servers_skips_pid_without_name() {
+        let probe = FakeProbe::default()
+            .server(1, "node.exe", &[3000])
+            .listen(2, "0.0.0.0:4000");
+        let servers = collect_servers(&probe, &PortFilter::default());
+        assert_eq!(servers.len(), 1);
+        assert_eq!(servers[0].pid, 1);
+    }
+
+    #[test]
+    fn test_collect_servers_dev_project_from_cwd() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("package.json"),
+            r#"{ "name": "shop", "devDependencies": { "vite": "5.0.0" } }"#,
+        )
+        .unwrap();
+        let probe = FakeProbe::default()
+            .server(42, "node.exe", &[5173])
+            .uptime(42, 90)
+            .cwd(42, dir.path().to_str().unwrap());
+
+        let servers = collect_servers(&probe, &PortFilter::default());
+        assert_eq!(servers.len(), 1);
+        let s = &servers[0];
+        assert_eq!(s.project_name, "shop");
+        let framework = s.framework.as_ref().unwrap();
+        assert_eq!(framework.name, "Vite");
+        assert_eq!(framework.version.as_deref(), Some("5.0.0"));
+        assert_eq!(s.uptime_seconds, 90);
+        assert_eq!(s.description, "Node.js");
+    }
+
+    #[test]
+    fn test_collect_servers_project_root_above_cwd() {
+        // vite запущен из src/ — манифест на уровень выше
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("package.json"),
+            r#"{ "name": "shop", "devDependencies": { "vite": "5.0.0" } }"#,
+        )
+        .unwrap();
+        fs::create_dir(dir.path().join("src")).unwrap();
+        let probe = FakeProbe::default()
+            .server(42, "node", &[5173])
+            .cwd(42, dir.path().join("src").to_str().unwrap());
+
+        let s = &collect_servers(&probe, &PortFilter::default())[0];
+        assert_eq!(s.project_name, "shop");
+        assert_eq!(s.framework.as_ref().unwrap().name, "Vite");
+        assert_eq!(s.project_root.as_deref(), dir.path().to_str());
+    }
+
+    #[test]
+    fn test_collect_servers_monorepo_package_from_filter() {
+        // turbo запущен из корня — проект и фреймворк берём из пакета
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir(root.join(".git")).unwrap();
+        fs::write(
+            root.join("package.json"),
+            r#"{ "name": "acme", "workspaces": ["apps/*"] }"#,
+        )
+        .unwrap();
+        fs::create_dir_all(root.join("apps/web")).unwrap();
+        fs::write(
+            root.join("apps/web/package.json"),
+            r#"{ "name": "@acme/web", "dependencies": { "next": "14.1.0" } }"#,
+        )
+        .unwrap();
+        let probe = FakeProbe::default()
+            .server(42, "node", &[3000])
+            .cwd(42, root.to_str().unwrap())
+            .cmdline(42, "node turbo run dev --filter=web");
+
+        let s = &collect_servers(&probe, &PortFilter::default())[0];
+        assert_eq!(s.project_name, "@acme/web");
+        assert_eq!(s.framework.as_ref().unwrap().name, "Next.js");
+        let member = s.workspace.as_ref().unwrap();
+        assert_eq!(member.package, "@acme/web");
+        assert!(member.dir.ends_with("web"));
+    }
+
+    #[test]
+    fn test_collect_servers_dev_without_cwd() {
+        let probe = FakeProbe::default().server(42, "node", &[3000]);
+        let s = &collect_servers(&probe, &PortFilter::default())[0];
+        assert_eq!(s.category, "dev");
+        assert_eq!(s.project_name, "node");
+        assert_eq!(s.framework, None);
+        assert_eq!(s.uptime_seconds, 0);
+    }
+
+    #[test]
+    fn test_collect_servers_classifies_by_command_line() {
+        let probe = FakeProbe::default()
+            .server(3, "node", &[5173])
+            .cmdline(3, "node /app/node_modules/.bin/vite --port 5173")
+            .server(4, "python3", &[8888])
+            .cmdline(4, "/usr/bin/python3 -m jupyter lab");
+
+        let servers = collect_servers(&probe, &PortFilter::default());
+        let vite = servers.iter().find(|s| s.pid == 3).unwrap();
+        assert_eq!(vite.description, "Vite");
+        assert_eq!(
+            vite.command_line,
+            vec!["node", "/app/node_modules/.bin/vite", "--port", "5173"]
+        );
+        let jupyter = servers.iter().find(|s| s.pid == 4).unwrap();
+        assert_eq!(jupyter.category, "dev");
+        assert_eq!(jupyter.description, "Jupyter");
+    }
+
+    #[test]
+    fn test_collect_servers_non_dev_has_no_framework() {
+        // CWD есть, но для не-dev процессов detector не вызывается
+        let probe = FakeProbe::default()
+            .server(8, "postgres.exe", &[5432])
+            .cwd(8, "/definitely/not/used");
+        let s = &collect_servers(&probe, &PortFilter::default())[0];
+        assert_eq!(s.category, "system");
+        assert_eq!(s.project_name, "postgres.exe");
+        assert_eq!(s.framework, None);
+        assert_eq!(s.description, "PostgreSQL");
+    }
+
+    #[test]
+    fn test_collect_servers_merges_families_per_port() {
+        let probe = FakeProbe::default()
+            .server(5, "node.exe", &[3000])
+            .listen(5, "[::1]:3000")
+            .listen(5, "127.0.0.1:9229");
+
+        let servers = collect_servers(&probe, &PortFilter::default());
+        assert_eq!(servers.len(), 2);
+        let web = &servers[0];
+        assert_eq!(web.port, 3000);
+        let ips: Vec<&str> = web.addresses.iter().map(|a| a.ip.as_str()).collect();
+        assert_eq!(ips, vec!["0.0.0.0", "::1"]);
+        assert_eq!(web.addresses[0].scope, scanner::BindScope::Any);
+        assert_eq!(web.addresses[0].family, scanner::IpFamily::Ipv4);
+        assert_eq!(web.addresses[1].scope, scanner::BindScope::Loopback);
+        assert_eq!(web.addresses[1].family, scanner::IpFamily::Ipv6);
+        assert_eq!(servers[1].addresses[0].scope, scanner::BindScope::Loopback);
+    }
+
+    #[test]
+    fn test_collect_servers_split_families_are_separate_servers() {
+        // IPv4 и IPv6 на одном порту держат разные процессы
+        let probe = FakeProbe::default()
+            .process(1, "node.exe")
+            .listen(1, "127.0.0.1:5173")
+            .process(2, "python.exe")
+            .listen(2, "[::1]:5173");
+
+        let servers = collect_servers(&probe, &PortFilter::default());
+        assert_eq!(servers.len(), 2);
+        for s in &servers {
+            assert_eq!(s.addresses.len(), 1);
+        }
+    }
+
+    #[test]
+    fn test_collect_servers_flags_port_conflicts() {
+        // :3000 занят, второй next dev съехал на :3001
+        let probe = FakeProbe::default()
+            .server(1, "node.exe", &[3000])
+            .cmdline(1, "node next dev")
+            .server(2, "node.exe", &[3001])
+            .cmdline(2, "node next dev")
+            .process(3, "python.exe")
+            .listen(3, "[::1]:3000");
+
+        let servers = collect_servers(&probe, &PortFilter::default());
+        let warned: Vec<(u32, u16, conflicts::WarningKind)> = servers
+            .iter()
+            .flat_map(|s| s.warnings.iter().map(move |w| (s.pid, s.port, w.kind)))
+            .collect();
+        assert!(warned.contains(&(1, 3000, conflicts::WarningKind::SharedPort)));
+        assert!(warned.contains(&(3, 3000, conflicts::WarningKind::SharedPort)));
+        assert!(warned.contains(&(2, 3001, conflicts::WarningKind::PortFallback)));
+    }
+
+    #[test]
+    fn test_collect_servers_tcp_and_udp_on_same_port() {
+        let probe = FakeProbe::default()
+            .server(9, "node.exe", &[4433])
+            .listen_udp(9, "0.0.0.0:4433")
+            .process(10, "mdnsresponder.exe")
+            .listen_udp(10, "0.0.0.0:5353")
+            .listen_udp(10, "[::]:5353");
+
+        let servers = collect_servers(&probe, &PortFilter::default());
+        let rows: Vec<(u32, u16, Protocol)> = servers
+            .iter()
+            .map(|s| (s.pid, s.port, s.protocol))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (9, 4433, Protocol::Tcp),
+                (9, 4433, Protocol::Udp),
+                (10, 5353, Protocol::Udp),
+            ]
+        );
+        assert_eq!(servers[2].addresses.len(), 2);
+    }
+
+    #[test]
+    fn test_collect_servers_applies_port_filter() {
+        use settings::PortRange;
+
+        let probe = FakeProbe::default()
+            .server(1, "nginx", &[80, 443])
+            .server(2, "node", &[3000, 5173])
+            .process(3, "mdnsresponder")
+            .listen_udp(3, "0.0.0.0:5353");
+
+        // По умолчанию — как раньше: порты < 1024 не видны
+        let ports: Vec<u16> = collect_servers(&probe, &PortFilter::default())
+            .iter()
+            .map(|s| s.port)
+            .collect();
+        assert_eq!(ports, vec![3000, 5173, 5353]);
+
+        let filter = PortFilter {
+            include: vec![PortRange::new(1, u16::MAX)],
+            exclude: vec![PortRange::new(443, 443), PortRange::new(5000, 5999)],
+            ignored_processes: Vec::new(),
+        };
+        let ports: Vec<u16> = collect_servers(&probe, &filter)
+            .iter()
+            .map(|s| s.port)
+            .collect();
+        assert_eq!(ports, vec![3000, 80]);
+    }
+
+    #[test]
+    fn test_collect_servers_skips_ignored_processes() {
+        let probe = FakeProbe::default()
+            .server(1, "node.exe", &[3000])
+            .server(2, "svchost.exe", &[5040]);
+        let filter = PortFilter {
+            ignored_processes: vec!["svchost".to_string()],
+            ..PortFilter::default()
+        };
+        let servers = collect_servers(&probe, &filter);
+        assert_eq!(servers.len(), 1);
+        assert_eq!(servers[0].pid, 1);
+    }
+
+    #[test]
+    fn test_stop_targets_respect_port_filter() {
+        let probe = FakeProbe::default().server(1, "nginx", &[80]);
+        assert!(stop_targets(&probe, &PortFilter::default(), 1, false).is_empty());
+        assert_eq!(stop_pids(&probe, &[], Duration::ZERO), StopOutcome::Failed);
+        assert!(probe.signals.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_browser_url_ignores_udp() {
+        let probe = FakeProbe::default()
+            .process(1, "node.exe")
+            .listen_udp(1, "127.0.0.1:4433");
+        assert_eq!(browser_url(&probe, 1, 4433), None);
+    }
+
+    #[test]
+    fn test_browser_url_uses_bound_host() {
+        let probe = FakeProbe::default()
+            .process(1, "node.exe")
+            .listen(1, "192.168.1.20:3000")
+            .process(2, "node.exe")
+            .listen(2, "[::1]:5173")
+            .process(3, "node.exe")
+            .listen(3, "[fd00::5]:8080");
+
+        assert_eq!(
+            browser_url(&probe, 1, 3000).as_deref(),
+            Some("http://192.168.1.20:3000")
+        );
+        assert_eq!(
+            browser_url(&probe, 2, 5173).as_deref(),
+            Some("http://localhost:5173")
+        );
+        assert_eq!(
+            browser_url(&probe, 3, 8080).as_deref(),
+            Some("http://[fd00::5]:8080")
+        );
+        // PID не слушает этот порт
+        assert_eq!(browser_url(&probe, 1, 5173), None);
+    }
+
+    #[test]
+    fn test_stop_targets_only_whitelisted_pids() {
+        let probe = FakeProbe::default()
+            .server(1, "node.exe", &[3000])
+            .process(2, "explorer.exe");
+
+        let filter = PortFilter::default();
+        assert!(stop_targets(&probe, &filter, 2, false).is_empty());
+        assert!(stop_targets(&probe, &filter, 2, true).is_empty());
+        assert_eq!(stop_targets(&probe, &filter, 1, false), vec![1]);
+        assert_eq!(stop_pids(&probe, &[1], Duration::ZERO), StopOutcome::Graceful);
+        assert_eq!(*probe.signals.borrow(), vec![(1, process::Signal::Graceful)]);
+    }
+
+    #[test]
+    fn test_stop_targets_tree_climbs_through_launchers() {
+        // bash(10) → npm run dev(11) → sh -c vite(12) → node vite(13, :5173) → esbuild(14)
+        let probe = FakeProbe::default()
+            .process(10, "bash")
+            .cmdline(10, "bash")
+            .child(10, 11, "npm")
+            .cmdline(11, "npm run dev")
+            .child(11, 12, "sh")
+            .cmdline(12, "sh -c vite")
+            .child(12, 13, "node")
+            .cmdline(13, "node /app/node_modules/.bin/vite")
+            .listen(13, "127.0.0.1:5173")
+            .listen(13, "[::1]:5173")
+            .child(13, 14, "esbuild")
+            .listen(14, "127.0.0.1:40000");
+
+        let filter = PortFilter::default();
+        let targets = stop_targets(&probe, &filter, 13, true);
+        assert_eq!(targets, vec![11, 12, 13, 14]);
+
+        let preview = describe_targets(&probe, &targets);
+        let rows: Vec<(u32, &str, Vec<u16>)> = preview
+            .iter()
+            .map(|p| (p.pid, p.name.as_str(), p.ports.clone()))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (11, "npm", vec![]),
+                (12, "sh", vec![]),
+                (13, "node", vec![5173]),
+                (14, "esbuild", vec![40000]),
+            ]
+        );
+
+        assert_eq!(stop_pids(&probe, &targets, Duration::ZERO), StopOutcome::Graceful);
+        assert!(probe.is_alive(10));
+    }
+
+    #[test]
+    fn test_stop_targets_tree_ignores_reused_parent_pid() {
+        // vite(21) пережил свой npm; его PID 20 занял чужой `npm start`,
+        // запущенный позже, со своим сервером (22)
+        let probe = FakeProbe::default()
+            .process(20, "npm")
+            .cmdline(20, "npm start")
+            .started_at(20, 500)
+            .child(20, 21, "node")
+            .cmdline(21, "node /app/node_modules/.bin/vite")
+            .listen(21, "127.0.0.1:5173")
+            .started_at(21, 100)
+            .child(20, 22, "node")
+            .cmdline(22, "node /other/server.js")
+            .listen(22, "127.0.0.1:8080")
+            .started_at(22, 600);
+
+        let filter = PortFilter::default();
+        assert_eq!(stop_targets(&probe, &filter, 21, true), vec![21]);
+        assert_eq!(stop_targets(&probe, &filter, 22, true), vec![20, 22]);
+    }
+
+    #[test]
+    fn test_restart_ignores_foreign_port_owner() {
+        // Пока vite(12) останавливался, порт занял чужой процесс (50);
+        // запущенная заново команда порт так и не открыла
+        let probe = FakeProbe::default()
+            .server(12, "node", &[5173])
+            .cwd(12, "/app")
+            .cmdline(12, "node /app/node_modules/.bin/vite")
+            .server(50, "caddy", &[5173]);
+
+        let spec = capture_launch(&probe, 12).unwrap();
+        let outcome = restart_with(&probe, &spec, &[12], 5173, Duration::ZERO, Duration::ZERO);
+        assert_eq!(outcome, RestartOutcome::Timeout);
+        assert_eq!(probe.spawned.borrow().len(), 1);
+    }
+
+    #[test]
+    fn test_restart_relaunches_launcher_and_waits_for_port() {
+        // npm run dev(11) → node vite(12, :5173)
+        let probe = FakeProbe::default()
+            .process(11, "npm")
+            .cmdline(11, "npm run dev")
+            .cwd(11, "/app")
+            .child(11, 12, "node")
+            .cmdline(12, "node /app/node_modules/.bin/vite")
+            .listen(12, "127.0.0.1:5173")
+            .respawn_on(&[5173]);
+
+        let spec = capture_launch(&probe, 12).unwrap();
+        assert_eq!(spec.args, vec!["npm", "run", "dev"]);
+        assert_eq!(spec.cwd, "/app");
+
+        let targets = stop_targets(&probe, &PortFilter::default(), 12, true);
+        let outcome = restart_with(&probe, &spec, &targets, 5173, Duration::ZERO, Duration::ZERO);
+        assert_eq!(outcome, RestartOutcome::Restarted { pid: 1001 });
+        assert_eq!(*probe.spawned.borrow(), vec![spec]);
+        assert!(!probe.is_alive(11) && !probe.is_alive(12));
+    }
+
+    #[test]
+    fn test_restart_timeout_when_port_not_reopened() {
+        let probe = FakeProbe::default()
+            .server(1, "node", &[3000])
+            .cmdline(1, "node server.js")
+            .cwd(1, "/api");
+        let spec = capture_launch(&probe, 1).unwrap();
+        let outcome = restart_with(&probe, &spec, &[1], 3000, Duration::ZERO, Duration::ZERO);
+        assert_eq!(outcome, RestartOutcome::Timeout);
+
+        let protected = FakeProbe::default().server(2, "node", &[3000]).protect(2);
+        assert_eq!(
+            restart_with(&protected, &spec, &[2], 3000, Duration::ZERO, Duration::ZERO),
+            RestartOutcome::StopFailed {
+                outcome: StopOutcome::AccessDenied
+            }
+        );
+        assert!(protected.spawned.borrow().is_empty());
+    }
+
     #[test]
     fn test_get_servers_performance() {
         let start = Instant::now();
-        let servers = get_servers();
+        let servers = collect_servers(&probe::NATIVE, &PortFilter::default());
         let elapsed = start.elapsed();
 
         println!("Found {} servers in {:.2?}", servers.len(), elapsed);
         for s in &servers {
             println!(
                 "  [{}] {}:{} (pid={}, uptime={}s, {})",
-                s.category, s.process_name, s.port, s.pid, s.uptime_seconds, s.framework
+                s.category,
+                s.process_name,
+                s.port,
+                s.pid,
+                s.uptime_seconds,
+                s.framework.as_ref().map_or("", |f| f.name.as_str())
             );
         }
 
@@ -0,0 +1,333 @@
+//! Вывод процессов, запущенных из LocalDock: stdout/stderr построчно с временем
+//! в кольцевом буфере на PID (`get_logs`, событие `log-line`), по желанию —
+//! ещё и в файл `logs/<проект>.log` с ротацией. Буфер живёт и после выхода
+//! процесса — чтобы была видна последняя ошибка упавшего сервера.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::thread::{self, JoinHandle};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Границы размера буфера — чтобы 0 не выключил логи, а опечатка не съела память
+const MIN_BUFFER_LINES: usize = 100;
+const MAX_BUFFER_LINES: usize = 100_000;
+/// Файл меньше этого ротировать бессмысленно
+const MIN_FILE_BYTES: u64 = 64 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogSettings {
+    /// Сколько последних строк держать в памяти на процесс
+    pub buffer_lines: usize,
+    /// Дублировать вывод в `logs/<проект>.log` в конфиг-директории
+    pub to_disk: bool,
+    /// Размер файла, после которого он переименовывается в `.log.1`
+    pub max_file_bytes: u64,
+}
+
+impl Default for LogSettings {
+    fn default() -> Self {
+        LogSettings {
+            buffer_lines: 1000,
+            to_disk: false,
+            max_file_bytes: 1024 * 1024,
+        }
+    }
+}
+
+impl LogSettings {
+    pub fn normalized(mut self) -> LogSettings {
+        self.buffer_lines = self.buffer_lines.clamp(MIN_BUFFER_LINES, MAX_BUFFER_LINES);
+        self.max_file_bytes = self.max_file_bytes.max(MIN_FILE_BYTES);
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogStream {
+    Stdout,
+    Stderr,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LogLine {
+    pub pid: u32,
+    /// Сквозной номер строки; `get_logs(pid, since)` отдаёт строки с `seq > since`
+    pub seq: u64,
+    /// Unix time, ms
+    pub timestamp_ms: u64,
+    pub stream: LogStream,
+    pub text: String,
+}
+
+/// Файл лога с одной резервной копией: `<name>.log` → `<name>.log.1`
+struct LogFile {
+    path: PathBuf,
+    max_bytes: u64,
+    written: u64,
+    file: Option<File>,
+}
+
+impl LogFile {
+    fn new(path: PathBuf, max_bytes: u64) -> LogFile {
+        let written = fs::metadata(&path).map_or(0, |m| m.len());
+        LogFile {
+            path,
+            max_bytes,
+            written,
+            file: None,
+        }
+    }
+
+    fn append(&mut self, line: &LogLine) -> io::Result<()> {
+        let stream = match line.stream {
+            LogStream::Stdout => "out",
+            LogStream::Stderr => "err",
+        };
+        let entry = format!("{} [{}] {}\n", line.timestamp_ms, stream, line.text);
+        if self.written > 0 && self.written + entry.len() as u64 > self.max_bytes {
+            self.file = None;
+            fs::rename(&self.path, self.path.with_extension("log.1"))?;
+            self.written = 0;
+        }
+        let file = match &mut self.file {
+            Some(file) => file,
+            None => {
+                if let Some(dir) = self.path.parent() {
+                    fs::create_dir_all(dir)?;
+                }
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&self.path)?;
+                self.file.insert(file)
+            }
+        };
+        file.write_all(entry.as_bytes())?;
+        self.written += entry.len() as u64;
+        Ok(())
+    }
+}
+
+struct ProcessLog {
+    label: String,
+    capacity: usize,
+    lines: VecDeque<LogLine>,
+    file: Option<LogFile>,
+}
+
+#[derive(Default)]
+struct Inner {
+    next_seq: u64,
+    logs: HashMap<u32, ProcessLog>,
+}
+
+/// Логи всех процессов, запущенных из LocalDock (managed state)
+pub struct LogStore {
+    /// Куда писать файлы при `to_disk`
+    dir: PathBuf,
+    inner: Mutex<Inner>,
+}
+
+impl LogStore {
+    pub fn new(dir: PathBuf) -> LogStore {
+        LogStore {
+            dir,
+            inner: Mutex::new(Inner::default()),
+        }
+    }
+
+    /// Начать лог процесса. `label` — id проекта: лог прошлого запуска
+    /// того же проекта отбрасывается, так что буферов не больше, чем проектов.
+    pub fn open(&self, pid: u32, label: &str, settings: &LogSettings) {
+        let Ok(mut inner) = self.inner.lock() else {
+            return;
+        };
+        inner.logs.retain(|_, log| log.label != label);
+        let settings = settings.clone().normalized();
+        let file = settings.to_disk.then(|| {
+            LogFile::new(
+                self.dir.join(format!("{}.log", label)),
+                settings.max_file_bytes,
+            )
+        });
+        inner.logs.insert(
+            pid,
+            ProcessLog {
+                label: label.to_string(),
+                capacity: settings.buffer_lines,
+                lines: VecDeque::new(),
+                file,
+            },
+        );
+    }
+
+    /// Добавить строку; None — для этого PID лог не открыт
+    pub fn push(&self, pid: u32, stream: LogStream, text: String) -> Option<LogLine> {
+        let mut inner = self.inner.lock().ok()?;
+        inner.next_seq += 1;
+        let seq = inner.next_seq;
+        let log = inner.logs.get_mut(&pid)?;
+        let line = LogLine {
+            pid,
+            seq,
+            timestamp_ms: now_ms(),
+            stream,
+            text,
+        };
+        if let Some(file) = &mut log.file {
+            // Диск не обязателен: ошибка записи не должна терять строку в памяти
+            let _ = file.append(&line);
+        }
+        if log.lines.len() == log.capacity {
+            log.lines.pop_front();
+        }
+        log.lines.push_back(line.clone());
+        Some(line)
+    }
+
+    /// Строки процесса с `seq > since` (все, если `since` не задан)
+    pub fn since(&self, pid: u32, since: Option<u64>) -> Vec<LogLine> {
+        let Ok(inner) = self.inner.lock() else {
+            return Vec::new();
+        };
+        let since = since.unwrap_or(0);
+        inner
+            .logs
+            .get(&pid)
+            .map(|log| {
+                log.lines
+                    .iter()
+                    .filter(|l| l.seq > since)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// PID последнего запуска с этой меткой — его лог ещё можно прочитать
+    pub fn pid_for(&self, label: &str) -> Option<u32> {
+        let inner = self.inner.lock().ok()?;
+        inner
+            .logs
+            .iter()
+            .find(|(_, log)| log.label == label)
+            .map(|(pid, _)| *pid)
+    }
+}
+
+pub fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as u64)
+}
+
+/// Читать поток построчно в отдельном потоке, пока процесс не закроет его.
+/// Невалидный UTF-8 заменяется, `\r\n` и `\n` срезаются.
+pub fn capture(
+    reader: impl Read + Send + 'static,
+    sink: impl Fn(String) + Send + 'static,
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        let mut reader = BufReader::new(reader);
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            match reader.read_until(b'\n', &mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {
+                    let text = String::from_utf8_lossy(&buf);
+                    sink(text.trim_end_matches(['\r', '\n']).to_string());
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_ring_buffer_keeps_last_lines() {
+        let store = LogStore::new(PathBuf::new());
+        let settings = LogSettings {
+            buffer_lines: 0,
+            ..LogSettings::default()
+        };
+        store.open(7, "web", &settings);
+        for i in 0..150 {
+            store.push(7, LogStream::Stdout, format!("line {}", i));
+        }
+        let lines = store.since(7, None);
+        // 0 поднят до минимума
+        assert_eq!(lines.len(), MIN_BUFFER_LINES);
+        assert_eq!(lines[0].text, "line 50");
+        assert_eq!(lines.last().unwrap().text, "line 149");
+
+        let last_seq = lines.last().unwrap().seq;
+        let err = store
+            .push(7, LogStream::Stderr, "boom".to_string())
+            .unwrap();
+        assert_eq!(store.since(7, Some(last_seq)), vec![err]);
+        assert_eq!(store.push(8, LogStream::Stdout, "x".to_string()), None);
+    }
+
+    #[test]
+    fn test_reopen_same_label_drops_previous_run() {
+        let store = LogStore::new(PathBuf::new());
+        store.open(1, "web", &LogSettings::default());
+        store.push(1, LogStream::Stderr, "crash".to_string());
+        assert_eq!(store.pid_for("web"), Some(1));
+
+        store.open(2, "web", &LogSettings::default());
+        assert!(store.since(1, None).is_empty());
+        assert_eq!(store.pid_for("web"), Some(2));
+    }
+
+    #[test]
+    fn test_disk_log_rotates() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = LogStore::new(dir.path().join("logs"));
+        let settings = LogSettings {
+            to_disk: true,
+            max_file_bytes: 0,
+            ..LogSettings::default()
+        };
+        store.open(1, "api", &settings);
+        let text = "x".repeat(1000);
+        for _ in 0..100 {
+            store.push(1, LogStream::Stdout, text.clone());
+        }
+        let current = fs::metadata(dir.path().join("logs/api.log")).unwrap().len();
+        let rotated = fs::metadata(dir.path().join("logs/api.log.1"))
+            .unwrap()
+            .len();
+        assert!(current <= MIN_FILE_BYTES);
+        assert!(rotated > MIN_FILE_BYTES - 1100 && rotated <= MIN_FILE_BYTES);
+        assert!(fs::read_to_string(dir.path().join("logs/api.log"))
+            .unwrap()
+            .contains(" [out] xxx"));
+    }
+
+    #[test]
+    fn test_capture_splits_lines() {
+        let lines = Arc::new(Mutex::new(Vec::new()));
+        let sink = lines.clone();
+        let input = Cursor::new(b"ready\r\nerr \xff\nno newline".to_vec());
+        capture(input, move |text| sink.lock().unwrap().push(text))
+            .join()
+            .unwrap();
+        assert_eq!(
+            *lines.lock().unwrap(),
+            vec!["ready", "err \u{fffd}", "no newline"]
+        );
+    }
+}
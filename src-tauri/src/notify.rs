@@ -0,0 +1,355 @@
+//! Уведомления рабочего стола по diff'ам наблюдателя: сервер запущен,
+//! остановлен или «упал» (dev-процесс исчез без `kill_server`).
+//! Какие события показывать — правила в настройках.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+
+use crate::settings::PortRange;
+use crate::watcher::Diff;
+use crate::Server;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoticeKind {
+    Started,
+    Stopped,
+    Crashed,
+}
+
+/// Одно уведомление на процесс: все его появившиеся/исчезнувшие порты вместе
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notice {
+    pub kind: NoticeKind,
+    pub server: Server,
+    pub ports: Vec<u16>,
+}
+
+/// Правило: пустые критерии совпадают с любым сервером.
+/// Срабатывает первое подходящее правило; если ни одно не подошло — молчим.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationRule {
+    pub category: Option<String>,
+    pub ports: Vec<PortRange>,
+    /// Имя проекта без учёта регистра
+    pub project: Option<String>,
+    pub started: bool,
+    pub stopped: bool,
+    pub crashed: bool,
+}
+
+impl Default for NotificationRule {
+    fn default() -> Self {
+        NotificationRule {
+            category: None,
+            ports: Vec::new(),
+            project: None,
+            started: true,
+            stopped: true,
+            crashed: true,
+        }
+    }
+}
+
+impl NotificationRule {
+    fn matches(&self, server: &Server) -> bool {
+        self.category.as_ref().is_none_or(|c| *c == server.category)
+            && (self.ports.is_empty() || self.ports.iter().any(|r| r.contains(server.port)))
+            && self
+                .project
+                .as_ref()
+                .is_none_or(|p| p.eq_ignore_ascii_case(&server.project_name))
+    }
+
+    fn allows(&self, kind: NoticeKind) -> bool {
+        match kind {
+            NoticeKind::Started => self.started,
+            NoticeKind::Stopped => self.stopped,
+            NoticeKind::Crashed => self.crashed,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationSettings {
+    pub enabled: bool,
+    pub rules: Vec<NotificationRule>,
+}
+
+impl Default for NotificationSettings {
+    /// Из коробки — только dev-серверы
+    fn default() -> Self {
+        NotificationSettings {
+            enabled: true,
+            rules: vec![NotificationRule {
+                category: Some("dev".to_string()),
+                ..NotificationRule::default()
+            }],
+        }
+    }
+}
+
+impl NotificationSettings {
+    pub fn allows(&self, notice: &Notice) -> bool {
+        self.enabled
+            && self
+                .rules
+                .iter()
+                .find(|r| r.matches(&notice.server))
+                .is_some_and(|r| r.allows(notice.kind))
+    }
+}
+
+/// Сгруппировать серверы по PID (порядок — по первому появлению)
+fn group_by_pid(servers: &[Server]) -> Vec<(Server, Vec<u16>)> {
+    let mut groups: Vec<(Server, Vec<u16>)> = Vec::new();
+    for server in servers {
+        match groups.iter_mut().find(|(s, _)| s.pid == server.pid) {
+            Some((_, ports)) => ports.push(server.port),
+            None => groups.push((server.clone(), vec![server.port])),
+        }
+    }
+    groups
+}
+
+/// Diff → уведомления. `killed` — PIDs, остановленные через `kill_server`;
+/// в нём остаются только те, что ещё среди `servers`: исчезнувшие сервера
+/// использовали отметку, а процессы без портов (npm, sh, esbuild из «Стоп
+/// дерево») в diff не попадут никогда — иначе их PID, доставшийся потом
+/// dev-серверу, выдал бы его падение за «Стоп». `alive` — жив ли процесс сейчас.
+pub fn classify(
+    diff: &Diff,
+    servers: &[Server],
+    killed: &mut HashSet<u32>,
+    alive: impl Fn(u32) -> bool,
+) -> Vec<Notice> {
+    let mut notices: Vec<Notice> = group_by_pid(&diff.added)
+        .into_iter()
+        .map(|(server, ports)| Notice {
+            kind: NoticeKind::Started,
+            server,
+            ports,
+        })
+        .collect();
+
+    for (server, ports) in group_by_pid(&diff.removed) {
+        let stopped_by_us = killed.remove(&server.pid);
+        let kind = if !stopped_by_us && server.category == "dev" && !alive(server.pid) {
+            NoticeKind::Crashed
+        } else {
+            NoticeKind::Stopped
+        };
+        notices.push(Notice {
+            kind,
+            server,
+            ports,
+        });
+    }
+    killed.retain(|pid| servers.iter().any(|s| s.pid == *pid));
+    notices
+}
+
+fn title(kind: NoticeKind) -> &'static str {
+    match kind {
+        NoticeKind::Started => "Сервер запущено",
+        NoticeKind::Stopped => "Сервер зупинено",
+        NoticeKind::Crashed => "Сервер впав",
+    }
+}
+
+fn body(notice: &Notice) -> String {
+    let ports = notice
+        .ports
+        .iter()
+        .map(|p| format!(":{}", p))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let name = &notice.server.project_name;
+    match notice.kind {
+        NoticeKind::Crashed => format!("{} ({}) завершився без команди «Стоп»", name, ports),
+        _ => format!("{} — {}", name, ports),
+    }
+}
+
+/// PIDs, остановленные пользователем — их исчезновение не «падение»
+#[derive(Default)]
+pub struct Notifier {
+    killed: Mutex<HashSet<u32>>,
+}
+
+impl Notifier {
+    pub fn mark_killed(&self, pid: u32) {
+        if let Ok(mut killed) = self.killed.lock() {
+            killed.insert(pid);
+        }
+    }
+
+    /// kill не удался — процесс по-прежнему «наш» только если исчезнет сам
+    pub fn unmark_killed(&self, pid: u32) {
+        if let Ok(mut killed) = self.killed.lock() {
+            killed.remove(&pid);
+        }
+    }
+
+    /// Разобрать diff и показать разрешённые правилами уведомления;
+    /// `servers` — текущий снимок, после которого посчитан `diff`
+    pub fn notify(
+        &self,
+        app: &AppHandle,
+        settings: &NotificationSettings,
+        diff: &Diff,
+        servers: &[Server],
+        alive: impl Fn(u32) -> bool,
+    ) {
+        let notices = match self.killed.lock() {
+            Ok(mut killed) => classify(diff, servers, &mut killed, alive),
+            Err(_) => return,
+        };
+        for notice in notices.iter().filter(|n| settings.allows(n)) {
+            let _ = app
+                .notification()
+                .builder()
+                .title(title(notice.kind))
+                .body(body(notice))
+                .show();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(pid: u32, port: u16, category: &str, project: &str) -> Server {
+        Server {
+            project_name: project.to_string(),
+            category: category.to_string(),
+            ..Server::sample(pid, port)
+        }
+    }
+
+    fn kinds(notices: &[Notice]) -> Vec<(u32, NoticeKind)> {
+        notices.iter().map(|n| (n.server.pid, n.kind)).collect()
+    }
+
+    #[test]
+    fn test_classify_groups_ports_per_process() {
+        let diff = Diff {
+            added: vec![
+                server(1, 5173, "dev", "shop"),
+                server(1, 24678, "dev", "shop"),
+            ],
+            ..Diff::default()
+        };
+        let notices = classify(&diff, &diff.added, &mut HashSet::new(), |_| true);
+        assert_eq!(kinds(&notices), vec![(1, NoticeKind::Started)]);
+        assert_eq!(notices[0].ports, vec![5173, 24678]);
+        assert_eq!(body(&notices[0]), "shop — :5173, :24678");
+    }
+
+    #[test]
+    fn test_classify_crash_vs_stop() {
+        let diff = Diff {
+            removed: vec![
+                server(1, 3000, "dev", "api"),  // исчез сам — crash
+                server(2, 3001, "dev", "web"),  // остановлен через kill_server
+                server(3, 3002, "dev", "docs"), // закрыл порт, но процесс жив
+                server(4, 9229, "app", "Code"), // не dev — просто остановлен
+            ],
+            ..Diff::default()
+        };
+        let mut killed = HashSet::from([2]);
+        let notices = classify(&diff, &[], &mut killed, |pid| pid == 3);
+        assert_eq!(
+            kinds(&notices),
+            vec![
+                (1, NoticeKind::Crashed),
+                (2, NoticeKind::Stopped),
+                (3, NoticeKind::Stopped),
+                (4, NoticeKind::Stopped),
+            ]
+        );
+        // Отметка о kill использована и больше не нужна (PID может быть переиспользован)
+        assert!(killed.is_empty());
+    }
+
+    #[test]
+    fn test_killed_without_ports_are_forgotten() {
+        // «Стоп дерево»: npm(10) и esbuild(12) без портов, vite(11) ещё не исчез
+        let current = vec![server(11, 5173, "dev", "shop")];
+        let mut killed = HashSet::from([10, 11, 12]);
+        let notices = classify(&Diff::default(), &current, &mut killed, |_| true);
+        assert!(notices.is_empty());
+        assert_eq!(killed, HashSet::from([11]));
+
+        // Позже PID 10 достался dev-серверу, и тот упал — это не «Стоп»
+        let diff = Diff {
+            removed: vec![current[0].clone(), server(10, 3000, "dev", "api")],
+            ..Diff::default()
+        };
+        let notices = classify(&diff, &[], &mut killed, |_| false);
+        assert_eq!(
+            kinds(&notices),
+            vec![(11, NoticeKind::Stopped), (10, NoticeKind::Crashed)]
+        );
+        assert!(killed.is_empty());
+    }
+
+    #[test]
+    fn test_default_rules_only_dev() {
+        let settings = NotificationSettings::default();
+        let notice = |category: &str| Notice {
+            kind: NoticeKind::Started,
+            server: server(1, 3000, category, "shop"),
+            ports: vec![3000],
+        };
+        assert!(settings.allows(&notice("dev")));
+        assert!(!settings.allows(&notice("system")));
+
+        let disabled = NotificationSettings {
+            enabled: false,
+            ..NotificationSettings::default()
+        };
+        assert!(!disabled.allows(&notice("dev")));
+    }
+
+    #[test]
+    fn test_first_matching_rule_wins() {
+        let settings = NotificationSettings {
+            enabled: true,
+            rules: vec![
+                // Шумный проект: только падения
+                NotificationRule {
+                    project: Some("Storybook".to_string()),
+                    started: false,
+                    stopped: false,
+                    ..NotificationRule::default()
+                },
+                // Базы данных на своих портах — всё
+                NotificationRule {
+                    ports: vec![PortRange::new(5432, 5433), PortRange::new(6379, 6379)],
+                    ..NotificationRule::default()
+                },
+                NotificationRule {
+                    category: Some("dev".to_string()),
+                    ..NotificationRule::default()
+                },
+            ],
+        };
+        let notice = |kind, port, category: &str, project: &str| Notice {
+            kind,
+            server: server(1, port, category, project),
+            ports: vec![port],
+        };
+        assert!(!settings.allows(&notice(NoticeKind::Started, 6006, "dev", "storybook")));
+        assert!(settings.allows(&notice(NoticeKind::Crashed, 6006, "dev", "storybook")));
+        assert!(settings.allows(&notice(NoticeKind::Started, 5432, "system", "postgres")));
+        assert!(!settings.allows(&notice(NoticeKind::Started, 3306, "system", "mysqld")));
+        assert!(settings.allows(&notice(NoticeKind::Stopped, 3000, "dev", "shop")));
+    }
+}
@@ -0,0 +1,321 @@
+//! Абстракция над ОС: всё, что `get_servers`/`kill_server` спрашивают у системы.
+//! `NativeProbe` — текущая ОС, `FakeProbe` — для тестов.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::process::{Signal, SignalError};
+use crate::restart::LaunchSpec;
+use crate::scanner::{Listener, ProcessParent};
+use crate::{process, restart, scanner};
+
+pub trait SystemProbe {
+    /// PID → слушающие сокеты (протокол + адрес + порт)
+    fn listeners(&self) -> HashMap<u32, HashSet<Listener>>;
+    /// PID → имя процесса (для ВСЕХ процессов)
+    fn process_names(&self) -> HashMap<u32, String>;
+    /// PID → uptime в секундах
+    fn uptimes(&self, pids: &[u32]) -> HashMap<u32, u64>;
+    /// PID → рабочая директория
+    fn cwds(&self, pids: &[u32]) -> HashMap<u32, String>;
+    /// PID → командная строка (argv, argv[0] — путь или имя программы)
+    fn command_lines(&self, pids: &[u32]) -> HashMap<u32, Vec<String>>;
+    /// Путь к исполняемому файлу
+    fn executable(&self, pid: u32) -> Option<String>;
+    /// Окружение процесса (пусто, если ОС не дала прочитать)
+    fn environment(&self, pid: u32) -> Vec<(String, String)>;
+    /// Запустить процесс отвязанно, вернуть PID
+    fn spawn(&self, spec: &LaunchSpec) -> Result<u32, String>;
+    /// PID → PID родителя и время запуска (для ВСЕХ живых процессов)
+    fn parent_pids(&self) -> HashMap<u32, ProcessParent>;
+    /// Послать сигнал завершения (мягкий или принудительный)
+    fn signal(&self, pid: u32, signal: Signal) -> Result<(), SignalError>;
+    /// Процесс ещё существует
+    fn is_alive(&self, pid: u32) -> bool;
+}
+
+/// Система, на которой запущены: `scanner` и `process` сами выбирают
+/// реализацию под ОС (netstat + tasklist + WinAPI на Windows, procfs на Linux)
+#[cfg(any(windows, target_os = "linux"))]
+pub struct NativeProbe;
+
+#[cfg(any(windows, target_os = "linux"))]
+impl SystemProbe for NativeProbe {
+    fn listeners(&self) -> HashMap<u32, HashSet<Listener>> {
+        scanner::scan_listeners()
+    }
+
+    fn process_names(&self) -> HashMap<u32, String> {
+        scanner::get_all_process_names()
+    }
+
+    fn uptimes(&self, pids: &[u32]) -> HashMap<u32, u64> {
+        scanner::get_all_uptimes(pids)
+    }
+
+    fn cwds(&self, pids: &[u32]) -> HashMap<u32, String> {
+        scanner::get_all_process_cwds(pids)
+    }
+
+    fn command_lines(&self, pids: &[u32]) -> HashMap<u32, Vec<String>> {
+        scanner::get_all_command_lines(pids)
+    }
+
+    fn executable(&self, pid: u32) -> Option<String> {
+        scanner::get_process_executable(pid)
+    }
+
+    fn environment(&self, pid: u32) -> Vec<(String, String)> {
+        scanner::get_process_environment(pid).unwrap_or_default()
+    }
+
+    fn spawn(&self, spec: &LaunchSpec) -> Result<u32, String> {
+        restart::spawn_detached(spec)
+    }
+
+    fn parent_pids(&self) -> HashMap<u32, ProcessParent> {
+        scanner::get_all_parent_pids()
+    }
+
+    fn signal(&self, pid: u32, signal: Signal) -> Result<(), SignalError> {
+        process::send_signal(pid, signal)
+    }
+
+    fn is_alive(&self, pid: u32) -> bool {
+        process::is_alive(pid)
+    }
+}
+
+/// Реализация для текущей ОС
+#[cfg(any(windows, target_os = "linux"))]
+pub const NATIVE: NativeProbe = NativeProbe;
+
+// Бэкенда сканера для других ОС нет — пусть сборка скажет об этом прямо,
+// а не списком неразрешённых `scanner::*`
+#[cfg(not(any(windows, target_os = "linux")))]
+compile_error!("LocalDock поддерживает только Windows и Linux: нет бэкенда `scanner` для этой ОС");
+
+#[cfg(test)]
+use crate::scanner::Protocol;
+#[cfg(test)]
+use std::cell::{Cell, RefCell};
+
+/// In-memory таблица процессов для детерминированных тестов
+#[cfg(test)]
+#[derive(Default)]
+pub struct FakeProbe {
+    pub listeners: HashMap<u32, HashSet<Listener>>,
+    pub names: HashMap<u32, String>,
+    pub uptimes: HashMap<u32, u64>,
+    pub cwds: HashMap<u32, String>,
+    pub command_lines: HashMap<u32, Vec<String>>,
+    pub parents: HashMap<u32, u32>,
+    /// Время запуска (в условных единицах); нет записи — неизвестно
+    pub started: HashMap<u32, u64>,
+    pub envs: HashMap<u32, Vec<(String, String)>>,
+    /// Запущенные через `spawn`; каждый новый процесс слушает `respawn_ports`
+    pub spawned: RefCell<Vec<LaunchSpec>>,
+    pub respawn_ports: Vec<u16>,
+    respawned: RefCell<HashMap<u32, HashSet<Listener>>>,
+    /// Посланные сигналы по порядку
+    pub signals: RefCell<Vec<(u32, Signal)>>,
+    /// Процессы, которые не реагируют на мягкий сигнал
+    pub stubborn: HashSet<u32>,
+    /// Процессы, на которые нет прав
+    pub protected: HashSet<u32>,
+    /// Завершённые сигналом: пропадают из listeners/process_names
+    pub exited: RefCell<HashSet<u32>>,
+    /// Сколько раз сканировали порты (`listeners`)
+    pub port_scans: Cell<usize>,
+}
+
+#[cfg(test)]
+impl FakeProbe {
+    /// Процесс без портов (есть в tasklist, но ничего не слушает)
+    pub fn process(mut self, pid: u32, name: &str) -> Self {
+        self.names.insert(pid, name.to_string());
+        self
+    }
+
+    /// Процесс, слушающий TCP-порты на 0.0.0.0
+    pub fn server(self, pid: u32, name: &str, ports: &[u16]) -> Self {
+        let mut probe = self.process(pid, name);
+        for port in ports {
+            probe = probe.listen(pid, &format!("0.0.0.0:{}", port));
+        }
+        probe
+    }
+
+    /// Дополнительный слушающий TCP-сокет, например "[::1]:3000"
+    pub fn listen(self, pid: u32, addr: &str) -> Self {
+        self.bind(pid, Protocol::Tcp, addr)
+    }
+
+    /// Bound UDP-сокет, например "0.0.0.0:5353"
+    pub fn listen_udp(self, pid: u32, addr: &str) -> Self {
+        self.bind(pid, Protocol::Udp, addr)
+    }
+
+    fn bind(mut self, pid: u32, protocol: Protocol, addr: &str) -> Self {
+        let addr = addr.parse().expect("valid socket address");
+        self.listeners
+            .entry(pid)
+            .or_default()
+            .insert(Listener { protocol, addr });
+        self
+    }
+
+    pub fn uptime(mut self, pid: u32, secs: u64) -> Self {
+        self.uptimes.insert(pid, secs);
+        self
+    }
+
+    pub fn cwd(mut self, pid: u32, path: &str) -> Self {
+        self.cwds.insert(pid, path.to_string());
+        self
+    }
+
+    /// Командная строка, аргументы через пробел: "node vite --port 5173"
+    pub fn cmdline(mut self, pid: u32, line: &str) -> Self {
+        let args = line.split_whitespace().map(str::to_string).collect();
+        self.command_lines.insert(pid, args);
+        self
+    }
+
+    pub fn env(mut self, pid: u32, vars: &[(&str, &str)]) -> Self {
+        let vars = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        self.envs.insert(pid, vars);
+        self
+    }
+
+    /// Процессы из `spawn` будут слушать эти TCP-порты
+    pub fn respawn_on(mut self, ports: &[u16]) -> Self {
+        self.respawn_ports = ports.to_vec();
+        self
+    }
+
+    /// Дочерний процесс `pid` у `ppid`
+    pub fn child(mut self, ppid: u32, pid: u32, name: &str) -> Self {
+        self.parents.insert(pid, ppid);
+        self.process(pid, name)
+    }
+
+    /// Время запуска процесса — для проверки переиспользованных PID
+    pub fn started_at(mut self, pid: u32, time: u64) -> Self {
+        self.started.insert(pid, time);
+        self
+    }
+
+    /// Игнорирует SIGTERM / WM_CLOSE
+    pub fn ignore_graceful(mut self, pid: u32) -> Self {
+        self.stubborn.insert(pid);
+        self
+    }
+
+    /// Сигналы отклоняются с AccessDenied
+    pub fn protect(mut self, pid: u32) -> Self {
+        self.protected.insert(pid);
+        self
+    }
+}
+
+#[cfg(test)]
+impl SystemProbe for FakeProbe {
+    fn listeners(&self) -> HashMap<u32, HashSet<Listener>> {
+        self.port_scans.set(self.port_scans.get() + 1);
+        let exited = self.exited.borrow();
+        self.listeners
+            .iter()
+            .chain(self.respawned.borrow().iter())
+            .filter(|(pid, _)| !exited.contains(pid))
+            .map(|(pid, l)| (*pid, l.clone()))
+            .collect()
+    }
+
+    fn process_names(&self) -> HashMap<u32, String> {
+        let exited = self.exited.borrow();
+        self.names
+            .iter()
+            .filter(|(pid, _)| !exited.contains(pid))
+            .map(|(pid, n)| (*pid, n.clone()))
+            .collect()
+    }
+
+    fn uptimes(&self, pids: &[u32]) -> HashMap<u32, u64> {
+        pick(&self.uptimes, pids)
+    }
+
+    fn cwds(&self, pids: &[u32]) -> HashMap<u32, String> {
+        pick(&self.cwds, pids)
+    }
+
+    fn command_lines(&self, pids: &[u32]) -> HashMap<u32, Vec<String>> {
+        pick(&self.command_lines, pids)
+    }
+
+    fn executable(&self, _pid: u32) -> Option<String> {
+        None
+    }
+
+    fn environment(&self, pid: u32) -> Vec<(String, String)> {
+        self.envs.get(&pid).cloned().unwrap_or_default()
+    }
+
+    fn spawn(&self, spec: &LaunchSpec) -> Result<u32, String> {
+        let mut spawned = self.spawned.borrow_mut();
+        spawned.push(spec.clone());
+        let pid = 1000 + spawned.len() as u32;
+        let listeners = self
+            .respawn_ports
+            .iter()
+            .map(|port| Listener {
+                protocol: Protocol::Tcp,
+                addr: ([0, 0, 0, 0], *port).into(),
+            })
+            .collect();
+        self.respawned.borrow_mut().insert(pid, listeners);
+        Ok(pid)
+    }
+
+    fn parent_pids(&self) -> HashMap<u32, ProcessParent> {
+        // Как снимок ОС: все живые процессы, корни — с PPID 0
+        self.process_names()
+            .into_keys()
+            .map(|pid| {
+                let parent = ProcessParent {
+                    ppid: self.parents.get(&pid).copied().unwrap_or(0),
+                    started: self.started.get(&pid).copied(),
+                };
+                (pid, parent)
+            })
+            .collect()
+    }
+
+    fn signal(&self, pid: u32, signal: Signal) -> Result<(), SignalError> {
+        if !self.is_alive(pid) {
+            return Err(SignalError::NotFound);
+        }
+        self.signals.borrow_mut().push((pid, signal));
+        if self.protected.contains(&pid) {
+            return Err(SignalError::AccessDenied);
+        }
+        if signal == Signal::Force || !self.stubborn.contains(&pid) {
+            self.exited.borrow_mut().insert(pid);
+        }
+        Ok(())
+    }
+
+    fn is_alive(&self, pid: u32) -> bool {
+        self.names.contains_key(&pid) && !self.exited.borrow().contains(&pid)
+    }
+}
+
+/// Как и нативные батч-вызовы — отдаём только запрошенные PIDs
+#[cfg(test)]
+fn pick<V: Clone>(map: &HashMap<u32, V>, pids: &[u32]) -> HashMap<u32, V> {
+    pids.iter()
+        .filter_map(|pid| map.get(pid).map(|v| (*pid, v.clone())))
+        .collect()
+}
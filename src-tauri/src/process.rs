@@ -1,17 +1,382 @@
+//! Остановка процессов: сначала мягкий сигнал (WM_CLOSE / CTRL_BREAK на Windows,
+//! SIGTERM на Unix), ждём освобождения портов, по таймауту — принудительно.
+
+use serde::Serialize;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::probe::SystemProbe;
+
+#[cfg(windows)]
+use std::ffi::c_void;
+#[cfg(windows)]
 use std::os::windows::process::CommandExt;
+#[cfg(windows)]
 use std::process::Command;
 
+#[cfg(windows)]
 const CREATE_NO_WINDOW: u32 = 0x08000000;
 
-/// Убить процесс по PID
-pub fn kill_process(pid: u32) -> bool {
-    let output = Command::new("taskkill")
-        .args(["/PID", &pid.to_string(), "/F"])
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Signal {
+    /// Дать процессу завершиться самому (обработчики очистки отработают)
+    Graceful,
+    /// Завершить немедленно (taskkill /F, SIGKILL)
+    Force,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignalError {
+    NotFound,
+    AccessDenied,
+    /// Сигнал не доставлен: например, у консольного процесса нет окна для WM_CLOSE
+    Failed,
+}
+
+/// Результат `kill_server`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StopOutcome {
+    /// Завершился сам после мягкого сигнала
+    Graceful,
+    /// Пришлось завершить принудительно
+    Forced,
+    Failed,
+    AccessDenied,
+}
+
+/// Как часто проверять, освободил ли процесс порты
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// Сколько ждать после принудительного завершения
+const FORCE_WAIT: Duration = Duration::from_secs(2);
+
+/// Процесс завершился и его порты освобождены. Сначала дешёвая проверка
+/// жив ли он: скан портов (netstat на Windows) — только после выхода.
+fn released(probe: &dyn SystemProbe, pid: u32) -> bool {
+    !probe.is_alive(pid) && !probe.listeners().contains_key(&pid)
+}
+
+/// Ждать, пока `done` не станет true, не дольше `timeout`
+fn wait_until(timeout: Duration, done: impl Fn() -> bool) -> bool {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if done() {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+fn wait_released(probe: &dyn SystemProbe, pid: u32, timeout: Duration) -> bool {
+    wait_until(timeout, || released(probe, pid))
+}
+
+/// Мягкий сигнал → ждём до `timeout` → принудительное завершение
+pub fn stop(probe: &dyn SystemProbe, pid: u32, timeout: Duration) -> StopOutcome {
+    match probe.signal(pid, Signal::Graceful) {
+        Ok(()) if wait_released(probe, pid, timeout) => return StopOutcome::Graceful,
+        // Исчез между сканом и сигналом — цель достигнута
+        Err(SignalError::NotFound) => return StopOutcome::Graceful,
+        Err(SignalError::AccessDenied) => return StopOutcome::AccessDenied,
+        // Не успел или мягкий сигнал не поддерживается — эскалация
+        Ok(()) | Err(SignalError::Failed) => {}
+    }
+
+    match probe.signal(pid, Signal::Force) {
+        Ok(()) if wait_released(probe, pid, FORCE_WAIT) => StopOutcome::Forced,
+        Ok(()) => StopOutcome::Failed,
+        Err(SignalError::NotFound) => StopOutcome::Forced,
+        Err(SignalError::AccessDenied) => StopOutcome::AccessDenied,
+        Err(SignalError::Failed) => StopOutcome::Failed,
+    }
+}
+
+/// Остановить дерево процессов (`pids` — корень первым).
+/// Мягкий сигнал всем, начиная с листьев; ждём, пока завершатся все,
+/// а не только держатели портов — иначе watcher вроде nodemon перезапустит ребёнка.
+pub fn stop_tree(probe: &dyn SystemProbe, pids: &[u32], timeout: Duration) -> StopOutcome {
+    let mut denied = false;
+    let mut pending: Vec<u32> = Vec::new();
+    for &pid in pids.iter().rev() {
+        match probe.signal(pid, Signal::Graceful) {
+            Ok(()) | Err(SignalError::Failed) => pending.push(pid),
+            Err(SignalError::NotFound) => {}
+            Err(SignalError::AccessDenied) => denied = true,
+        }
+    }
+    let all_exited = || pending.iter().all(|&pid| !probe.is_alive(pid));
+    if wait_until(timeout, all_exited) {
+        return if denied {
+            StopOutcome::AccessDenied
+        } else {
+            StopOutcome::Graceful
+        };
+    }
+
+    let mut failed = false;
+    for &pid in pending.iter().filter(|&&pid| probe.is_alive(pid)) {
+        match probe.signal(pid, Signal::Force) {
+            Ok(()) | Err(SignalError::NotFound) => {}
+            Err(SignalError::AccessDenied) => denied = true,
+            Err(SignalError::Failed) => failed = true,
+        }
+    }
+    let all_gone = wait_until(FORCE_WAIT, all_exited);
+    if denied {
+        StopOutcome::AccessDenied
+    } else if failed || !all_gone {
+        StopOutcome::Failed
+    } else {
+        StopOutcome::Forced
+    }
+}
+
+#[cfg(windows)]
+#[link(name = "kernel32")]
+extern "system" {
+    fn OpenProcess(access: u32, inherit: i32, pid: u32) -> *mut c_void;
+    fn TerminateProcess(process: *mut c_void, exit_code: u32) -> i32;
+    fn GetExitCodeProcess(process: *mut c_void, exit_code: *mut u32) -> i32;
+    fn CloseHandle(handle: *mut c_void) -> i32;
+    fn GetLastError() -> u32;
+    fn AttachConsole(pid: u32) -> i32;
+    fn FreeConsole() -> i32;
+    fn SetConsoleCtrlHandler(handler: *const c_void, add: i32) -> i32;
+    fn GenerateConsoleCtrlEvent(event: u32, group: u32) -> i32;
+}
+
+#[cfg(windows)]
+const PROCESS_TERMINATE: u32 = 0x0001;
+#[cfg(windows)]
+const PROCESS_QUERY_LIMITED_INFORMATION: u32 = 0x1000;
+#[cfg(windows)]
+const ERROR_ACCESS_DENIED: u32 = 5;
+#[cfg(windows)]
+const ERROR_INVALID_PARAMETER: u32 = 87;
+#[cfg(windows)]
+const STILL_ACTIVE: u32 = 259;
+#[cfg(windows)]
+const CTRL_BREAK_EVENT: u32 = 1;
+
+#[cfg(windows)]
+fn last_error() -> SignalError {
+    // SAFETY: читает thread-local код ошибки
+    match unsafe { GetLastError() } {
+        ERROR_ACCESS_DENIED => SignalError::AccessDenied,
+        ERROR_INVALID_PARAMETER => SignalError::NotFound,
+        _ => SignalError::Failed,
+    }
+}
+
+/// WM_CLOSE окнам процесса (taskkill без /F)
+#[cfg(windows)]
+fn close_windows(pid: u32) -> bool {
+    Command::new("taskkill")
+        .args(["/PID", &pid.to_string()])
         .creation_flags(CREATE_NO_WINDOW)
-        .output();
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+/// Консоль у процесса одна: AttachConsole/FreeConsole из параллельных
+/// остановок (`spawn_blocking`) не должны пересекаться
+#[cfg(windows)]
+static CONSOLE: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+/// CTRL_BREAK группе процессов `pid` — как Ctrl+Break в его терминале, но
+/// только для группы, а не для всей консоли. Группу с этим id имеют процессы,
+/// запущенные с CREATE_NEW_PROCESS_GROUP (проекты и перезапуски LocalDock);
+/// остальные сигнал не получат и будут завершены принудительно по таймауту.
+#[cfg(windows)]
+fn console_break(pid: u32) -> Result<(), SignalError> {
+    let _console = CONSOLE.lock().unwrap_or_else(|e| e.into_inner());
+    // SAFETY: своей консоли у GUI-приложения нет; подключаемся к чужой,
+    // игнорируем событие сами и отключаемся
+    unsafe {
+        if AttachConsole(pid) == 0 {
+            return Err(last_error());
+        }
+        SetConsoleCtrlHandler(std::ptr::null(), 1);
+        let sent = GenerateConsoleCtrlEvent(CTRL_BREAK_EVENT, pid) != 0;
+        FreeConsole();
+        SetConsoleCtrlHandler(std::ptr::null(), 0);
+        if sent {
+            Ok(())
+        } else {
+            Err(SignalError::Failed)
+        }
+    }
+}
+
+#[cfg(windows)]
+fn terminate(pid: u32) -> Result<(), SignalError> {
+    // SAFETY: handle проверяется на null и закрывается
+    unsafe {
+        let handle = OpenProcess(PROCESS_TERMINATE, 0, pid);
+        if handle.is_null() {
+            return Err(last_error());
+        }
+        let ok = TerminateProcess(handle, 1) != 0;
+        let result = if ok { Ok(()) } else { Err(last_error()) };
+        CloseHandle(handle);
+        result
+    }
+}
+
+#[cfg(windows)]
+pub fn send_signal(pid: u32, signal: Signal) -> Result<(), SignalError> {
+    match signal {
+        Signal::Graceful if close_windows(pid) => Ok(()),
+        Signal::Graceful => console_break(pid),
+        Signal::Force => terminate(pid),
+    }
+}
+
+#[cfg(windows)]
+pub fn is_alive(pid: u32) -> bool {
+    // SAFETY: handle проверяется на null и закрывается
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if handle.is_null() {
+            // Нет доступа — процесс существует
+            return GetLastError() == ERROR_ACCESS_DENIED;
+        }
+        let mut code = 0u32;
+        let ok = GetExitCodeProcess(handle, &mut code) != 0;
+        CloseHandle(handle);
+        ok && code == STILL_ACTIVE
+    }
+}
+
+#[cfg(unix)]
+fn errno() -> Option<i32> {
+    std::io::Error::last_os_error().raw_os_error()
+}
+
+/// SIGTERM / SIGKILL
+#[cfg(unix)]
+pub fn send_signal(pid: u32, signal: Signal) -> Result<(), SignalError> {
+    let pid = match i32::try_from(pid) {
+        Ok(p) if p > 0 => p,
+        _ => return Err(SignalError::NotFound),
+    };
+    let sig = match signal {
+        Signal::Graceful => libc::SIGTERM,
+        Signal::Force => libc::SIGKILL,
+    };
+    // SAFETY: kill() не трогает память процесса, только шлёт сигнал
+    if unsafe { libc::kill(pid, sig) } == 0 {
+        return Ok(());
+    }
+    match errno() {
+        Some(libc::ESRCH) => Err(SignalError::NotFound),
+        Some(libc::EPERM) => Err(SignalError::AccessDenied),
+        _ => Err(SignalError::Failed),
+    }
+}
+
+#[cfg(unix)]
+pub fn is_alive(pid: u32) -> bool {
+    let pid = match i32::try_from(pid) {
+        Ok(p) if p > 0 => p,
+        _ => return false,
+    };
+    // SAFETY: сигнал 0 только проверяет существование процесса
+    let exists = unsafe { libc::kill(pid, 0) == 0 || errno() == Some(libc::EPERM) };
+    // Свой дочерний процесс (оболочка проекта) после выхода остаётся зомби,
+    // пока Supervisor его не заберёт, — он уже завершился
+    #[cfg(target_os = "linux")]
+    let exists = exists && !crate::scanner::is_zombie(pid as u32);
+    exists
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::probe::FakeProbe;
+
+    #[test]
+    fn test_stop_graceful() {
+        let probe = FakeProbe::default().server(1, "node", &[3000]);
+        assert_eq!(
+            stop(&probe, 1, Duration::from_secs(5)),
+            StopOutcome::Graceful
+        );
+        assert_eq!(*probe.signals.borrow(), vec![(1, Signal::Graceful)]);
+    }
+
+    #[test]
+    fn test_stop_escalates_after_timeout() {
+        let probe = FakeProbe::default()
+            .server(1, "postgres", &[5432])
+            .ignore_graceful(1);
+        assert_eq!(stop(&probe, 1, Duration::ZERO), StopOutcome::Forced);
+        assert_eq!(
+            *probe.signals.borrow(),
+            vec![(1, Signal::Graceful), (1, Signal::Force)]
+        );
+    }
+
+    #[test]
+    fn test_stop_scans_ports_only_after_exit() {
+        // Пока процесс жив, netstat не запускаем — только после выхода
+        let probe = FakeProbe::default()
+            .server(1, "postgres", &[5432])
+            .ignore_graceful(1);
+        assert_eq!(
+            stop(&probe, 1, Duration::from_millis(300)),
+            StopOutcome::Forced
+        );
+        assert_eq!(probe.port_scans.get(), 1);
+    }
+
+    #[test]
+    fn test_stop_access_denied() {
+        let probe = FakeProbe::default()
+            .server(4, "svchost", &[5040])
+            .protect(4);
+        assert_eq!(
+            stop(&probe, 4, Duration::from_secs(5)),
+            StopOutcome::AccessDenied
+        );
+        // Без прав эскалация бессмысленна
+        assert_eq!(probe.signals.borrow().len(), 1);
+    }
+
+    #[test]
+    fn test_stop_tree_leaves_first_and_escalates() {
+        // npm(1) → node(2, порт) → esbuild(3) игнорирует SIGTERM
+        let probe = FakeProbe::default()
+            .process(1, "npm")
+            .child(1, 2, "node")
+            .listen(2, "0.0.0.0:5173")
+            .child(2, 3, "esbuild")
+            .ignore_graceful(3);
+        assert_eq!(
+            stop_tree(&probe, &[1, 2, 3], Duration::ZERO),
+            StopOutcome::Forced
+        );
+        assert_eq!(
+            *probe.signals.borrow(),
+            vec![
+                (3, Signal::Graceful),
+                (2, Signal::Graceful),
+                (1, Signal::Graceful),
+                (3, Signal::Force),
+            ]
+        );
+    }
 
-    match output {
-        Ok(o) => o.status.success(),
-        Err(_) => false,
+    #[test]
+    fn test_stop_already_gone() {
+        let probe = FakeProbe::default();
+        assert_eq!(
+            stop(&probe, 42, Duration::from_secs(5)),
+            StopOutcome::Graceful
+        );
     }
 }
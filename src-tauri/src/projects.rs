@@ -0,0 +1,689 @@
+//! Управляемые проекты: сохранённый список папок с командой запуска,
+//! ожидаемым портом и переменными окружения (`projects.json` в конфиг-директории).
+//! `Supervisor` запускает команду, держит дочерний процесс, перезапускает его
+//! по политике (`supervise.rs`) и связывает найденные сканером серверы с проектом.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::detector;
+use crate::logs::LogStream;
+use crate::restart;
+use crate::supervise::{RestartEvent, RestartPolicy, Supervision};
+use crate::tree::ProcessTree;
+use crate::Server;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Project {
+    pub id: String,
+    pub name: String,
+    pub path: String,
+    /// Команда для оболочки (`sh -c` / `cmd /C`), например "npm run dev"
+    pub command: String,
+    /// Порт, который сервер должен открыть
+    pub port: Option<u16>,
+    pub env: BTreeMap<String, String>,
+    pub restart: RestartPolicy,
+    /// Путь HTTP-проверки здоровья; None — "/"
+    pub health_path: Option<String>,
+}
+
+/// Проект + его запущенный процесс, для UI
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProjectState {
+    #[serde(flatten)]
+    pub project: Project,
+    pub pid: Option<u32>,
+    /// PID последнего запуска, чей вывод есть в `LogStore` (в том числе упавшего)
+    pub log_pid: Option<u32>,
+    /// Последние перезапуски по политике, старые первыми
+    pub restarts: Vec<RestartEvent>,
+    /// Процесс вышел, перезапуск запланирован
+    pub restart_pending: bool,
+}
+
+/// npm / pnpm / yarn / bun — по lock-файлу
+fn package_manager(path: &Path) -> &'static str {
+    if path.join("pnpm-lock.yaml").exists() {
+        "pnpm"
+    } else if path.join("yarn.lock").exists() {
+        "yarn"
+    } else if path.join("bun.lockb").exists() || path.join("bun.lock").exists() {
+        "bun"
+    } else {
+        "npm"
+    }
+}
+
+/// Скрипты из package.json
+fn npm_scripts(path: &Path) -> Vec<String> {
+    fs::read_to_string(path.join("package.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .and_then(|json| {
+            json.get("scripts")
+                .and_then(|s| s.as_object())
+                .map(|s| s.keys().cloned().collect())
+        })
+        .unwrap_or_default()
+}
+
+/// Команда запуска по умолчанию: по фреймворку из `detect_framework`
+pub fn default_command(path: &str) -> Option<String> {
+    let dir = Path::new(path);
+    match detector::detect_framework(path).as_str() {
+        "Django" => Some("python manage.py runserver".to_string()),
+        "FastAPI" => Some("fastapi dev".to_string()),
+        "Flask" => Some("flask run".to_string()),
+        "Jupyter" => Some("jupyter lab".to_string()),
+        "Go" => Some("go run .".to_string()),
+        "Rust" | "Axum" | "Actix" | "Rocket" => Some("cargo run".to_string()),
+        "Leptos" => Some("cargo leptos watch".to_string()),
+        "Rails" => Some("ruby bin/rails server".to_string()),
+        "Laravel" => Some("php artisan serve".to_string()),
+        "Symfony" => Some("symfony server:start".to_string()),
+        "Spring Boot" if dir.join("pom.xml").exists() => Some("mvn spring-boot:run".to_string()),
+        "Spring Boot" => Some("gradle bootRun".to_string()),
+        "ASP.NET" | ".NET" => Some("dotnet run".to_string()),
+        "Phoenix" => Some("mix phx.server".to_string()),
+        // Точка входа — файл, который не угадать
+        "Python" | "Streamlit" | "Uvicorn" | "Gunicorn" | "Sinatra" | "Ruby" | "PHP" | "Java"
+        | "Elixir" | "Deno" | "Unknown" => None,
+        // Всё остальное — из package.json
+        _ => {
+            let scripts = npm_scripts(dir);
+            let pm = package_manager(dir);
+            if scripts.iter().any(|s| s == "dev") {
+                Some(format!("{} run dev", pm))
+            } else if scripts.iter().any(|s| s == "start") {
+                Some(format!("{} start", pm))
+            } else {
+                None
+            }
+        }
+    }
+}
+
+/// Порт по умолчанию у dev-сервера фреймворка
+pub fn default_port(framework: &str) -> Option<u16> {
+    match framework {
+        "Vite" | "Svelte" => Some(5173),
+        "Next.js" | "Nuxt" | "CRA" | "Express" => Some(3000),
+        "Astro" => Some(4321),
+        "Leptos" | "Rails" => Some(3000),
+        "Django" | "FastAPI" | "Uvicorn" | "Gunicorn" | "Laravel" | "Symfony" | "Rocket"
+        | "Deno" => Some(8000),
+        "Flask" => Some(5000),
+        "Streamlit" => Some(8501),
+        "Jupyter" => Some(8888),
+        "Phoenix" => Some(4000),
+        "Sinatra" => Some(4567),
+        "Spring Boot" => Some(8080),
+        _ => None,
+    }
+}
+
+/// Новый проект для папки: имя, команда и порт — по умолчанию
+pub fn new_project(path: &str) -> Result<Project, String> {
+    if !Path::new(path).is_dir() {
+        return Err(format!("Папку не знайдено: {}", path));
+    }
+    Ok(Project {
+        id: String::new(),
+        name: detector::detect_project_name(path),
+        path: path.to_string(),
+        command: default_command(path).unwrap_or_default(),
+        port: default_port(&detector::detect_framework(path)),
+        env: BTreeMap::new(),
+        restart: RestartPolicy::default(),
+        health_path: None,
+    })
+}
+
+/// "My App" → "my-app"; пустое имя — "project"
+fn slug(name: &str) -> String {
+    let slug = name
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() {
+        "project".to_string()
+    } else {
+        slug
+    }
+}
+
+/// Список проектов в managed state + путь к `projects.json`
+pub struct ProjectStore {
+    path: PathBuf,
+    projects: Mutex<Vec<Project>>,
+}
+
+impl ProjectStore {
+    /// Нет файла или он битый — пустой список
+    pub fn open(path: PathBuf) -> ProjectStore {
+        let projects = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        ProjectStore {
+            path,
+            projects: Mutex::new(projects),
+        }
+    }
+
+    pub fn list(&self) -> Vec<Project> {
+        self.projects.lock().map(|p| p.clone()).unwrap_or_default()
+    }
+
+    pub fn get(&self, id: &str) -> Option<Project> {
+        self.list().into_iter().find(|p| p.id == id)
+    }
+
+    fn save(&self, projects: &[Project]) -> Result<(), String> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let json = serde_json::to_string_pretty(projects).map_err(|e| e.to_string())?;
+        fs::write(&self.path, json).map_err(|e| e.to_string())
+    }
+
+    /// Добавить (пустой id) или заменить проект, вернуть сохранённый
+    pub fn upsert(&self, mut project: Project) -> Result<Project, String> {
+        let mut projects = self.projects.lock().map_err(|e| e.to_string())?;
+        let mut updated = projects.clone();
+        match updated
+            .iter_mut()
+            .find(|p| !project.id.is_empty() && p.id == project.id)
+        {
+            Some(existing) => *existing = project.clone(),
+            None => {
+                let base = slug(&project.name);
+                let mut id = base.clone();
+                let mut n = 2;
+                while updated.iter().any(|p| p.id == id) {
+                    id = format!("{}-{}", base, n);
+                    n += 1;
+                }
+                project.id = id;
+                updated.push(project.clone());
+            }
+        }
+        self.save(&updated)?;
+        *projects = updated;
+        Ok(project)
+    }
+
+    pub fn remove(&self, id: &str) -> Result<(), String> {
+        let mut projects = self.projects.lock().map_err(|e| e.to_string())?;
+        let updated: Vec<Project> = projects.iter().filter(|p| p.id != id).cloned().collect();
+        self.save(&updated)?;
+        *projects = updated;
+        Ok(())
+    }
+}
+
+/// Команда проекта через оболочку: так работают `npm.cmd`, пайпы и `&&`
+fn shell_command(project: &Project) -> Command {
+    #[cfg(windows)]
+    let mut cmd = {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", &project.command]);
+        cmd
+    };
+    #[cfg(not(windows))]
+    let mut cmd = {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", &project.command]);
+        cmd
+    };
+    cmd.current_dir(&project.path)
+        .envs(&project.env)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    restart::detach(&mut cmd);
+    cmd
+}
+
+/// Запущенный проект: PID оболочки и её вывод — читать до EOF, иначе процесс
+/// упрётся в заполненный пайп
+pub struct Started {
+    pub pid: u32,
+    pub output: Vec<(LogStream, Box<dyn Read + Send>)>,
+}
+
+#[derive(Default)]
+struct Inner {
+    children: HashMap<String, Child>,
+    states: HashMap<String, Supervision>,
+}
+
+/// Запущенные из LocalDock проекты: id → дочерний процесс (оболочка)
+/// и состояние надзора за ним
+#[derive(Default)]
+pub struct Supervisor {
+    inner: Mutex<Inner>,
+}
+
+impl Supervisor {
+    pub fn start(&self, project: &Project) -> Result<Started, String> {
+        if project.command.trim().is_empty() {
+            return Err("Не задано команду запуску".to_string());
+        }
+        let mut inner = self.inner.lock().map_err(|e| e.to_string())?;
+        if let Some(child) = inner.children.get(&project.id) {
+            return Err(format!("Вже запущено (PID {})", child.id()));
+        }
+        let mut child = shell_command(project).spawn().map_err(|e| e.to_string())?;
+        let mut output: Vec<(LogStream, Box<dyn Read + Send>)> = Vec::new();
+        if let Some(stdout) = child.stdout.take() {
+            output.push((LogStream::Stdout, Box::new(stdout)));
+        }
+        if let Some(stderr) = child.stderr.take() {
+            output.push((LogStream::Stderr, Box::new(stderr)));
+        }
+        let pid = child.id();
+        inner.children.insert(project.id.clone(), child);
+        inner
+            .states
+            .entry(project.id.clone())
+            .or_default()
+            .started(Instant::now());
+        Ok(Started { pid, output })
+    }
+
+    /// «Стоп» пользователя: выход не перезапускать, расписание отменить.
+    /// true — был запланирован перезапуск.
+    pub fn stop_requested(&self, id: &str) -> bool {
+        self.inner
+            .lock()
+            .ok()
+            .and_then(|mut inner| inner.states.get_mut(id).map(Supervision::stop_requested))
+            .unwrap_or(false)
+    }
+
+    pub fn pid(&self, id: &str) -> Option<u32> {
+        self.inner.lock().ok()?.children.get(id).map(Child::id)
+    }
+
+    /// (id, PID) запущенных проектов
+    pub fn running(&self) -> Vec<(String, u32)> {
+        self.inner
+            .lock()
+            .map(|inner| {
+                inner
+                    .children
+                    .iter()
+                    .map(|(id, child)| (id.clone(), child.id()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Перезапуски проекта и запланирован ли следующий
+    pub fn restarts(&self, id: &str) -> (Vec<RestartEvent>, bool) {
+        self.inner
+            .lock()
+            .ok()
+            .and_then(|inner| inner.states.get(id).map(|s| (s.events(), s.pending())))
+            .unwrap_or_default()
+    }
+
+    /// Забрать завершившиеся процессы и запланировать перезапуски по политике;
+    /// вернуть id проектов, которые завершились
+    pub fn reap(&self, projects: &[Project], now: Instant) -> Vec<String> {
+        let Ok(mut inner) = self.inner.lock() else {
+            return Vec::new();
+        };
+        let exited: Vec<(String, Option<i32>)> = inner
+            .children
+            .iter_mut()
+            .filter_map(|(id, child)| match child.try_wait() {
+                Ok(None) => None,
+                Ok(Some(status)) => Some((id.clone(), status.code())),
+                Err(_) => Some((id.clone(), None)),
+            })
+            .collect();
+        for (id, code) in &exited {
+            inner.children.remove(id);
+            let policy = policy_of(projects, id);
+            inner
+                .states
+                .entry(id.clone())
+                .or_default()
+                .exited(&policy, *code, now);
+        }
+        exited.into_iter().map(|(id, _)| id).collect()
+    }
+
+    /// PID запущенных проектов, чей ожидаемый порт молчит дольше таймаута.
+    /// `servers` — уже связанные с проектами (`link`).
+    pub fn unhealthy(&self, projects: &[Project], servers: &[Server], now: Instant) -> Vec<u32> {
+        let Ok(mut inner) = self.inner.lock() else {
+            return Vec::new();
+        };
+        let Inner { children, states } = &mut *inner;
+        let mut result = Vec::new();
+        for (id, child) in children.iter() {
+            let Some(project) = projects.iter().find(|p| p.id == *id) else {
+                continue;
+            };
+            let Some(port) = project.port else {
+                continue;
+            };
+            let listening = servers
+                .iter()
+                .any(|s| s.port == port && s.project_id.as_deref() == Some(id));
+            let state = states.entry(id.clone()).or_default();
+            if state.check_health(&project.restart, listening, now) {
+                result.push(child.id());
+            }
+        }
+        result
+    }
+
+    /// Перезапуск не смог запустить команду — как выход с ошибкой:
+    /// следующая попытка с большей задержкой, пока не исчерпан лимит
+    pub fn spawn_failed(&self, project: &Project, now: Instant) {
+        if let Ok(mut inner) = self.inner.lock() {
+            inner
+                .states
+                .entry(project.id.clone())
+                .or_default()
+                .exited(&project.restart, None, now);
+        }
+    }
+
+    /// Проекты, которым пора перезапуститься
+    pub fn take_due(&self, now: Instant) -> Vec<String> {
+        let Ok(mut inner) = self.inner.lock() else {
+            return Vec::new();
+        };
+        inner
+            .states
+            .iter_mut()
+            .filter_map(|(id, state)| state.take_due(now).then(|| id.clone()))
+            .collect()
+    }
+}
+
+/// Удалённый проект не перезапускаем
+fn policy_of(projects: &[Project], id: &str) -> RestartPolicy {
+    projects
+        .iter()
+        .find(|p| p.id == id)
+        .map(|p| p.restart.clone())
+        .unwrap_or_default()
+}
+
+/// Пометить серверы проектов: PID из дерева запущенного процесса,
+/// а если дерево не нашло (процесс отвязался) — ожидаемый порт, но только
+/// у сервера из папки проекта: чужой процесс на том же порту не наш
+pub fn link(
+    servers: &mut [Server],
+    projects: &[Project],
+    running: &[(String, u32)],
+    tree: &ProcessTree,
+) {
+    for (id, pid) in running {
+        let members: HashSet<u32> = tree.subtree(*pid).into_iter().collect();
+        let Some(project) = projects.iter().find(|p| p.id == *id) else {
+            continue;
+        };
+        for server in servers.iter_mut() {
+            if members.contains(&server.pid) {
+                server.project_id = Some(id.clone());
+            }
+        }
+        if !servers.iter().any(|s| s.project_id.as_deref() == Some(id)) {
+            let by_port = servers.iter_mut().filter(|s| {
+                Some(s.port) == project.port
+                    && s.project_root
+                        .as_deref()
+                        .is_some_and(|root| is_within(root, &project.path))
+            });
+            for server in by_port {
+                server.project_id.get_or_insert_with(|| id.clone());
+            }
+        }
+    }
+}
+
+/// `path` — это `dir` или папка внутри него (на Windows без учёта регистра)
+fn is_within(path: &str, dir: &str) -> bool {
+    if cfg!(windows) {
+        Path::new(&path.to_lowercase()).starts_with(dir.to_lowercase())
+    } else {
+        Path::new(path).starts_with(dir)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project_dir(files: &[(&str, &str)]) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        for (name, content) in files {
+            fs::write(dir.path().join(name), content).unwrap();
+        }
+        dir
+    }
+
+    fn command_for(files: &[(&str, &str)]) -> Option<String> {
+        let dir = project_dir(files);
+        default_command(dir.path().to_str().unwrap())
+    }
+
+    #[test]
+    fn test_default_command_by_framework() {
+        let vite = r#"{ "scripts": { "dev": "vite" }, "devDependencies": { "vite": "5" } }"#;
+        assert_eq!(
+            command_for(&[("package.json", vite)]).as_deref(),
+            Some("npm run dev")
+        );
+        assert_eq!(
+            command_for(&[("package.json", vite), ("pnpm-lock.yaml", "")]).as_deref(),
+            Some("pnpm run dev")
+        );
+        let cra = r#"{ "scripts": { "start": "react-scripts start" }, "dependencies": { "react-scripts": "5" } }"#;
+        assert_eq!(
+            command_for(&[("package.json", cra), ("yarn.lock", "")]).as_deref(),
+            Some("yarn start")
+        );
+        assert_eq!(
+            command_for(&[("requirements.txt", "django"), ("manage.py", "")]).as_deref(),
+            Some("python manage.py runserver")
+        );
+        assert_eq!(
+            command_for(&[("Cargo.toml", "[package]")]).as_deref(),
+            Some("cargo run")
+        );
+        assert_eq!(
+            command_for(&[("Cargo.toml", "[dependencies]\naxum = \"0.7\"\n")]).as_deref(),
+            Some("cargo run")
+        );
+        assert_eq!(
+            command_for(&[("Gemfile", "gem 'rails'\n")]).as_deref(),
+            Some("ruby bin/rails server")
+        );
+        assert_eq!(
+            command_for(&[(
+                "build.gradle",
+                "id 'org.springframework.boot' version '3.2.0'\n"
+            )])
+            .as_deref(),
+            Some("gradle bootRun")
+        );
+        assert_eq!(command_for(&[("requirements.txt", "streamlit")]), None);
+        assert_eq!(command_for(&[("package.json", "{}")]), None);
+        assert_eq!(command_for(&[]), None);
+    }
+
+    #[test]
+    fn test_new_project_defaults() {
+        let dir = project_dir(&[(
+            "package.json",
+            r#"{ "name": "shop", "scripts": { "dev": "next dev" }, "dependencies": { "next": "14" } }"#,
+        )]);
+        let project = new_project(dir.path().to_str().unwrap()).unwrap();
+        assert_eq!(project.name, "shop");
+        assert_eq!(project.command, "npm run dev");
+        assert_eq!(project.port, Some(3000));
+        assert!(new_project("/definitely/missing/dir").is_err());
+    }
+
+    #[test]
+    fn test_store_assigns_unique_ids_and_persists() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("projects.json");
+        let store = ProjectStore::open(path.clone());
+        let project = |name: &str| Project {
+            name: name.to_string(),
+            ..Project::default()
+        };
+
+        let a = store.upsert(project("My Shop")).unwrap();
+        let b = store.upsert(project("my shop")).unwrap();
+        assert_eq!(a.id, "my-shop");
+        assert_eq!(b.id, "my-shop-2");
+
+        store
+            .upsert(Project {
+                port: Some(5173),
+                ..a.clone()
+            })
+            .unwrap();
+        store.remove(&b.id).unwrap();
+
+        let reopened = ProjectStore::open(path);
+        assert_eq!(reopened.list().len(), 1);
+        assert_eq!(reopened.get("my-shop").unwrap().port, Some(5173));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_start_pipes_output_and_reaps() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = Project {
+            id: "echo".to_string(),
+            path: dir.path().to_str().unwrap().to_string(),
+            command: "echo \"$GREETING\"; echo oops >&2".to_string(),
+            env: BTreeMap::from([("GREETING".to_string(), "hi".to_string())]),
+            ..Project::default()
+        };
+        let supervisor = Supervisor::default();
+        let started = supervisor.start(&project).unwrap();
+        assert_eq!(supervisor.pid("echo"), Some(started.pid));
+        assert!(supervisor.start(&project).is_err());
+
+        let mut output = Vec::new();
+        for (stream, mut reader) in started.output {
+            let mut text = String::new();
+            reader.read_to_string(&mut text).unwrap();
+            output.push((stream, text));
+        }
+        assert_eq!(
+            output,
+            vec![
+                (LogStream::Stdout, "hi\n".to_string()),
+                (LogStream::Stderr, "oops\n".to_string())
+            ]
+        );
+
+        // EOF — оболочка закрыла вывод; дождаться выхода и забрать
+        let deadline = Instant::now() + std::time::Duration::from_secs(5);
+        while supervisor.reap(&[], Instant::now()).is_empty() && Instant::now() < deadline {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        assert_eq!(supervisor.pid("echo"), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_stop_running_project_without_waiting_timeout() {
+        use crate::probe::{self, SystemProbe};
+        use crate::process::{self, StopOutcome};
+        use std::time::Duration;
+
+        let dir = tempfile::tempdir().unwrap();
+        let project = Project {
+            id: "sleepy".to_string(),
+            path: dir.path().to_str().unwrap().to_string(),
+            command: "sleep 30".to_string(),
+            ..Project::default()
+        };
+        let supervisor = Supervisor::default();
+        let pid = supervisor.start(&project).unwrap().pid;
+
+        // Как stop_project: дерево оболочки; после SIGTERM она — наш незабранный зомби
+        let started = Instant::now();
+        let targets = ProcessTree::new(probe::NATIVE.parent_pids()).subtree(pid);
+        let outcome = process::stop_tree(&probe::NATIVE, &targets, Duration::from_secs(5));
+        assert_eq!(outcome, StopOutcome::Graceful);
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert_eq!(
+            supervisor.reap(&[], Instant::now()),
+            vec!["sleepy".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_link_by_tree_then_by_port() {
+        let projects = vec![
+            Project {
+                id: "web".to_string(),
+                port: Some(5173),
+                ..Project::default()
+            },
+            Project {
+                id: "api".to_string(),
+                path: "/srv/api".to_string(),
+                port: Some(8000),
+                ..Project::default()
+            },
+            Project {
+                id: "docs".to_string(),
+                path: "/srv/docs".to_string(),
+                port: Some(3000),
+                ..Project::default()
+            },
+        ];
+        // sh(10) → node(11, :5173); у api дерево пустое — сервер найдётся по порту
+        // в папке проекта; порт docs занят чужим процессом
+        let tree = ProcessTree::from_edges(&[(11, 10)]);
+        let mut servers = vec![
+            Server::sample(11, 5173),
+            Server {
+                project_root: Some("/srv/api".to_string()),
+                ..Server::sample(30, 8000)
+            },
+            Server::sample(40, 9000),
+            Server {
+                project_root: Some("/home/u/other".to_string()),
+                ..Server::sample(50, 3000)
+            },
+        ];
+        let running = vec![
+            ("web".to_string(), 10),
+            ("api".to_string(), 20),
+            ("docs".to_string(), 60),
+        ];
+        link(&mut servers, &projects, &running, &tree);
+
+        let ids: Vec<Option<&str>> = servers.iter().map(|s| s.project_id.as_deref()).collect();
+        assert_eq!(ids, vec![Some("web"), Some("api"), None, None]);
+        assert!(is_within("/srv/api/packages/ui", "/srv/api"));
+        assert!(!is_within("/srv/api-old", "/srv/api"));
+    }
+}
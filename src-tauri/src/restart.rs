@@ -0,0 +1,290 @@
+//! Перезапуск dev-серверов: при первом появлении запоминаем, как процесс был
+//! запущен (argv, CWD, часть окружения), `restart_server` останавливает его
+//! и запускает заново в той же директории, ждёт, пока порт снова заслушают.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::probe::SystemProbe;
+use crate::process::StopOutcome;
+use crate::scanner::Protocol;
+use crate::tree::ProcessTree;
+use crate::Server;
+
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+
+#[cfg(windows)]
+const CREATE_NEW_PROCESS_GROUP: u32 = 0x0000_0200;
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// Сколько ждать, пока перезапущенный сервер снова откроет порт
+pub const START_TIMEOUT: Duration = Duration::from_secs(30);
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Переменные окружения, которые переносим при перезапуске (остальное — от LocalDock)
+const ENV_KEYS: &[&str] = &[
+    "PATH",
+    "PORT",
+    "HOST",
+    "NODE_ENV",
+    "NODE_OPTIONS",
+    "VIRTUAL_ENV",
+    "CONDA_PREFIX",
+    "PYTHONPATH",
+    "DJANGO_SETTINGS_MODULE",
+    "FLASK_APP",
+    "FLASK_DEBUG",
+    "RAILS_ENV",
+    "RACK_ENV",
+    "APP_ENV",
+];
+const ENV_PREFIXES: &[&str] = &["VITE_", "NEXT_PUBLIC_", "REACT_APP_", "NUXT_"];
+
+/// Как запустить процесс заново
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LaunchSpec {
+    /// argv; argv[0] ищется в PATH, если это не путь
+    pub args: Vec<String>,
+    /// Полный путь к исполняемому файлу — если argv[0] не нашёлся
+    pub executable: Option<String>,
+    pub cwd: String,
+    pub env: Vec<(String, String)>,
+}
+
+/// Оставить только переменные, влияющие на запуск dev-сервера
+/// (имена без учёта регистра: на Windows это "Path")
+pub fn env_subset(vars: Vec<(String, String)>) -> Vec<(String, String)> {
+    vars.into_iter()
+        .filter(|(key, _)| {
+            let upper = key.to_uppercase();
+            ENV_KEYS.contains(&upper.as_str()) || ENV_PREFIXES.iter().any(|p| upper.starts_with(p))
+        })
+        .collect()
+}
+
+/// Снять `LaunchSpec` с работающего процесса; None — ОС не дала argv или CWD
+pub fn capture(probe: &dyn SystemProbe, pid: u32) -> Option<LaunchSpec> {
+    let args = probe.command_lines(&[pid]).remove(&pid)?;
+    let cwd = probe.cwds(&[pid]).remove(&pid)?;
+    if args.is_empty() {
+        return None;
+    }
+    Some(LaunchSpec {
+        args,
+        executable: probe.executable(pid),
+        cwd,
+        env: env_subset(probe.environment(pid)),
+    })
+}
+
+fn command(program: &str, spec: &LaunchSpec) -> Command {
+    let mut cmd = Command::new(program);
+    cmd.args(&spec.args[1..])
+        .current_dir(&spec.cwd)
+        .envs(spec.env.iter().map(|(k, v)| (k, v)))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    detach(&mut cmd);
+    cmd
+}
+
+/// Своя группа процессов: не получит наши сигналы и переживёт закрытие LocalDock
+pub fn detach(cmd: &mut Command) {
+    #[cfg(unix)]
+    cmd.process_group(0);
+    #[cfg(windows)]
+    cmd.creation_flags(CREATE_NEW_PROCESS_GROUP | CREATE_NO_WINDOW);
+}
+
+/// Запустить отвязанно от LocalDock, вернуть PID
+pub fn spawn_detached(spec: &LaunchSpec) -> Result<u32, String> {
+    let program = spec.args.first().ok_or("пустий argv")?;
+    let spawned = match command(program, spec).spawn() {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => match &spec.executable {
+            Some(exe) => command(exe, spec).spawn(),
+            None => Err(e),
+        },
+        other => other,
+    };
+    let mut child = spawned.map_err(|e| e.to_string())?;
+    let pid = child.id();
+    // Забрать код выхода, чтобы не оставлять зомби
+    thread::spawn(move || {
+        let _ = child.wait();
+    });
+    Ok(pid)
+}
+
+/// Результат `restart_server`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum RestartOutcome {
+    /// Порт снова слушается; `pid` — новый владелец порта
+    Restarted {
+        pid: u32,
+    },
+    /// Запустили, но порт не открылся за `START_TIMEOUT`
+    Timeout,
+    /// Нет сохранённой команды запуска (не dev-сервер или ОС не дала argv/CWD)
+    NotRecorded,
+    StopFailed {
+        outcome: StopOutcome,
+    },
+    SpawnFailed {
+        error: String,
+    },
+}
+
+/// Кто из дерева `spawned` слушает TCP `port`, ждать не дольше `timeout`.
+/// Чужой процесс, занявший порт, или уцелевший сосед по IPv4/IPv6 — не наш сервер.
+pub fn wait_for_port(
+    probe: &dyn SystemProbe,
+    spawned: u32,
+    port: u16,
+    timeout: Duration,
+) -> Option<u32> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let owners: Vec<u32> = probe
+            .listeners()
+            .into_iter()
+            .filter(|(_, listeners)| {
+                listeners
+                    .iter()
+                    .any(|l| l.protocol == Protocol::Tcp && l.addr.port() == port)
+            })
+            .map(|(pid, _)| pid)
+            .collect();
+        // Дерево строим, только когда порт кто-то слушает
+        let owner = if owners.is_empty() {
+            None
+        } else {
+            let ours = ProcessTree::new(probe.parent_pids()).subtree(spawned);
+            owners.into_iter().find(|pid| ours.contains(pid))
+        };
+        if owner.is_some() || Instant::now() >= deadline {
+            return owner;
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Команды запуска dev-серверов, по PID владельца порта.
+/// `None` — снять не удалось, повторно не пробуем.
+#[derive(Default)]
+pub struct RestartRegistry {
+    specs: Mutex<HashMap<u32, Option<LaunchSpec>>>,
+}
+
+impl RestartRegistry {
+    /// Запомнить dev-серверы, увиденные впервые; забыть исчезнувшие
+    pub fn record(&self, servers: &[Server], capture: impl Fn(u32) -> Option<LaunchSpec>) {
+        let mut specs = self.specs.lock().unwrap_or_else(|e| e.into_inner());
+        specs.retain(|pid, _| servers.iter().any(|s| s.pid == *pid));
+        for server in servers.iter().filter(|s| s.category == "dev") {
+            specs
+                .entry(server.pid)
+                .or_insert_with(|| capture(server.pid));
+        }
+    }
+
+    pub fn get(&self, pid: u32) -> Option<LaunchSpec> {
+        self.specs.lock().ok()?.get(&pid).cloned().flatten()
+    }
+
+    /// Перезапущенный сервер запускается той же командой
+    pub fn insert(&self, pid: u32, spec: LaunchSpec) {
+        if let Ok(mut specs) = self.specs.lock() {
+            specs.insert(pid, Some(spec));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::probe::FakeProbe;
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_env_subset() {
+        let env = vars(&[
+            ("Path", "C:\\nodejs"),
+            ("VITE_API_URL", "http://localhost:8000"),
+            ("PORT", "5174"),
+            ("GITHUB_TOKEN", "secret"),
+            ("HOME", "/home/dev"),
+        ]);
+        assert_eq!(
+            env_subset(env),
+            vars(&[
+                ("Path", "C:\\nodejs"),
+                ("VITE_API_URL", "http://localhost:8000"),
+                ("PORT", "5174"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_capture_needs_argv_and_cwd() {
+        let probe = FakeProbe::default()
+            .server(1, "node", &[5173])
+            .cmdline(1, "node /app/node_modules/.bin/vite")
+            .cwd(1, "/app")
+            .env(1, &[("PATH", "/usr/bin"), ("SSH_AUTH_SOCK", "/tmp/agent")])
+            .server(2, "node", &[3000])
+            .cmdline(2, "node server.js");
+
+        let spec = capture(&probe, 1).unwrap();
+        assert_eq!(spec.args, vec!["node", "/app/node_modules/.bin/vite"]);
+        assert_eq!(spec.cwd, "/app");
+        assert_eq!(spec.env, vars(&[("PATH", "/usr/bin")]));
+        assert_eq!(capture(&probe, 2), None);
+    }
+
+    #[test]
+    fn test_registry_records_dev_servers_once() {
+        let registry = RestartRegistry::default();
+        let server = |pid, category: &str| Server {
+            project_name: "app".to_string(),
+            category: category.to_string(),
+            ..Server::sample(pid, 3000)
+        };
+        let spec = LaunchSpec {
+            args: vec!["node".to_string()],
+            executable: None,
+            cwd: "/app".to_string(),
+            env: Vec::new(),
+        };
+
+        let calls = std::cell::Cell::new(0);
+        let capture = |_| {
+            calls.set(calls.get() + 1);
+            Some(spec.clone())
+        };
+        registry.record(&[server(1, "dev"), server(2, "system")], capture);
+        registry.record(&[server(1, "dev")], capture);
+        assert_eq!(calls.get(), 1);
+        assert_eq!(registry.get(1), Some(spec.clone()));
+        assert_eq!(registry.get(2), None);
+
+        // Исчез — забыли
+        registry.record(&[], capture);
+        assert_eq!(registry.get(1), None);
+    }
+}
@@ -0,0 +1,238 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+
+use super::{parse, Listener, ProcessParent, Protocol};
+
+/// Длина /proc/<pid>/comm ограничена ядром (TASK_COMM_LEN - 1)
+const COMM_MAX_LEN: usize = 15;
+
+/// Все числовые записи /proc — PIDs живых процессов
+fn list_pids() -> Vec<u32> {
+    let entries = match fs::read_dir("/proc") {
+        Ok(e) => e,
+        Err(_) => return Vec::new(),
+    };
+    entries
+        .flatten()
+        .filter_map(|e| e.file_name().to_str()?.parse().ok())
+        .collect()
+}
+
+/// "socket:[12345]" → Some(12345)
+fn socket_inode(link: &str) -> Option<u64> {
+    link.strip_prefix("socket:[")?
+        .strip_suffix(']')?
+        .parse()
+        .ok()
+}
+
+/// Результат сканирования: PID → слушающие сокеты TCP + UDP (дедуплицированные)
+pub fn scan_listeners() -> HashMap<u32, HashSet<Listener>> {
+    let mut pid_listeners: HashMap<u32, HashSet<Listener>> = HashMap::new();
+
+    // 1. inode → сокет из таблиц ядра (IPv4 + IPv6, TCP + UDP)
+    let mut inode_listeners: HashMap<u64, Listener> = HashMap::new();
+    let tables = [
+        ("/proc/net/tcp", Protocol::Tcp),
+        ("/proc/net/tcp6", Protocol::Tcp),
+        ("/proc/net/udp", Protocol::Udp),
+        ("/proc/net/udp6", Protocol::Udp),
+    ];
+    for (table, protocol) in tables {
+        if let Ok(content) = fs::read_to_string(table) {
+            inode_listeners.extend(parse::parse_proc_net(&content, protocol));
+        }
+    }
+    if inode_listeners.is_empty() {
+        return pid_listeners;
+    }
+
+    // 2. inode → PID через /proc/<pid>/fd (чужие процессы без root недоступны)
+    for pid in list_pids() {
+        let fds = match fs::read_dir(format!("/proc/{}/fd", pid)) {
+            Ok(f) => f,
+            Err(_) => continue,
+        };
+        for fd in fds.flatten() {
+            let link = match fs::read_link(fd.path()) {
+                Ok(l) => l,
+                Err(_) => continue,
+            };
+            if let Some(&listener) =
+                socket_inode(&link.to_string_lossy()).and_then(|inode| inode_listeners.get(&inode))
+            {
+                pid_listeners.entry(pid).or_default().insert(listener);
+            }
+        }
+    }
+
+    pid_listeners
+}
+
+/// Имя процесса: comm, а если он обрезан ядром — basename из /proc/<pid>/exe
+fn read_process_name(pid: u32) -> Option<String> {
+    let comm = fs::read_to_string(format!("/proc/{}/comm", pid)).ok()?;
+    let comm = comm.trim_end_matches('\n').to_string();
+    if comm.len() < COMM_MAX_LEN {
+        return Some(comm);
+    }
+    let exe = fs::read_link(format!("/proc/{}/exe", pid)).ok();
+    Some(
+        exe.as_deref()
+            .and_then(|p| p.file_name())
+            .and_then(|n| n.to_str())
+            .map(|n| n.to_string())
+            .unwrap_or(comm),
+    )
+}
+
+/// БАТЧ: получить имена ВСЕХ процессов из /proc
+pub fn get_all_process_names() -> HashMap<u32, String> {
+    let mut names: HashMap<u32, String> = HashMap::new();
+    for pid in list_pids() {
+        if let Some(name) = read_process_name(pid) {
+            if !name.is_empty() {
+                names.insert(pid, name);
+            }
+        }
+    }
+    names
+}
+
+/// Поле starttime (22-е) из /proc/<pid>/stat — в тиках с момента загрузки.
+/// comm может содержать пробелы и скобки, поэтому режем по последней ')'.
+fn parse_stat_start_ticks(stat: &str) -> Option<u64> {
+    let after_comm = &stat[stat.rfind(')')? + 1..];
+    // После comm идёт поле 3 (state), значит starttime — индекс 22 - 3
+    after_comm.split_whitespace().nth(19)?.parse().ok()
+}
+
+/// Поле ppid (4-е) из /proc/<pid>/stat
+fn parse_stat_ppid(stat: &str) -> Option<u32> {
+    let after_comm = &stat[stat.rfind(')')? + 1..];
+    after_comm.split_whitespace().nth(1)?.parse().ok()
+}
+
+/// Поле state (3-е) из /proc/<pid>/stat: 'R', 'S', 'Z'...
+fn parse_stat_state(stat: &str) -> Option<char> {
+    let after_comm = &stat[stat.rfind(')')? + 1..];
+    after_comm.split_whitespace().next()?.chars().next()
+}
+
+/// Процесс завершился, но родитель ещё не забрал его статус (зомби).
+/// `kill(pid, 0)` для него успешен, хотя процесса уже нет.
+pub fn is_zombie(pid: u32) -> bool {
+    fs::read_to_string(format!("/proc/{}/stat", pid))
+        .ok()
+        .and_then(|s| parse_stat_state(&s))
+        == Some('Z')
+}
+
+/// Время с загрузки системы в секундах (первое число /proc/uptime)
+fn system_uptime_secs() -> Option<f64> {
+    let content = fs::read_to_string("/proc/uptime").ok()?;
+    content.split_whitespace().next()?.parse().ok()
+}
+
+fn clock_ticks_per_sec() -> u64 {
+    // SAFETY: sysconf без побочных эффектов
+    let ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+    if ticks > 0 {
+        ticks as u64
+    } else {
+        100
+    }
+}
+
+/// БАТЧ: получить uptime всех указанных PIDs из /proc/<pid>/stat
+pub fn get_all_uptimes(pids: &[u32]) -> HashMap<u32, u64> {
+    let mut uptimes: HashMap<u32, u64> = HashMap::new();
+    let boot_uptime = match system_uptime_secs() {
+        Some(u) => u as u64,
+        None => return uptimes,
+    };
+    let ticks = clock_ticks_per_sec();
+
+    for &pid in pids {
+        let start = fs::read_to_string(format!("/proc/{}/stat", pid))
+            .ok()
+            .and_then(|s| parse_stat_start_ticks(&s));
+        if let Some(start) = start {
+            uptimes.insert(pid, boot_uptime.saturating_sub(start / ticks));
+        }
+    }
+    uptimes
+}
+
+/// БАТЧ: получить CWD для всех указанных PIDs через /proc/<pid>/cwd
+pub fn get_all_process_cwds(pids: &[u32]) -> HashMap<u32, String> {
+    let mut cwds = HashMap::new();
+    for &pid in pids {
+        if let Ok(path) = fs::read_link(format!("/proc/{}/cwd", pid)) {
+            cwds.insert(pid, path.to_string_lossy().to_string());
+        }
+    }
+    cwds
+}
+
+/// БАТЧ: получить командные строки (argv) указанных PIDs из /proc/<pid>/cmdline
+pub fn get_all_command_lines(pids: &[u32]) -> HashMap<u32, Vec<String>> {
+    let mut command_lines = HashMap::new();
+    for &pid in pids {
+        if let Ok(raw) = fs::read(format!("/proc/{}/cmdline", pid)) {
+            let args = parse::parse_proc_cmdline(&raw);
+            if !args.is_empty() {
+                command_lines.insert(pid, args);
+            }
+        }
+    }
+    command_lines
+}
+
+/// БАТЧ: PID → PID родителя (поле 4 /proc/<pid>/stat) и starttime для всех процессов
+pub fn get_all_parent_pids() -> HashMap<u32, ProcessParent> {
+    let mut parents = HashMap::new();
+    for pid in list_pids() {
+        let Ok(stat) = fs::read_to_string(format!("/proc/{}/stat", pid)) else {
+            continue;
+        };
+        if let Some(ppid) = parse_stat_ppid(&stat) {
+            let started = parse_stat_start_ticks(&stat);
+            parents.insert(pid, ProcessParent { ppid, started });
+        }
+    }
+    parents
+}
+
+/// Переменные окружения процесса (/proc/<pid>/environ, только свои процессы)
+pub fn get_process_environment(pid: u32) -> Option<Vec<(String, String)>> {
+    let raw = fs::read(format!("/proc/{}/environ", pid)).ok()?;
+    Some(parse::parse_environ(&String::from_utf8_lossy(&raw)))
+}
+
+/// Полный путь к исполняемому файлу процесса (/proc/<pid>/exe)
+pub fn get_process_executable(pid: u32) -> Option<String> {
+    let path = fs::read_link(format!("/proc/{}/exe", pid)).ok()?;
+    Some(path.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_socket_inode() {
+        assert_eq!(socket_inode("socket:[41234]"), Some(41234));
+        assert_eq!(socket_inode("pipe:[41234]"), None);
+        assert_eq!(socket_inode("/dev/null"), None);
+    }
+
+    #[test]
+    fn test_parse_stat_with_spaces_in_comm() {
+        let stat = "1234 (my (weird) proc) S 1 1234 1234 0 -1 4194560 100 0 0 0 1 2 0 0 20 0 1 0 98765 1000 200";
+        assert_eq!(parse_stat_start_ticks(stat), Some(98765));
+        assert_eq!(parse_stat_ppid(stat), Some(1));
+        assert_eq!(parse_stat_state(stat), Some('S'));
+        assert_eq!(parse_stat_state("77 (sh) Z 1 77 77 0"), Some('Z'));
+    }
+}
@@ -0,0 +1,88 @@
+//! Сканирование слушающих портов и сведений о процессах.
+//! Реализация выбирается по целевой ОС, публичный API одинаковый.
+
+// Парсеры всех форматов компилируются на любой ОС, чтобы фикстуры
+// проверялись на любой CI-машине; в рантайме нужен только формат текущей ОС.
+#[cfg_attr(not(test), allow(dead_code))]
+mod parse;
+
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(windows)]
+mod windows;
+
+#[cfg(target_os = "linux")]
+pub use linux::*;
+#[cfg(windows)]
+pub use windows::*;
+
+use serde::Serialize;
+use std::net::{IpAddr, SocketAddr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Protocol {
+    Tcp,
+    Udp,
+}
+
+/// Слушающий сокет: TCP в LISTEN или несоединённый (bound) UDP
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Listener {
+    pub protocol: Protocol,
+    pub addr: SocketAddr,
+}
+
+/// Родитель процесса и время запуска самого процесса. Время в единицах ОС
+/// (тики с загрузки на Linux, FILETIME на Windows) — сравнивается только между
+/// процессами одного снимка; None — ОС не дала прочитать.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProcessParent {
+    pub ppid: u32,
+    pub started: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IpFamily {
+    Ipv4,
+    Ipv6,
+}
+
+/// Откуда доступен сокет: только с этой машины, со всех интерфейсов или с одного адреса
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BindScope {
+    Loopback,
+    Any,
+    Specific,
+}
+
+/// Адрес, на котором слушает сокет
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BindAddress {
+    pub ip: String,
+    pub family: IpFamily,
+    pub scope: BindScope,
+}
+
+impl From<IpAddr> for BindAddress {
+    fn from(ip: IpAddr) -> Self {
+        let scope = if ip.is_unspecified() {
+            BindScope::Any
+        } else if ip.is_loopback() {
+            BindScope::Loopback
+        } else {
+            BindScope::Specific
+        };
+        BindAddress {
+            ip: ip.to_string(),
+            family: if ip.is_ipv4() {
+                IpFamily::Ipv4
+            } else {
+                IpFamily::Ipv6
+            },
+            scope,
+        }
+    }
+}
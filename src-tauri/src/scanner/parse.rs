@@ -0,0 +1,801 @@
+//! Чистые парсеры вывода системных утилит: текст → PID → слушающие сокеты.
+//! Никаких вызовов ОС — всё покрыто фикстурами из `tests/fixtures/`.
+
+use std::collections::{HashMap, HashSet};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use super::{Listener, Protocol};
+
+pub type PidListeners = HashMap<u32, HashSet<Listener>>;
+
+fn insert(pid_listeners: &mut PidListeners, pid: u32, protocol: Protocol, addr: SocketAddr) {
+    // PID 0 — System Idle / сокеты без владельца
+    if pid != 0 {
+        pid_listeners
+            .entry(pid)
+            .or_default()
+            .insert(Listener { protocol, addr });
+    }
+}
+
+/// "127.0.0.1:3000", "[::]:3000", "*:3000", "127.0.0.53%lo:53" → ("127.0.0.1", 3000)
+fn split_host_port(addr: &str) -> Option<(&str, u16)> {
+    let (host, port) = addr.rsplit_once(':')?;
+    Some((host, port.parse().ok()?))
+}
+
+/// BSD-формат macOS netstat: порт после последней точки — "127.0.0.1.3000", "*.3000"
+fn split_host_port_bsd(addr: &str) -> Option<(&str, u16)> {
+    let (host, port) = addr.rsplit_once('.')?;
+    Some((host, port.parse().ok()?))
+}
+
+/// Хост из вывода утилит → IP. Скобки и zone id ("%lo", "%12") отбрасываются.
+/// "*" — любой адрес, семейство утилиты сообщают отдельно (`wildcard_v6`).
+fn parse_host(host: &str, wildcard_v6: bool) -> Option<IpAddr> {
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let host = host.split('%').next()?;
+    if host == "*" {
+        return Some(if wildcard_v6 {
+            Ipv6Addr::UNSPECIFIED.into()
+        } else {
+            Ipv4Addr::UNSPECIFIED.into()
+        });
+    }
+    host.parse().ok()
+}
+
+/// "host:port" → SocketAddr
+fn parse_socket(addr: &str, wildcard_v6: bool) -> Option<SocketAddr> {
+    let (host, port) = split_host_port(addr)?;
+    Some(SocketAddr::new(parse_host(host, wildcard_v6)?, port))
+}
+
+/// Windows `netstat -ano` (TCP, TCPv6, UDP, UDPv6 одним вызовом).
+///
+/// Название состояния локализовано (LISTENING, ABHÖREN, ПРОСЛУШИВАНИЕ...),
+/// поэтому слушающий TCP-сокет определяем по удалённому порту 0.
+/// У UDP колонки состояния нет: `UDP 0.0.0.0:5353 *:* 2212`.
+pub fn parse_netstat_windows(text: &str) -> PidListeners {
+    let mut pid_listeners = PidListeners::new();
+
+    for line in text.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let (protocol, pid) = match (parts.first(), parts.len()) {
+            // Proto, Local, Foreign, State, PID
+            (Some(p), 5) if p.eq_ignore_ascii_case("TCP") => {
+                if !matches!(split_host_port(parts[2]), Some((_, 0))) {
+                    continue;
+                }
+                (Protocol::Tcp, parts[4])
+            }
+            // Proto, Local, Foreign, PID
+            (Some(p), 4) if p.eq_ignore_ascii_case("UDP") => (Protocol::Udp, parts[3]),
+            _ => continue,
+        };
+        let (addr, pid) = match (parse_socket(parts[1], false), pid.parse::<u32>()) {
+            (Some(addr), Ok(pid)) => (addr, pid),
+            _ => continue,
+        };
+        insert(&mut pid_listeners, pid, protocol, addr);
+    }
+
+    pid_listeners
+}
+
+/// Linux `ss -ltnpH` / `ss -lunpH` / `ss -ltunpH` (последний — с колонкой Netid).
+/// PIDs берутся из `users:(("node",pid=123,fd=20),...)`.
+/// Строки без users (чужие сокеты без root) пропускаются.
+pub fn parse_ss(text: &str) -> PidListeners {
+    let mut pid_listeners = PidListeners::new();
+
+    for line in text.lines() {
+        let mut parts: Vec<&str> = line.split_whitespace().collect();
+        if matches!(parts.first(), Some(&"tcp") | Some(&"udp")) {
+            parts.remove(0);
+        }
+        // State, Recv-Q, Send-Q, Local, Peer, [Process]
+        if parts.len() < 6 {
+            continue;
+        }
+        let protocol = match parts[0] {
+            "LISTEN" => Protocol::Tcp,
+            "UNCONN" => Protocol::Udp,
+            _ => continue,
+        };
+        // "*" у ss — dual-stack сокет на in6addr_any
+        let addr = match parse_socket(parts[3], true) {
+            Some(addr) => addr,
+            None => continue,
+        };
+        let process = parts[5..].join(" ");
+        for chunk in process.split("pid=").skip(1) {
+            let digits: String = chunk.chars().take_while(|c| c.is_ascii_digit()).collect();
+            if let Ok(pid) = digits.parse() {
+                insert(&mut pid_listeners, pid, protocol, addr);
+            }
+        }
+    }
+
+    pid_listeners
+}
+
+/// macOS `lsof -iTCP -sTCP:LISTEN -nP` и `lsof -iUDP -nP`.
+/// COMMAND экранирует пробелы (\x20), так что PID — всегда вторая колонка.
+pub fn parse_lsof(text: &str) -> PidListeners {
+    let mut pid_listeners = PidListeners::new();
+
+    for line in text.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 9 {
+            continue;
+        }
+        let pid: u32 = match parts[1].parse() {
+            Ok(p) => p,
+            Err(_) => continue,
+        };
+        // NAME у TCP стоит прямо перед "(LISTEN)", у UDP — последний.
+        // Подключённый UDP-сокет ("local->remote") — клиент, не сервер.
+        let (protocol, name) = match parts[7] {
+            "TCP" if parts.last() == Some(&"(LISTEN)") => (Protocol::Tcp, parts[parts.len() - 2]),
+            "UDP" if !parts[8].contains("->") => (Protocol::Udp, parts[8]),
+            _ => continue,
+        };
+        // TYPE (IPv4/IPv6) — семейство для "*:port"
+        let v6 = parts[4] == "IPv6";
+        if let Some(addr) = parse_socket(name, v6) {
+            insert(&mut pid_listeners, pid, protocol, addr);
+        }
+    }
+
+    pid_listeners
+}
+
+/// macOS `netstat -anv`.
+///
+/// Старые версии печатают колонку `pid`, начиная с Ventura — `process:pid`
+/// (например `node:48390`). Индекс колонки берём из заголовка.
+/// У UDP-строк колонка `(state)` пустая, поэтому всё сдвинуто на одну влево.
+pub fn parse_netstat_macos(text: &str) -> PidListeners {
+    let mut pid_listeners = PidListeners::new();
+    let mut pid_column: Option<usize> = None;
+    let mut process_pid = false;
+
+    for line in text.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.first() == Some(&"Proto") {
+            // "Local Address" и "Foreign Address" в заголовке — по два слова
+            pid_column = parts
+                .iter()
+                .position(|p| *p == "pid" || *p == "process:pid")
+                .map(|i| i.saturating_sub(2));
+            process_pid = parts.contains(&"process:pid");
+            continue;
+        }
+        if parts.len() < 6 {
+            continue;
+        }
+        let (protocol, rest) = if parts[0].starts_with("tcp") && parts[5] == "LISTEN" {
+            (Protocol::Tcp, 6)
+        } else if parts[0].starts_with("udp") && parts[4] == "*.*" {
+            (Protocol::Udp, 5)
+        } else {
+            continue;
+        };
+        // tcp4 / tcp6 / tcp46 — семейство для "*.port"
+        let v6 = !parts[0].ends_with('4') || parts[0].ends_with("46");
+        let addr = match split_host_port_bsd(parts[3])
+            .and_then(|(host, port)| Some(SocketAddr::new(parse_host(host, v6)?, port)))
+        {
+            Some(addr) => addr,
+            None => continue,
+        };
+        let pid = if process_pid {
+            // Имя процесса может содержать пробелы — ищем первый "имя:число"
+            parts[rest..]
+                .iter()
+                .find_map(|p| p.rsplit_once(':').and_then(|(_, pid)| pid.parse().ok()))
+        } else {
+            pid_column
+                .and_then(|i| (i + rest).checked_sub(6))
+                .and_then(|i| parts.get(i))
+                .and_then(|p| p.parse().ok())
+        };
+        if let Some(pid) = pid {
+            insert(&mut pid_listeners, pid, protocol, addr);
+        }
+    }
+
+    pid_listeners
+}
+
+/// Состояние TCP_LISTEN в /proc/net/tcp (колонка `st`, hex)
+const TCP_LISTEN: &str = "0A";
+/// Несоединённый UDP-сокет в /proc/net/udp числится как TCP_CLOSE
+const UDP_UNCONNECTED: &str = "07";
+
+/// Адрес из /proc/net/tcp{,6}: hex-слова по 32 бита в порядке байт хоста.
+/// "0100007F" → 127.0.0.1, "00000000000000000000000001000000" → ::1
+fn parse_proc_ip(hex: &str) -> Option<IpAddr> {
+    let word = |chunk: &str| u32::from_str_radix(chunk, 16).ok().map(u32::to_ne_bytes);
+    match hex.len() {
+        8 => Some(Ipv4Addr::from(word(hex)?).into()),
+        32 => {
+            let mut bytes = [0u8; 16];
+            for i in 0..4 {
+                bytes[i * 4..i * 4 + 4].copy_from_slice(&word(&hex[i * 8..i * 8 + 8])?);
+            }
+            Some(Ipv6Addr::from(bytes).into())
+        }
+        _ => None,
+    }
+}
+
+/// "0100007F:0BB8" → 127.0.0.1:3000
+fn parse_proc_socket(field: &str) -> Option<SocketAddr> {
+    let (ip, port) = field.split_once(':')?;
+    Some(SocketAddr::new(
+        parse_proc_ip(ip)?,
+        u16::from_str_radix(port, 16).ok()?,
+    ))
+}
+
+/// Linux /proc/net/{tcp,udp}{,6}: пары (inode сокета, слушатель).
+/// TCP — только LISTEN, UDP — только несоединённые сокеты (удалённый порт 0).
+/// PID здесь нет — его находят по inode в /proc/<pid>/fd.
+pub fn parse_proc_net(text: &str, protocol: Protocol) -> Vec<(u64, Listener)> {
+    let mut result = Vec::new();
+    // Первая строка — заголовок: sl local_address rem_address st ... inode
+    for line in text.lines().skip(1) {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 10 {
+            continue;
+        }
+        let bound = match protocol {
+            Protocol::Tcp => parts[3] == TCP_LISTEN,
+            Protocol::Udp => {
+                parts[3] == UDP_UNCONNECTED
+                    && parse_proc_socket(parts[2]).is_some_and(|remote| remote.port() == 0)
+            }
+        };
+        if !bound {
+            continue;
+        }
+        let addr = match parse_proc_socket(parts[1]) {
+            Some(addr) => addr,
+            None => continue,
+        };
+        let inode: u64 = match parts[9].parse() {
+            Ok(i) if i != 0 => i,
+            _ => continue,
+        };
+        result.push((inode, Listener { protocol, addr }));
+    }
+    result
+}
+
+/// Windows `tasklist /FO CSV /NH`: PID → имя процесса
+pub fn parse_tasklist_csv(text: &str) -> HashMap<u32, String> {
+    let mut names: HashMap<u32, String> = HashMap::new();
+
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        // Формат: "name.exe","1234","Console","1","12,345 K"
+        // split(',') ломается на запятых внутри кавычек — парсим корректно
+        if let Some((name, pid_str)) = parse_csv_first_two(line) {
+            if let Ok(pid) = pid_str.parse::<u32>() {
+                if !name.is_empty() {
+                    names.insert(pid, name.to_string());
+                }
+            }
+        }
+    }
+
+    names
+}
+
+/// Парсит первые два поля CSV строки с учётом кавычек.
+/// "name.exe","1234",... → Some(("name.exe", "1234"))
+fn parse_csv_first_two(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim();
+    if !line.starts_with('"') {
+        return None;
+    }
+    let end1 = line[1..].find('"')? + 1;
+    let name = &line[1..end1];
+    let rest = &line[end1 + 1..];
+    if !rest.starts_with(",\"") {
+        return None;
+    }
+    let rest = &rest[2..];
+    let end2 = rest.find('"')?;
+    let pid_str = &rest[..end2];
+    Some((name, pid_str))
+}
+
+/// `/proc/<pid>/cmdline`: аргументы разделены NUL, после последнего — ровно один NUL.
+/// Процессы, переписавшие argv через setproctitle (postgres, nginx), кладут строку
+/// с пробелами в argv[0] и добивают остаток исходной области NUL-ами (PS_PADDING,
+/// NGX_SETPROCTITLE_PAD); если же заголовок занял область целиком, ядро отдаёт его
+/// без завершающего NUL. Одиночный аргумент с пробелами (`/opt/My App/server`)
+/// без такой добивки не дробим.
+pub fn parse_proc_cmdline(raw: &[u8]) -> Vec<String> {
+    let text = String::from_utf8_lossy(raw);
+    let args: Vec<String> = text
+        .split('\0')
+        .filter(|a| !a.is_empty())
+        .map(str::to_string)
+        .collect();
+    let retitled = raw.last() != Some(&0) || raw.ends_with(b"\0\0");
+    match args.as_slice() {
+        [single] if single.contains(' ') && retitled => {
+            single.split_whitespace().map(str::to_string).collect()
+        }
+        _ => args,
+    }
+}
+
+/// Командная строка Windows (PEB `CommandLine`) → argv по правилам CommandLineToArgvW:
+/// argv[0] читается до кавычки/пробела без экранирования; дальше 2n обратных
+/// слешей перед `"` дают n слешей и переключают кавычки, 2n+1 — n слешей и литерал `"`,
+/// `""` внутри кавычек — литерал `"`.
+pub fn split_windows_command_line(line: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut chars = line.trim_start().chars().peekable();
+
+    // argv[0] — путь к exe, обратные слеши в нём не экранируют
+    let mut program = String::new();
+    if chars.peek() == Some(&'"') {
+        chars.next();
+        for c in chars.by_ref() {
+            if c == '"' {
+                break;
+            }
+            program.push(c);
+        }
+    }
+    while let Some(&c) = chars.peek() {
+        if c == ' ' || c == '\t' {
+            break;
+        }
+        program.push(c);
+        chars.next();
+    }
+    if program.is_empty() {
+        return args;
+    }
+    args.push(program);
+
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut in_quotes = false;
+    while let Some(c) = chars.next() {
+        match c {
+            ' ' | '\t' if !in_quotes => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            '\\' => {
+                let mut slashes = 1;
+                while chars.peek() == Some(&'\\') {
+                    chars.next();
+                    slashes += 1;
+                }
+                in_arg = true;
+                if chars.peek() == Some(&'"') {
+                    current.extend(std::iter::repeat_n('\\', slashes / 2));
+                    if slashes % 2 == 1 {
+                        chars.next();
+                        current.push('"');
+                    }
+                } else {
+                    current.extend(std::iter::repeat_n('\\', slashes));
+                }
+            }
+            '"' => {
+                in_arg = true;
+                if in_quotes && chars.peek() == Some(&'"') {
+                    chars.next();
+                    current.push('"');
+                } else {
+                    in_quotes = !in_quotes;
+                }
+            }
+            _ => {
+                in_arg = true;
+                current.push(c);
+            }
+        }
+    }
+    if in_arg {
+        args.push(current);
+    }
+    args
+}
+
+/// Блок окружения (`/proc/<pid>/environ`, PEB `Environment`): "KEY=VALUE" через NUL.
+/// Скрытые переменные Windows вида "=C:=C:\app" пропускаются.
+pub fn parse_environ(block: &str) -> Vec<(String, String)> {
+    block
+        .split('\0')
+        .filter_map(|entry| {
+            let (key, value) = entry.split_once('=')?;
+            (!key.is_empty()).then(|| (key.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listeners(parsed: &PidListeners, pid: u32, protocol: Protocol) -> Vec<SocketAddr> {
+        parsed
+            .get(&pid)
+            .map(|l| {
+                l.iter()
+                    .filter(|l| l.protocol == protocol)
+                    .map(|l| l.addr)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// TCP-порты процесса (дедуплицированные, по возрастанию)
+    fn ports(parsed: &PidListeners, pid: u32) -> Vec<u16> {
+        let ports: HashSet<u16> = listeners(parsed, pid, Protocol::Tcp)
+            .iter()
+            .map(|a| a.port())
+            .collect();
+        let mut v: Vec<u16> = ports.into_iter().collect();
+        v.sort();
+        v
+    }
+
+    /// TCP-адреса процесса
+    fn addrs(parsed: &PidListeners, pid: u32) -> Vec<String> {
+        let mut v: Vec<String> = listeners(parsed, pid, Protocol::Tcp)
+            .iter()
+            .map(|a| a.to_string())
+            .collect();
+        v.sort();
+        v
+    }
+
+    /// UDP-адреса процесса
+    fn udp(parsed: &PidListeners, pid: u32) -> Vec<String> {
+        let mut v: Vec<String> = listeners(parsed, pid, Protocol::Udp)
+            .iter()
+            .map(|a| a.to_string())
+            .collect();
+        v.sort();
+        v
+    }
+
+    fn tcp(s: &str) -> Listener {
+        Listener {
+            protocol: Protocol::Tcp,
+            addr: s.parse().unwrap(),
+        }
+    }
+
+    fn udp_listener(s: &str) -> Listener {
+        Listener {
+            protocol: Protocol::Udp,
+            addr: s.parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_netstat_windows_english() {
+        let parsed =
+            parse_netstat_windows(include_str!("../../tests/fixtures/netstat_windows_en.txt"));
+        assert_eq!(ports(&parsed, 1024), vec![135]);
+        // IPv4 + IPv6 одного процесса — один порт, два адреса
+        assert_eq!(ports(&parsed, 18340), vec![3000]);
+        assert_eq!(addrs(&parsed, 18340), vec!["0.0.0.0:3000", "[::]:3000"]);
+        assert_eq!(addrs(&parsed, 6012), vec!["0.0.0.0:5432", "[::1]:5432"]);
+        assert_eq!(ports(&parsed, 21560), vec![5173, 24678]);
+        assert_eq!(ports(&parsed, 6012), vec![5432]);
+        // ESTABLISHED / TIME_WAIT не попадают
+        assert!(!parsed.contains_key(&9876));
+        // PID 0 отбрасывается
+        assert!(!parsed.contains_key(&0));
+    }
+
+    #[test]
+    fn test_netstat_windows_localized() {
+        let en = parse_netstat_windows(include_str!("../../tests/fixtures/netstat_windows_en.txt"));
+        let de = parse_netstat_windows(include_str!("../../tests/fixtures/netstat_windows_de.txt"));
+        let ru = parse_netstat_windows(include_str!("../../tests/fixtures/netstat_windows_ru.txt"));
+        assert_eq!(de, en);
+        assert_eq!(ru, en);
+    }
+
+    #[test]
+    fn test_netstat_windows_udp() {
+        let parsed =
+            parse_netstat_windows(include_str!("../../tests/fixtures/netstat_windows_en.txt"));
+        assert_eq!(udp(&parsed, 2212), vec!["0.0.0.0:5353", "[::]:5353"]);
+        // zone id у link-local отбрасывается
+        assert_eq!(udp(&parsed, 3456), vec!["[fe80::1]:1900"]);
+        assert_eq!(udp(&parsed, 21560), vec!["127.0.0.1:4433"]);
+        // UDP-only процесс не получает TCP-портов
+        assert!(ports(&parsed, 2212).is_empty());
+    }
+
+    #[test]
+    fn test_netstat_windows_ignores_garbage() {
+        let text = "garbage line\n\
+                    \n\
+                    TCP    0.0.0.0:notaport       0.0.0.0:0              LISTENING       1\n\
+                    UDP    0.0.0.0:53             *:*\n";
+        assert!(parse_netstat_windows(text).is_empty());
+    }
+
+    #[test]
+    fn test_ss() {
+        let parsed = parse_ss(include_str!("../../tests/fixtures/ss_ltnp.txt"));
+        assert_eq!(ports(&parsed, 4242), vec![3000]);
+        assert_eq!(ports(&parsed, 612), vec![53]);
+        assert_eq!(ports(&parsed, 5151), vec![5173]);
+        assert_eq!(ports(&parsed, 900), vec![6379]);
+        // SO_REUSEPORT: несколько PIDs на одном сокете
+        assert_eq!(ports(&parsed, 777), vec![8080]);
+        assert_eq!(ports(&parsed, 778), vec![8080]);
+        // sshd без users:(...) — PID неизвестен
+        assert_eq!(parsed.len(), 6);
+        // zone id отброшен, "*" — dual-stack
+        assert_eq!(addrs(&parsed, 612), vec!["127.0.0.53:53"]);
+        assert!(udp(&parsed, 612).is_empty());
+        assert_eq!(addrs(&parsed, 777), vec!["[::]:8080"]);
+        assert_eq!(addrs(&parsed, 900), vec!["[::1]:6379"]);
+    }
+
+    #[test]
+    fn test_ss_udp() {
+        let parsed = parse_ss(include_str!("../../tests/fixtures/ss_lunp.txt"));
+        assert_eq!(udp(&parsed, 612), vec!["127.0.0.53:53"]);
+        assert_eq!(udp(&parsed, 700), vec!["0.0.0.0:5353"]);
+        assert_eq!(udp(&parsed, 5151), vec!["[::]:4433"]);
+        assert!(ports(&parsed, 5151).is_empty());
+    }
+
+    #[test]
+    fn test_ss_with_netid_column() {
+        let text = "tcp   LISTEN 0      511        127.0.0.1:3000      0.0.0.0:*    users:((\"node\",pid=4242,fd=21))\n\
+                    udp   UNCONN 0      0          127.0.0.1:3000      0.0.0.0:*    users:((\"node\",pid=4242,fd=22))\n";
+        let parsed = parse_ss(text);
+        assert_eq!(addrs(&parsed, 4242), vec!["127.0.0.1:3000"]);
+        assert_eq!(udp(&parsed, 4242), vec!["127.0.0.1:3000"]);
+    }
+
+    #[test]
+    fn test_lsof_macos() {
+        let parsed = parse_lsof(include_str!("../../tests/fixtures/lsof_macos.txt"));
+        assert_eq!(ports(&parsed, 512), vec![49152]);
+        assert_eq!(ports(&parsed, 48213), vec![5173]);
+        assert_eq!(ports(&parsed, 48390), vec![3000]);
+        assert_eq!(ports(&parsed, 601), vec![5432]);
+        assert_eq!(ports(&parsed, 1201), vec![9222]);
+        assert_eq!(parsed.len(), 5);
+        // "*" с TYPE IPv4/IPv6 — два разных сокета
+        assert_eq!(addrs(&parsed, 512), vec!["0.0.0.0:49152", "[::]:49152"]);
+        assert_eq!(addrs(&parsed, 601), vec!["127.0.0.1:5432", "[::1]:5432"]);
+    }
+
+    #[test]
+    fn test_lsof_macos_udp() {
+        let parsed = parse_lsof(include_str!("../../tests/fixtures/lsof_macos_udp.txt"));
+        assert_eq!(udp(&parsed, 412), vec!["0.0.0.0:5353", "[::]:5353"]);
+        assert_eq!(udp(&parsed, 48213), vec!["[::1]:4433"]);
+        // Подключённый UDP-сокет браузера — клиент
+        assert!(!parsed.contains_key(&1201));
+    }
+
+    #[test]
+    fn test_netstat_macos_pid_column() {
+        let parsed = parse_netstat_macos(include_str!("../../tests/fixtures/netstat_macos.txt"));
+        assert_eq!(ports(&parsed, 48390), vec![3000]);
+        assert_eq!(ports(&parsed, 48213), vec![5173]);
+        assert_eq!(ports(&parsed, 601), vec![5432]);
+        assert!(!parsed.contains_key(&1201));
+        // tcp46 "*.5173" — dual-stack
+        assert_eq!(addrs(&parsed, 48213), vec!["[::]:5173"]);
+        // udp4 без колонки состояния
+        assert_eq!(udp(&parsed, 412), vec!["0.0.0.0:5353"]);
+        assert_eq!(addrs(&parsed, 601), vec!["127.0.0.1:5432", "[::1]:5432"]);
+    }
+
+    #[test]
+    fn test_netstat_macos_process_pid_column() {
+        let old = parse_netstat_macos(include_str!("../../tests/fixtures/netstat_macos.txt"));
+        let new = parse_netstat_macos(include_str!(
+            "../../tests/fixtures/netstat_macos_process.txt"
+        ));
+        assert_eq!(new, old);
+    }
+
+    #[test]
+    fn test_proc_net_tcp() {
+        let v4 = parse_proc_net(
+            include_str!("../../tests/fixtures/proc_net_tcp.txt"),
+            Protocol::Tcp,
+        );
+        assert_eq!(
+            v4,
+            vec![
+                (41234, tcp("127.0.0.1:3000")),
+                (41500, tcp("0.0.0.0:5432")),
+                (38811, tcp("127.0.0.1:631")),
+            ]
+        );
+        let v6 = parse_proc_net(
+            include_str!("../../tests/fixtures/proc_net_tcp6.txt"),
+            Protocol::Tcp,
+        );
+        assert_eq!(
+            v6,
+            vec![(52001, tcp("[::1]:5173")), (52002, tcp("[::]:8080"))]
+        );
+    }
+
+    #[test]
+    fn test_proc_net_udp() {
+        let v4 = parse_proc_net(
+            include_str!("../../tests/fixtures/proc_net_udp.txt"),
+            Protocol::Udp,
+        );
+        assert_eq!(
+            v4,
+            vec![
+                (20411, udp_listener("127.0.0.53:53")),
+                (30512, udp_listener("0.0.0.0:5353")),
+            ]
+        );
+        let v6 = parse_proc_net(
+            include_str!("../../tests/fixtures/proc_net_udp6.txt"),
+            Protocol::Udp,
+        );
+        assert_eq!(v6, vec![(30600, udp_listener("[::]:4433"))]);
+    }
+
+    #[test]
+    fn test_tasklist_csv() {
+        let names = parse_tasklist_csv(include_str!("../../tests/fixtures/tasklist.csv"));
+        assert_eq!(names.get(&4).map(String::as_str), Some("System"));
+        assert_eq!(names.get(&18340).map(String::as_str), Some("node.exe"));
+        // Запятая внутри кавычек в имени
+        assert_eq!(
+            names.get(&7712).map(String::as_str),
+            Some("Foo, Inc. Helper.exe")
+        );
+    }
+
+    #[test]
+    fn test_split_host_port_forms() {
+        assert_eq!(split_host_port("0.0.0.0:3000"), Some(("0.0.0.0", 3000)));
+        assert_eq!(split_host_port("[::]:3000"), Some(("[::]", 3000)));
+        assert_eq!(split_host_port("[::1]:3000"), Some(("[::1]", 3000)));
+        assert_eq!(split_host_port("*:3000"), Some(("*", 3000)));
+        assert_eq!(
+            split_host_port("127.0.0.53%lo:53"),
+            Some(("127.0.0.53%lo", 53))
+        );
+        assert_eq!(split_host_port("*:*"), None);
+        assert_eq!(split_host_port_bsd("*.5173"), Some(("*", 5173)));
+        assert_eq!(split_host_port_bsd("::1.5432"), Some(("::1", 5432)));
+        assert_eq!(split_host_port_bsd("*.*"), None);
+    }
+
+    #[test]
+    fn test_parse_host_forms() {
+        assert_eq!(parse_host("[fe80::1%12]", false), "fe80::1".parse().ok());
+        assert_eq!(parse_host("*", false), Some(Ipv4Addr::UNSPECIFIED.into()));
+        assert_eq!(parse_host("*", true), Some(Ipv6Addr::UNSPECIFIED.into()));
+        assert_eq!(parse_host("localhost", false), None);
+    }
+
+    #[test]
+    fn test_proc_cmdline() {
+        assert_eq!(
+            parse_proc_cmdline(b"node\0/app/node_modules/.bin/vite\0--port\x005173\0"),
+            vec!["node", "/app/node_modules/.bin/vite", "--port", "5173"]
+        );
+        // postgres 15 на Linux: заголовок, добитый NUL до длины исходного argv
+        let checkpointer = [b"postgres: checkpointer ".as_slice(), &[0; 55]].concat();
+        assert_eq!(
+            parse_proc_cmdline(&checkpointer),
+            vec!["postgres:", "checkpointer"]
+        );
+        let launcher = [b"postgres: logical replication launcher ".as_slice(), &[0; 39]].concat();
+        assert_eq!(
+            parse_proc_cmdline(&launcher),
+            vec!["postgres:", "logical", "replication", "launcher"]
+        );
+        // Заголовок занял всю область — ядро отдаёт его без NUL
+        assert_eq!(
+            parse_proc_cmdline(b"postgres: checkpointer "),
+            vec!["postgres:", "checkpointer"]
+        );
+        // Обычный argv с пробелом в пути не дробим
+        assert_eq!(
+            parse_proc_cmdline(b"/opt/My App/server\0"),
+            vec!["/opt/My App/server"]
+        );
+        // postmaster argv не переписывает
+        assert_eq!(
+            parse_proc_cmdline(b"/usr/lib/postgresql/15/bin/postgres\0-D\0/var/lib/pg\0"),
+            vec!["/usr/lib/postgresql/15/bin/postgres", "-D", "/var/lib/pg"]
+        );
+        assert!(parse_proc_cmdline(b"").is_empty());
+    }
+
+    #[test]
+    fn test_split_windows_command_line() {
+        assert_eq!(
+            split_windows_command_line(
+                r#""C:\Program Files\nodejs\node.exe" node_modules\vite\bin\vite.js --host"#
+            ),
+            vec![
+                r"C:\Program Files\nodejs\node.exe",
+                r"node_modules\vite\bin\vite.js",
+                "--host"
+            ]
+        );
+        assert_eq!(
+            split_windows_command_line(r#"python.exe -m uvicorn "main:app"  --reload"#),
+            vec!["python.exe", "-m", "uvicorn", "main:app", "--reload"]
+        );
+        // Хвостовой \ перед кавычкой экранирует её — как и в самой Windows
+        assert_eq!(
+            split_windows_command_line(r#"postgres.exe -D "C:\dev\db\" -p 5433"#),
+            vec!["postgres.exe", "-D", r#"C:\dev\db" -p 5433"#]
+        );
+    }
+
+    #[test]
+    fn test_split_windows_command_line_escapes() {
+        // Примеры из документации CommandLineToArgvW
+        assert_eq!(
+            split_windows_command_line(r#"a.exe "a b c" d e"#),
+            vec!["a.exe", "a b c", "d", "e"]
+        );
+        assert_eq!(
+            split_windows_command_line(r#"a.exe "ab\"c" "\\" d"#),
+            vec!["a.exe", r#"ab"c"#, r"\", "d"]
+        );
+        assert_eq!(
+            split_windows_command_line(r#"a.exe a\\\b d"e f"g h"#),
+            vec!["a.exe", r"a\\\b", "de fg", "h"]
+        );
+        assert_eq!(
+            split_windows_command_line(r#"a.exe a\\\"b c d"#),
+            vec!["a.exe", r#"a\"b"#, "c", "d"]
+        );
+        assert_eq!(
+            split_windows_command_line(r#"a.exe a\\\\"b c" d e"#),
+            vec!["a.exe", r"a\\b c", "d", "e"]
+        );
+        assert_eq!(split_windows_command_line(r#"a.exe """#), vec!["a.exe", ""]);
+        assert!(split_windows_command_line("   ").is_empty());
+    }
+
+    #[test]
+    fn test_parse_environ() {
+        let block = "=C:=C:\\app\0PATH=C:\\nodejs;C:\\Windows\0NODE_OPTIONS=--inspect=9229\0\0";
+        assert_eq!(
+            parse_environ(block),
+            vec![
+                ("PATH".to_string(), "C:\\nodejs;C:\\Windows".to_string()),
+                ("NODE_OPTIONS".to_string(), "--inspect=9229".to_string()),
+            ]
+        );
+        assert!(parse_environ("").is_empty());
+    }
+}
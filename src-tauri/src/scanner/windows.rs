@@ -0,0 +1,344 @@
+use std::collections::{HashMap, HashSet};
+use std::ffi::c_void;
+use std::os::windows::ffi::OsStringExt;
+use std::os::windows::process::CommandExt;
+use std::process::Command;
+
+use super::{parse, Listener, ProcessParent};
+
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+// --- Windows API FFI для получения CWD и командной строки процесса ---
+
+const PROCESS_QUERY_INFORMATION: u32 = 0x0400;
+const PROCESS_QUERY_LIMITED_INFORMATION: u32 = 0x1000;
+const PROCESS_VM_READ: u32 = 0x0010;
+
+#[repr(C)]
+struct ProcessBasicInformation {
+    reserved1: *mut c_void,
+    peb_base_address: *mut c_void,
+    reserved2: [*mut c_void; 2],
+    unique_process_id: usize,
+    reserved3: *mut c_void,
+}
+
+#[link(name = "ntdll")]
+extern "system" {
+    fn NtQueryInformationProcess(
+        process_handle: *mut c_void,
+        info_class: u32,
+        info: *mut c_void,
+        info_length: u32,
+        return_length: *mut u32,
+    ) -> i32;
+}
+
+#[link(name = "kernel32")]
+extern "system" {
+    fn OpenProcess(access: u32, inherit: i32, pid: u32) -> *mut c_void;
+    fn ReadProcessMemory(
+        process: *mut c_void,
+        base: *const c_void,
+        buf: *mut c_void,
+        size: usize,
+        read: *mut usize,
+    ) -> i32;
+    fn CloseHandle(handle: *mut c_void) -> i32;
+    fn GetProcessTimes(
+        process: *mut c_void,
+        creation: *mut u64,
+        exit: *mut u64,
+        kernel: *mut u64,
+        user: *mut u64,
+    ) -> i32;
+    fn GetSystemTimeAsFileTime(time: *mut u64);
+    fn QueryFullProcessImageNameW(
+        process: *mut c_void,
+        flags: u32,
+        name: *mut u16,
+        size: *mut u32,
+    ) -> i32;
+    fn CreateToolhelp32Snapshot(flags: u32, pid: u32) -> *mut c_void;
+    fn Process32FirstW(snapshot: *mut c_void, entry: *mut ProcessEntry32W) -> i32;
+    fn Process32NextW(snapshot: *mut c_void, entry: *mut ProcessEntry32W) -> i32;
+}
+
+const TH32CS_SNAPPROCESS: u32 = 0x0000_0002;
+const INVALID_HANDLE_VALUE: isize = -1;
+
+#[repr(C)]
+struct ProcessEntry32W {
+    size: u32,
+    usage: u32,
+    process_id: u32,
+    default_heap_id: usize,
+    module_id: u32,
+    threads: u32,
+    parent_process_id: u32,
+    pri_class_base: i32,
+    flags: u32,
+    exe_file: [u16; 260],
+}
+
+/// Offsets строк UNICODE_STRING в RTL_USER_PROCESS_PARAMETERS (x64)
+const PARAMS_CURRENT_DIRECTORY: usize = 0x38;
+const PARAMS_COMMAND_LINE: usize = 0x70;
+/// Environment (указатель на блок) и EnvironmentSize (байты) там же
+const PARAMS_ENVIRONMENT: usize = 0x80;
+const PARAMS_ENVIRONMENT_SIZE: usize = 0x3F0;
+/// Защита от мусорного EnvironmentSize
+const MAX_ENVIRONMENT_SIZE: usize = 1 << 20;
+
+/// Открыть процесс и передать `f` handle + адрес его RTL_USER_PROCESS_PARAMETERS
+/// (NtQueryInformationProcess + PEB). Работает только на x64 Windows 10/11
+fn with_process_parameters<T>(
+    pid: u32,
+    f: impl FnOnce(*mut c_void, usize) -> Option<T>,
+) -> Option<T> {
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_INFORMATION | PROCESS_VM_READ, 0, pid);
+        if handle.is_null() {
+            return None;
+        }
+
+        let result = (|| -> Option<T> {
+            // 1. Получить PEB address
+            let mut pbi: ProcessBasicInformation = std::mem::zeroed();
+            let status = NtQueryInformationProcess(
+                handle,
+                0, // ProcessBasicInformation
+                &mut pbi as *mut _ as *mut c_void,
+                std::mem::size_of::<ProcessBasicInformation>() as u32,
+                std::ptr::null_mut(),
+            );
+            if status < 0 || pbi.peb_base_address.is_null() {
+                return None;
+            }
+
+            // 2. Прочитать ProcessParameters pointer из PEB (offset 0x20 на x64)
+            let params_ptr: usize = read_value(handle, pbi.peb_base_address as usize + 0x20)?;
+            if params_ptr == 0 {
+                return None;
+            }
+            f(handle, params_ptr)
+        })();
+
+        CloseHandle(handle);
+        result
+    }
+}
+
+/// Прочитать значение `T` из памяти чужого процесса
+unsafe fn read_value<T: Copy + Default>(handle: *mut c_void, addr: usize) -> Option<T> {
+    let mut value = T::default();
+    let ok = ReadProcessMemory(
+        handle,
+        addr as *const c_void,
+        &mut value as *mut T as *mut c_void,
+        std::mem::size_of::<T>(),
+        std::ptr::null_mut(),
+    );
+    (ok != 0).then_some(value)
+}
+
+/// Прочитать `len` байт UTF-16 из памяти чужого процесса
+unsafe fn read_wide(handle: *mut c_void, addr: usize, len: usize) -> Option<Vec<u16>> {
+    let mut wide_buf: Vec<u16> = vec![0u16; len / 2];
+    let ok = ReadProcessMemory(
+        handle,
+        addr as *const c_void,
+        wide_buf.as_mut_ptr() as *mut c_void,
+        wide_buf.len() * 2,
+        std::ptr::null_mut(),
+    );
+    (ok != 0).then_some(wide_buf)
+}
+
+/// Прочитать строку из ProcessParameters процесса
+fn read_process_parameter(pid: u32, offset: usize) -> Option<String> {
+    with_process_parameters(pid, |handle, params_ptr| unsafe {
+        // UNICODE_STRING: Length(u16) + MaxLength(u16) + padding(4) + Buffer(*u16)
+        let length: u16 = read_value(handle, params_ptr + offset)?;
+        if length == 0 {
+            return None;
+        }
+        let buffer_ptr: usize = read_value(handle, params_ptr + offset + 8)?;
+        if buffer_ptr == 0 {
+            return None;
+        }
+        let wide_buf = read_wide(handle, buffer_ptr, length as usize)?;
+        let os_str = std::ffi::OsString::from_wide(&wide_buf);
+        Some(os_str.to_string_lossy().to_string())
+    })
+}
+
+/// CWD процесса из PEB (`CurrentDirectory.DosPath`)
+fn get_process_cwd_native(pid: u32) -> Option<String> {
+    let mut path = read_process_parameter(pid, PARAMS_CURRENT_DIRECTORY)?;
+
+    // Убрать trailing backslash (CWD обычно заканчивается на \)
+    if path.ends_with('\\') && !path.ends_with(":\\") {
+        path.pop();
+    }
+
+    Some(path)
+}
+
+/// БАТЧ: получить CWD для всех указанных PIDs (нативно, без PowerShell)
+pub fn get_all_process_cwds(pids: &[u32]) -> HashMap<u32, String> {
+    let mut cwds = HashMap::new();
+    for &pid in pids {
+        if let Some(cwd) = get_process_cwd_native(pid) {
+            cwds.insert(pid, cwd);
+        }
+    }
+    cwds
+}
+
+/// Результат сканирования: PID → слушающие сокеты TCP + UDP (дедуплицированные)
+pub fn scan_listeners() -> HashMap<u32, HashSet<Listener>> {
+    // Без -p: TCP, TCPv6, UDP и UDPv6 за один вызов
+    let output = Command::new("netstat")
+        .args(["-ano"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output();
+
+    let output = match output {
+        Ok(o) => o,
+        Err(_) => return HashMap::new(),
+    };
+
+    parse::parse_netstat_windows(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// БАТЧ: получить имена ВСЕХ процессов за один вызов tasklist
+pub fn get_all_process_names() -> HashMap<u32, String> {
+    let output = Command::new("tasklist")
+        .args(["/FO", "CSV", "/NH"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output();
+
+    match output {
+        Ok(o) => parse::parse_tasklist_csv(&String::from_utf8_lossy(&o.stdout)),
+        Err(_) => HashMap::new(),
+    }
+}
+
+/// Время создания процесса (FILETIME: 100ns с 1601-01-01) через GetProcessTimes
+fn get_process_creation_time(pid: u32) -> Option<u64> {
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if handle.is_null() {
+            return None;
+        }
+
+        let mut creation: u64 = 0;
+        let mut exit: u64 = 0;
+        let mut kernel: u64 = 0;
+        let mut user: u64 = 0;
+
+        let ok = GetProcessTimes(handle, &mut creation, &mut exit, &mut kernel, &mut user);
+        CloseHandle(handle);
+
+        if ok == 0 || creation == 0 {
+            return None;
+        }
+        Some(creation)
+    }
+}
+
+/// Получить uptime процесса через Windows API (GetProcessTimes)
+fn get_process_uptime_native(pid: u32) -> Option<u64> {
+    let creation = get_process_creation_time(pid)?;
+    let mut now: u64 = 0;
+    unsafe { GetSystemTimeAsFileTime(&mut now) };
+    Some(now.saturating_sub(creation) / 10_000_000) // в секунды
+}
+
+/// БАТЧ: получить uptime ВСЕХ указанных PIDs через Windows API (без PowerShell)
+pub fn get_all_uptimes(pids: &[u32]) -> HashMap<u32, u64> {
+    let mut uptimes: HashMap<u32, u64> = HashMap::new();
+    for &pid in pids {
+        if let Some(secs) = get_process_uptime_native(pid) {
+            uptimes.insert(pid, secs);
+        }
+    }
+    uptimes
+}
+
+/// БАТЧ: получить командные строки (argv) указанных PIDs из PEB `CommandLine`
+pub fn get_all_command_lines(pids: &[u32]) -> HashMap<u32, Vec<String>> {
+    let mut command_lines = HashMap::new();
+    for &pid in pids {
+        if let Some(line) = read_process_parameter(pid, PARAMS_COMMAND_LINE) {
+            let args = parse::split_windows_command_line(&line);
+            if !args.is_empty() {
+                command_lines.insert(pid, args);
+            }
+        }
+    }
+    command_lines
+}
+
+/// БАТЧ: PID → PID родителя для всех процессов (один снимок Toolhelp32) и
+/// время создания. Toolhelp хранит PPID и после выхода родителя, так что без
+/// времени создания не отличить родителя от чужого процесса с тем же PID.
+pub fn get_all_parent_pids() -> HashMap<u32, ProcessParent> {
+    let mut parents = HashMap::new();
+    unsafe {
+        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0);
+        if snapshot.is_null() || snapshot as isize == INVALID_HANDLE_VALUE {
+            return parents;
+        }
+        let mut entry: ProcessEntry32W = std::mem::zeroed();
+        entry.size = std::mem::size_of::<ProcessEntry32W>() as u32;
+        let mut ok = Process32FirstW(snapshot, &mut entry);
+        while ok != 0 {
+            let started = get_process_creation_time(entry.process_id);
+            parents.insert(
+                entry.process_id,
+                ProcessParent {
+                    ppid: entry.parent_process_id,
+                    started,
+                },
+            );
+            ok = Process32NextW(snapshot, &mut entry);
+        }
+        CloseHandle(snapshot);
+    }
+    parents
+}
+
+/// Переменные окружения процесса из PEB (блок "KEY=VALUE\0...\0\0")
+pub fn get_process_environment(pid: u32) -> Option<Vec<(String, String)>> {
+    let block = with_process_parameters(pid, |handle, params_ptr| unsafe {
+        let env_ptr: usize = read_value(handle, params_ptr + PARAMS_ENVIRONMENT)?;
+        let size: usize = read_value(handle, params_ptr + PARAMS_ENVIRONMENT_SIZE)?;
+        if env_ptr == 0 || size == 0 || size > MAX_ENVIRONMENT_SIZE {
+            return None;
+        }
+        read_wide(handle, env_ptr, size)
+    })?;
+    let text = String::from_utf16_lossy(&block);
+    Some(parse::parse_environ(&text))
+}
+
+/// Полный путь к исполняемому файлу процесса
+pub fn get_process_executable(pid: u32) -> Option<String> {
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if handle.is_null() {
+            return None;
+        }
+        let mut buf = vec![0u16; 1024];
+        let mut size = buf.len() as u32;
+        let ok = QueryFullProcessImageNameW(handle, 0, buf.as_mut_ptr(), &mut size);
+        CloseHandle(handle);
+        if ok == 0 {
+            return None;
+        }
+        let os_str = std::ffi::OsString::from_wide(&buf[..size as usize]);
+        Some(os_str.to_string_lossy().to_string())
+    }
+}
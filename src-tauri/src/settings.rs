@@ -0,0 +1,411 @@
+//! Пользовательские настройки LocalDock.
+//! Хранятся в `settings.json` в конфиг-директории приложения; старые версии
+//! файла мигрируются при загрузке (`migrate`).
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::logs::LogSettings;
+use crate::notify::NotificationSettings;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Диапазон портов, обе границы включительно
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PortRange {
+    pub start: u16,
+    pub end: u16,
+}
+
+impl PortRange {
+    pub const fn new(start: u16, end: u16) -> Self {
+        PortRange { start, end }
+    }
+
+    pub fn contains(&self, port: u16) -> bool {
+        self.start <= port && port <= self.end
+    }
+}
+
+/// Фильтр сканера. Порт показывается, если попал хотя бы в один `include`
+/// (пустой список — все порты) и ни в один `exclude`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PortFilter {
+    pub include: Vec<PortRange>,
+    pub exclude: Vec<PortRange>,
+    /// Имена процессов, которые не показываем вовсе (без учёта регистра и `.exe`)
+    pub ignored_processes: Vec<String>,
+}
+
+impl Default for PortFilter {
+    /// Как было до настроек: привилегированные порты < 1024 скрыты
+    fn default() -> Self {
+        PortFilter {
+            include: vec![PortRange::new(1024, u16::MAX)],
+            exclude: Vec::new(),
+            ignored_processes: Vec::new(),
+        }
+    }
+}
+
+impl PortFilter {
+    pub fn allows_port(&self, port: u16) -> bool {
+        let included = self.include.is_empty() || self.include.iter().any(|r| r.contains(port));
+        included && !self.exclude.iter().any(|r| r.contains(port))
+    }
+
+    pub fn allows_process(&self, name: &str) -> bool {
+        let name = normalize_process_name(name);
+        !self
+            .ignored_processes
+            .iter()
+            .any(|ignored| normalize_process_name(ignored) == name)
+    }
+}
+
+/// "Node.EXE" и "node" — один и тот же процесс
+fn normalize_process_name(name: &str) -> String {
+    let lower = name.trim().to_lowercase();
+    match lower.strip_suffix(".exe") {
+        Some(stem) => stem.to_string(),
+        None => lower,
+    }
+}
+
+/// Текущая версия схемы `settings.json`. Новые поля её не поднимают — их
+/// заполняет `#[serde(default)]`; поднимать, когда поле переименовано или
+/// поменяло смысл, и добавлять шаг в `migrate`.
+pub const SETTINGS_VERSION: u32 = 2;
+
+/// Границы интервала опроса — чтобы случайный 0 не положил CPU
+const MIN_POLL_INTERVAL_MS: u64 = 500;
+const MAX_POLL_INTERVAL_MS: u64 = 60_000;
+/// Дольше ждать мягкого завершения нет смысла — UI висит на «Зупиняю...»
+const MAX_STOP_TIMEOUT_MS: u64 = 30_000;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub version: u32,
+    /// Интервал фонового скана (наблюдатель шлёт `server-*` по отличиям)
+    pub poll_interval_ms: u64,
+    /// Крестик окна прячет его в трей, а не завершает приложение
+    pub close_to_tray: bool,
+    /// Сколько ждать освобождения порта после мягкого сигнала, потом — принудительно
+    pub stop_timeout_ms: u64,
+    /// Категории, свёрнутые в списке при запуске
+    pub collapsed_categories: Vec<String>,
+    pub port_filter: PortFilter,
+    pub notifications: NotificationSettings,
+    /// Буфер вывода запущенных из LocalDock процессов
+    pub logs: LogSettings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            version: SETTINGS_VERSION,
+            poll_interval_ms: 3000,
+            close_to_tray: true,
+            stop_timeout_ms: 5000,
+            collapsed_categories: vec!["system".to_string()],
+            port_filter: PortFilter::default(),
+            notifications: NotificationSettings::default(),
+            logs: LogSettings::default(),
+        }
+    }
+}
+
+/// Версия файла на диске. Нет поля `version` — формат v1 (только `port_filter`).
+/// None — файла нет или это не JSON.
+fn file_version(path: &Path) -> Option<u32> {
+    let content = fs::read_to_string(path).ok()?;
+    let value: Value = serde_json::from_str(&content).ok()?;
+    Some(version_of(&value))
+}
+
+fn version_of(value: &Value) -> u32 {
+    value
+        .get("version")
+        .and_then(Value::as_u64)
+        .map_or(1, |v| v as u32)
+}
+
+/// Поднять JSON старой версии до `SETTINGS_VERSION`, по одному шагу за раз
+fn migrate(mut value: Value) -> Value {
+    if !value.is_object() {
+        return value;
+    }
+    let mut version = version_of(&value);
+    while version < SETTINGS_VERSION {
+        // v1 → v2: в файле появилось поле `version`, остальные поля прежние.
+        // Шаги с переименованием полей — сюда, через match по version.
+        version += 1;
+        value["version"] = Value::from(version);
+    }
+    value
+}
+
+impl Settings {
+    /// Нет файла или он битый — настройки по умолчанию
+    pub fn load(path: &Path) -> Settings {
+        let settings: Settings = fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .map(migrate)
+            .and_then(|value| serde_json::from_value(value).ok())
+            .unwrap_or_default();
+        settings.normalized()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(path, json).map_err(|e| e.to_string())
+    }
+
+    /// Привести значения к допустимым: версия — текущая, остальное — в границах
+    fn normalized(mut self) -> Settings {
+        self.version = SETTINGS_VERSION;
+        self.poll_interval_ms = self
+            .poll_interval_ms
+            .clamp(MIN_POLL_INTERVAL_MS, MAX_POLL_INTERVAL_MS);
+        self.stop_timeout_ms = self.stop_timeout_ms.min(MAX_STOP_TIMEOUT_MS);
+        self.logs = self.logs.normalized();
+        self
+    }
+}
+
+/// Настройки в managed state Tauri + путь, куда их сохранять
+pub struct SettingsStore {
+    path: PathBuf,
+    settings: Mutex<Settings>,
+}
+
+impl SettingsStore {
+    /// Загрузить настройки. Файл переписывается, если его нет (чтобы было что
+    /// редактировать) или он старой версии; битый файл не трогаем.
+    pub fn open(path: PathBuf) -> SettingsStore {
+        let settings = Settings::load(&path);
+        let outdated = match file_version(&path) {
+            Some(version) => version < SETTINGS_VERSION,
+            None => !path.exists(),
+        };
+        if outdated {
+            let _ = settings.save(&path);
+        }
+        SettingsStore {
+            path,
+            settings: Mutex::new(settings),
+        }
+    }
+
+    pub fn get(&self) -> Settings {
+        self.settings.lock().map(|s| s.clone()).unwrap_or_default()
+    }
+
+    pub fn port_filter(&self) -> PortFilter {
+        self.get().port_filter
+    }
+
+    /// Сохранить новые настройки на диск и в память, вернуть итоговые (нормализованные)
+    pub fn update(&self, settings: Settings) -> Result<Settings, String> {
+        let settings = settings.normalized();
+        settings.save(&self.path)?;
+        let mut current = self.settings.lock().map_err(|e| e.to_string())?;
+        *current = settings.clone();
+        Ok(settings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_filter_hides_privileged_ports() {
+        let filter = PortFilter::default();
+        assert!(!filter.allows_port(80));
+        assert!(!filter.allows_port(1023));
+        assert!(filter.allows_port(1024));
+        assert!(filter.allows_port(u16::MAX));
+    }
+
+    #[test]
+    fn test_include_and_exclude_ranges() {
+        let filter = PortFilter {
+            include: vec![PortRange::new(80, 80), PortRange::new(3000, 9000)],
+            exclude: vec![PortRange::new(5000, 5099)],
+            ignored_processes: Vec::new(),
+        };
+        assert!(filter.allows_port(80));
+        assert!(!filter.allows_port(443));
+        assert!(filter.allows_port(3000));
+        assert!(!filter.allows_port(5040));
+        assert!(filter.allows_port(5173));
+        assert!(!filter.allows_port(9229));
+    }
+
+    #[test]
+    fn test_empty_include_means_all_ports() {
+        let filter = PortFilter {
+            include: Vec::new(),
+            exclude: vec![PortRange::new(53, 53)],
+            ignored_processes: Vec::new(),
+        };
+        assert!(filter.allows_port(22));
+        assert!(!filter.allows_port(53));
+    }
+
+    #[test]
+    fn test_ignored_processes_ignore_case_and_exe() {
+        let filter = PortFilter {
+            ignored_processes: vec!["svchost.exe".to_string(), "Spotify".to_string()],
+            ..PortFilter::default()
+        };
+        assert!(!filter.allows_process("SVCHOST.EXE"));
+        assert!(!filter.allows_process("svchost"));
+        assert!(!filter.allows_process("spotify.exe"));
+        assert!(filter.allows_process("node.exe"));
+    }
+
+    #[test]
+    fn test_load_missing_or_corrupt_file_gives_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        assert_eq!(Settings::load(&path), Settings::default());
+
+        fs::write(&path, "{ not json").unwrap();
+        assert_eq!(Settings::load(&path), Settings::default());
+    }
+
+    #[test]
+    fn test_partial_file_keeps_other_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        fs::write(
+            &path,
+            r#"{ "port_filter": { "exclude": [{ "start": 5353, "end": 5353 }] } }"#,
+        )
+        .unwrap();
+
+        let filter = Settings::load(&path).port_filter;
+        assert_eq!(filter.include, PortFilter::default().include);
+        assert_eq!(filter.exclude, vec![PortRange::new(5353, 5353)]);
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("settings.json");
+        let settings = Settings {
+            poll_interval_ms: 10_000,
+            close_to_tray: false,
+            port_filter: PortFilter {
+                include: vec![PortRange::new(1, u16::MAX)],
+                exclude: vec![PortRange::new(135, 139)],
+                ignored_processes: vec!["svchost.exe".to_string()],
+            },
+            ..Settings::default()
+        };
+        settings.save(&path).unwrap();
+        assert_eq!(Settings::load(&path), settings);
+    }
+
+    #[test]
+    fn test_store_writes_defaults_on_first_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        let store = SettingsStore::open(path.clone());
+        assert!(path.exists());
+        assert_eq!(store.port_filter(), PortFilter::default());
+    }
+
+    #[test]
+    fn test_migrate_v1_file() {
+        // Формат до версионирования: только port_filter, без version
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        fs::write(
+            &path,
+            r#"{ "port_filter": { "include": [{ "start": 1, "end": 65535 }] } }"#,
+        )
+        .unwrap();
+        assert_eq!(file_version(&path), Some(1));
+
+        let store = SettingsStore::open(path.clone());
+        let settings = store.get();
+        // Пользовательский фильтр сохранён, всё остальное — умолчания
+        let expected = Settings {
+            port_filter: PortFilter {
+                include: vec![PortRange::new(1, u16::MAX)],
+                ..PortFilter::default()
+            },
+            ..Settings::default()
+        };
+        assert_eq!(settings, expected);
+        // Файл переписан в новом формате
+        assert_eq!(file_version(&path), Some(SETTINGS_VERSION));
+        assert_eq!(Settings::load(&path), settings);
+    }
+
+    #[test]
+    fn test_open_keeps_corrupt_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        fs::write(&path, "{ \"poll_interval_ms\": ").unwrap();
+        let store = SettingsStore::open(path.clone());
+        assert_eq!(store.get(), Settings::default());
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "{ \"poll_interval_ms\": "
+        );
+    }
+
+    #[test]
+    fn test_update_persists_and_clamps() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        let store = SettingsStore::open(path.clone());
+
+        let saved = store
+            .update(Settings {
+                version: 0,
+                poll_interval_ms: 0,
+                stop_timeout_ms: 600_000,
+                logs: LogSettings {
+                    buffer_lines: 10_000_000,
+                    ..LogSettings::default()
+                },
+                ..Settings::default()
+            })
+            .unwrap();
+        assert_eq!(saved.version, SETTINGS_VERSION);
+        assert_eq!(saved.poll_interval_ms, MIN_POLL_INTERVAL_MS);
+        assert_eq!(saved.stop_timeout_ms, MAX_STOP_TIMEOUT_MS);
+        assert_eq!(saved.logs.buffer_lines, 100_000);
+        assert_eq!(store.get(), saved);
+        assert_eq!(Settings::load(&path), saved);
+    }
+
+    #[test]
+    fn test_current_file_without_new_fields_gets_defaults() {
+        // Добавленные поля версию не поднимают: файл v2 без них не переписывается
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        let content = r#"{ "version": 2, "poll_interval_ms": 5000 }"#;
+        fs::write(&path, content).unwrap();
+
+        let settings = SettingsStore::open(path.clone()).get();
+        assert_eq!(settings.version, SETTINGS_VERSION);
+        assert_eq!(settings.poll_interval_ms, 5000);
+        assert_eq!(settings.notifications, NotificationSettings::default());
+        assert_eq!(settings.stop_timeout_ms, 5000);
+        assert_eq!(settings.logs, LogSettings::default());
+        assert_eq!(fs::read_to_string(&path).unwrap(), content);
+    }
+}
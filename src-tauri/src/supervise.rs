@@ -0,0 +1,292 @@
+//! Политика перезапуска управляемых проектов: never / on-failure / always,
+//! экспоненциальная задержка, лимит перезапусков за окно и проверка здоровья
+//! (ожидаемый порт должен слушаться). Решения принимаются на каждом скане
+//! фонового наблюдателя; здесь — только состояние и расчёт, без процессов.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::logs::now_ms;
+
+/// Сколько последних событий перезапуска хранить на проект
+const MAX_EVENTS: usize = 20;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RestartMode {
+    #[default]
+    Never,
+    /// Только если процесс завершился с ошибкой или не прошёл проверку здоровья
+    OnFailure,
+    /// После любого выхода, кроме «Стоп» пользователя
+    Always,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RestartPolicy {
+    pub mode: RestartMode,
+    /// Больше перезапусков за `window_secs` — сдаёмся до ручного запуска
+    pub max_restarts: u32,
+    pub window_secs: u64,
+    /// Задержка первого перезапуска; каждый следующий в окне — вдвое дольше
+    pub backoff_ms: u64,
+    pub max_backoff_ms: u64,
+    /// Ожидаемый порт не слушается дольше этого — процесс перезапускается.
+    /// None — не проверять.
+    pub health_timeout_secs: Option<u64>,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        RestartPolicy {
+            mode: RestartMode::Never,
+            max_restarts: 5,
+            window_secs: 600,
+            backoff_ms: 1000,
+            max_backoff_ms: 60_000,
+            health_timeout_secs: None,
+        }
+    }
+}
+
+/// Почему процесс перезапускается
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ExitReason {
+    /// `code` — None, если процесс убит сигналом
+    Exited { code: Option<i32> },
+    /// Не открыл ожидаемый порт за `health_timeout_secs`
+    Unhealthy,
+}
+
+impl ExitReason {
+    fn is_failure(&self) -> bool {
+        !matches!(self, ExitReason::Exited { code: Some(0) })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RestartEvent {
+    pub timestamp_ms: u64,
+    pub reason: ExitReason,
+    /// Через сколько перезапуск; None — лимит исчерпан, больше не пробуем
+    pub delay_ms: Option<u64>,
+}
+
+/// Состояние надзора за одним проектом
+#[derive(Debug, Default)]
+pub struct Supervision {
+    /// Когда были запланированы перезапуски — для лимита за окно и задержки
+    restarts: VecDeque<Instant>,
+    /// Когда запустить снова
+    due: Option<Instant>,
+    /// Последний момент, когда ожидаемый порт слушался (или запуск)
+    healthy_at: Option<Instant>,
+    /// Процесс останавливаем мы (проверка здоровья) — выход считается `Unhealthy`
+    unhealthy: bool,
+    /// «Стоп» пользователя — выход не перезапускаем
+    stopping: bool,
+    events: VecDeque<RestartEvent>,
+}
+
+impl Supervision {
+    /// Новый запуск: ручной или по расписанию
+    pub fn started(&mut self, now: Instant) {
+        self.due = None;
+        self.healthy_at = Some(now);
+        self.unhealthy = false;
+        self.stopping = false;
+    }
+
+    /// «Стоп» пользователя: отменить запланированный перезапуск.
+    /// true — перезапуск был запланирован.
+    pub fn stop_requested(&mut self) -> bool {
+        self.stopping = true;
+        self.due.take().is_some()
+    }
+
+    /// Процесс завершился: запланировать перезапуск по политике
+    pub fn exited(&mut self, policy: &RestartPolicy, code: Option<i32>, now: Instant) {
+        self.healthy_at = None;
+        if self.stopping {
+            return;
+        }
+        let reason = if self.unhealthy {
+            ExitReason::Unhealthy
+        } else {
+            ExitReason::Exited { code }
+        };
+        let wanted = match policy.mode {
+            RestartMode::Never => false,
+            RestartMode::OnFailure => reason.is_failure(),
+            RestartMode::Always => true,
+        };
+        if !wanted {
+            return;
+        }
+
+        let window = Duration::from_secs(policy.window_secs);
+        while self
+            .restarts
+            .front()
+            .is_some_and(|&t| now.saturating_duration_since(t) >= window)
+        {
+            self.restarts.pop_front();
+        }
+        let delay = if self.restarts.len() >= policy.max_restarts as usize {
+            None
+        } else {
+            let shift = self.restarts.len().min(32) as u32;
+            let delay = policy
+                .backoff_ms
+                .saturating_mul(1 << shift)
+                .min(policy.max_backoff_ms);
+            self.restarts.push_back(now);
+            self.due = Some(now + Duration::from_millis(delay));
+            Some(delay)
+        };
+        if self.events.len() == MAX_EVENTS {
+            self.events.pop_front();
+        }
+        self.events.push_back(RestartEvent {
+            timestamp_ms: now_ms(),
+            reason,
+            delay_ms: delay,
+        });
+    }
+
+    /// Проверка здоровья на скане. true — порт молчит дольше таймаута,
+    /// процесс надо остановить (один раз на запуск).
+    pub fn check_health(&mut self, policy: &RestartPolicy, listening: bool, now: Instant) -> bool {
+        let (Some(timeout), Some(healthy_at)) = (policy.health_timeout_secs, self.healthy_at)
+        else {
+            return false;
+        };
+        if self.unhealthy || self.stopping || policy.mode == RestartMode::Never {
+            return false;
+        }
+        if listening {
+            self.healthy_at = Some(now);
+            return false;
+        }
+        let silent = now.saturating_duration_since(healthy_at);
+        self.unhealthy = silent > Duration::from_secs(timeout);
+        self.unhealthy
+    }
+
+    /// Пора перезапускать (снимает расписание)
+    pub fn take_due(&mut self, now: Instant) -> bool {
+        if self.due.is_some_and(|due| due <= now) {
+            self.due = None;
+            return true;
+        }
+        false
+    }
+
+    pub fn pending(&self) -> bool {
+        self.due.is_some()
+    }
+
+    pub fn events(&self) -> Vec<RestartEvent> {
+        self.events.iter().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(mode: RestartMode) -> RestartPolicy {
+        RestartPolicy {
+            mode,
+            max_restarts: 3,
+            window_secs: 60,
+            backoff_ms: 1000,
+            max_backoff_ms: 3000,
+            health_timeout_secs: Some(10),
+        }
+    }
+
+    fn delays(state: &Supervision) -> Vec<Option<u64>> {
+        state.events().iter().map(|e| e.delay_ms).collect()
+    }
+
+    #[test]
+    fn test_modes() {
+        let t0 = Instant::now();
+        let mut never = Supervision::default();
+        never.exited(&policy(RestartMode::Never), Some(1), t0);
+        assert!(!never.pending());
+
+        let mut on_failure = Supervision::default();
+        on_failure.exited(&policy(RestartMode::OnFailure), Some(0), t0);
+        assert!(!on_failure.pending());
+        on_failure.exited(&policy(RestartMode::OnFailure), None, t0);
+        assert!(on_failure.pending());
+        assert_eq!(
+            on_failure.events()[0].reason,
+            ExitReason::Exited { code: None }
+        );
+
+        let mut always = Supervision::default();
+        always.exited(&policy(RestartMode::Always), Some(0), t0);
+        assert!(always.pending());
+
+        // «Стоп» пользователя — не перезапускаем и отменяем расписание
+        let mut stopped = Supervision::default();
+        stopped.started(t0);
+        assert!(!stopped.stop_requested());
+        stopped.exited(&policy(RestartMode::Always), Some(1), t0);
+        assert!(!stopped.pending());
+        assert!(always.stop_requested());
+        assert!(!always.pending());
+    }
+
+    #[test]
+    fn test_backoff_and_limit_per_window() {
+        let policy = policy(RestartMode::OnFailure);
+        let mut state = Supervision::default();
+        let t0 = Instant::now();
+        for i in 0..4 {
+            let now = t0 + Duration::from_secs(i);
+            state.exited(&policy, Some(1), now);
+            if state.take_due(now + Duration::from_secs(5)) {
+                state.started(now);
+            }
+        }
+        // 1с, 2с, 3с (потолок), затем лимит 3 за минуту
+        assert_eq!(
+            delays(&state),
+            vec![Some(1000), Some(2000), Some(3000), None]
+        );
+        assert!(!state.pending());
+
+        // Окно прошло — снова с начальной задержки
+        state.exited(&policy, Some(1), t0 + Duration::from_secs(120));
+        assert_eq!(state.events().last().unwrap().delay_ms, Some(1000));
+        assert!(!state.take_due(t0 + Duration::from_millis(120_500)));
+        assert!(state.take_due(t0 + Duration::from_secs(121)));
+    }
+
+    #[test]
+    fn test_health_check_marks_exit_unhealthy() {
+        let policy = policy(RestartMode::OnFailure);
+        let mut state = Supervision::default();
+        let t0 = Instant::now();
+        state.started(t0);
+        assert!(!state.check_health(&policy, false, t0 + Duration::from_secs(5)));
+        assert!(!state.check_health(&policy, true, t0 + Duration::from_secs(8)));
+        // Порт был жив в 8с — таймаут считается от него
+        assert!(!state.check_health(&policy, false, t0 + Duration::from_secs(15)));
+        assert!(state.check_health(&policy, false, t0 + Duration::from_secs(19)));
+        // Второй раз не просим остановить
+        assert!(!state.check_health(&policy, false, t0 + Duration::from_secs(20)));
+
+        // Остановили мы — выход с кодом 0 всё равно сбой
+        state.exited(&policy, Some(0), t0 + Duration::from_secs(21));
+        assert_eq!(state.events()[0].reason, ExitReason::Unhealthy);
+        assert!(state.pending());
+    }
+}
@@ -0,0 +1,198 @@
+//! Дерево процессов по PPID — для режима «Стоп дерево». От процесса с портом
+//! поднимаемся через обёртки-запускалки (`npm run dev`, `sh -c`, `cmd /c`)
+//! и останавливаем корень вместе со всеми потомками.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::catalog::{arg_token, normalize};
+use crate::scanner::ProcessParent;
+
+/// Пакетные менеджеры: сами ничего не слушают, только запускают скрипт
+const LAUNCHERS: &[&str] = &["npm", "npx", "pnpm", "pnpx", "yarn", "bunx"];
+/// То же, но запущенные как `node <путь>/npm-cli.js`
+const LAUNCHER_SCRIPTS: &[&str] = &["npm-cli", "npx-cli", "pnpm", "yarn"];
+
+/// Запускалка, через которую можно подняться к корню дерева.
+/// Интерактивные оболочки — нет: иначе «Стоп» закроет терминал пользователя.
+pub fn is_launcher(name: &str, args: &[String]) -> bool {
+    let name = normalize(name);
+    if LAUNCHERS.contains(&name.as_str()) {
+        return true;
+    }
+    let rest = args.get(1..).unwrap_or_default();
+    match name.as_str() {
+        "node" | "bun" => rest
+            .iter()
+            .take(2)
+            .any(|a| LAUNCHER_SCRIPTS.contains(&arg_token(a).as_str())),
+        "cmd" => rest.iter().any(|a| a.eq_ignore_ascii_case("/c")),
+        "sh" | "bash" | "dash" | "zsh" => rest.iter().any(|a| a == "-c"),
+        _ => false,
+    }
+}
+
+pub struct ProcessTree {
+    parents: HashMap<u32, u32>,
+    children: HashMap<u32, Vec<u32>>,
+}
+
+impl ProcessTree {
+    /// `parents`: снимок `SystemProbe::parent_pids`. Связь с родителем верим,
+    /// только если родитель жив (есть в снимке) и запущен не позже ребёнка:
+    /// иначе это чужой процесс, получивший PID умершего родителя.
+    pub fn new(snapshot: HashMap<u32, ProcessParent>) -> Self {
+        let parents: HashMap<u32, u32> = snapshot
+            .iter()
+            .filter(|(_, p)| {
+                snapshot.get(&p.ppid).is_some_and(|parent| {
+                    // Время неизвестно — проверить нечем, оставляем
+                    match (parent.started, p.started) {
+                        (Some(parent), Some(child)) => parent <= child,
+                        _ => true,
+                    }
+                })
+            })
+            .map(|(&pid, p)| (pid, p.ppid))
+            .collect();
+        let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+        for (&pid, &ppid) in &parents {
+            if pid != ppid {
+                children.entry(ppid).or_default().push(pid);
+            }
+        }
+        for list in children.values_mut() {
+            list.sort_unstable();
+        }
+        ProcessTree { parents, children }
+    }
+
+    fn parent(&self, pid: u32) -> Option<u32> {
+        self.parents
+            .get(&pid)
+            .copied()
+            .filter(|&ppid| ppid != 0 && ppid != pid)
+    }
+
+    /// Предки от ближайшего к дальнему (без циклов — Windows переиспользует PID)
+    pub fn ancestors(&self, pid: u32) -> Vec<u32> {
+        let mut seen = HashSet::from([pid]);
+        let mut result = Vec::new();
+        let mut current = pid;
+        while let Some(ppid) = self.parent(current) {
+            if !seen.insert(ppid) {
+                break;
+            }
+            result.push(ppid);
+            current = ppid;
+        }
+        result
+    }
+
+    /// Самый верхний предок, до которого вся цепочка — запускалки
+    pub fn launcher_root(&self, pid: u32, is_launcher: impl Fn(u32) -> bool) -> u32 {
+        self.ancestors(pid)
+            .into_iter()
+            .take_while(|&p| is_launcher(p))
+            .last()
+            .unwrap_or(pid)
+    }
+
+    /// Корень и все потомки в ширину, корень первым
+    pub fn subtree(&self, root: u32) -> Vec<u32> {
+        let mut seen = HashSet::from([root]);
+        let mut result = vec![root];
+        let mut queue = VecDeque::from([root]);
+        while let Some(pid) = queue.pop_front() {
+            for &child in self.children.get(&pid).into_iter().flatten() {
+                if seen.insert(child) {
+                    result.push(child);
+                    queue.push_back(child);
+                }
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+impl ProcessTree {
+    /// Дерево из пар (PID, PPID): все упомянутые родители живы, время запуска неизвестно
+    pub fn from_edges(edges: &[(u32, u32)]) -> Self {
+        let unknown = |ppid| ProcessParent {
+            ppid,
+            started: None,
+        };
+        let mut snapshot: HashMap<u32, ProcessParent> =
+            edges.iter().map(|&(_, ppid)| (ppid, unknown(0))).collect();
+        snapshot.extend(edges.iter().map(|&(pid, ppid)| (pid, unknown(ppid))));
+        ProcessTree::new(snapshot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_is_launcher() {
+        assert!(is_launcher("npm", &args("npm run dev")));
+        assert!(is_launcher("pnpm.exe", &args("pnpm dev")));
+        assert!(is_launcher(
+            "node.exe",
+            &args("node C:\\nodejs\\node_modules\\npm\\bin\\npm-cli.js run dev")
+        ));
+        assert!(is_launcher("sh", &args("sh -c vite")));
+        assert!(is_launcher("cmd.exe", &args("cmd.exe /d /s /C vite")));
+
+        assert!(!is_launcher("node", &args("node vite.js")));
+        assert!(!is_launcher("bash", &args("bash")));
+        assert!(!is_launcher("cmd.exe", &args("cmd.exe")));
+        assert!(!is_launcher("explorer.exe", &[]));
+    }
+
+    #[test]
+    fn test_launcher_root_stops_at_interactive_shell() {
+        // bash(10) → npm(11) → sh -c(12) → node vite(13) → esbuild(14)
+        let tree = ProcessTree::from_edges(&[(10, 1), (11, 10), (12, 11), (13, 12), (14, 13)]);
+        let launchers = [11, 12];
+        assert_eq!(tree.ancestors(13), vec![12, 11, 10, 1]);
+        assert_eq!(tree.launcher_root(13, |p| launchers.contains(&p)), 11);
+        assert_eq!(tree.subtree(11), vec![11, 12, 13, 14]);
+        // Без запускалок — сам процесс
+        assert_eq!(tree.launcher_root(13, |_| false), 13);
+    }
+
+    #[test]
+    fn test_cycle_from_pid_reuse() {
+        let tree = ProcessTree::from_edges(&[(5, 6), (6, 5), (7, 7)]);
+        assert_eq!(tree.ancestors(5), vec![6]);
+        assert_eq!(tree.launcher_root(5, |_| true), 6);
+        assert_eq!(tree.subtree(5), vec![5, 6]);
+        assert_eq!(tree.ancestors(7), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn test_reused_parent_pid_is_not_a_parent() {
+        let entry = |ppid, started| ProcessParent {
+            ppid,
+            started: Some(started),
+        };
+        // Родитель 21 умер, его PID занял процесс 20, запущенный позже ребёнка;
+        // родитель 30 умер, и PID никому не достался
+        let tree = ProcessTree::new(HashMap::from([
+            (20, entry(1, 500)),
+            (21, entry(20, 100)),
+            (22, entry(20, 600)),
+            (30, entry(25, 100)),
+        ]));
+        assert_eq!(tree.subtree(20), vec![20, 22]);
+        assert_eq!(tree.ancestors(21), Vec::<u32>::new());
+        assert_eq!(tree.launcher_root(21, |_| true), 21);
+        assert_eq!(tree.ancestors(22), vec![20]);
+        assert_eq!(tree.ancestors(30), Vec::<u32>::new());
+    }
+}
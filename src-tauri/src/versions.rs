@@ -0,0 +1,974 @@
+//! Версии зависимостей проекта для детекторов фреймворка. Объявленные — из
+//! манифестов (package.json, Cargo.toml, go.mod, requirements.txt,
+//! pyproject.toml, Gemfile, composer.json, pom.xml, build.gradle, mix.exs,
+//! *.csproj), установленные — из node_modules и lock-файлов
+//! (package-lock.json, pnpm-lock.yaml, yarn.lock, bun.lock, Cargo.lock,
+//! poetry.lock, Gemfile.lock, composer.lock, mix.lock). Lock-файлы и
+//! манифесты без готового парсера разбираются построчно: нужна только
+//! версия одного пакета.
+
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ecosystem {
+    Npm,
+    Cargo,
+    Go,
+    Python,
+    Ruby,
+    Composer,
+    /// artifactId из pom.xml и build.gradle, id плагинов Gradle
+    Maven,
+    /// Mix (Elixir)
+    Hex,
+    /// PackageReference и Sdk из *.csproj
+    Nuget,
+}
+
+impl Ecosystem {
+    /// Шаблон зависимости в правиле: "cargo:axum", "go:github.com/gin-gonic/gin",
+    /// "pypi:fastapi", "gem:rails", "composer:laravel/framework",
+    /// "maven:spring-boot-starter-web", "hex:phoenix", "nuget:Microsoft.AspNetCore.*";
+    /// без префикса (или "npm:") — пакет npm
+    pub fn split(pattern: &str) -> (Ecosystem, &str) {
+        let prefixes = [
+            ("npm:", Ecosystem::Npm),
+            ("cargo:", Ecosystem::Cargo),
+            ("go:", Ecosystem::Go),
+            ("pypi:", Ecosystem::Python),
+            ("gem:", Ecosystem::Ruby),
+            ("composer:", Ecosystem::Composer),
+            ("maven:", Ecosystem::Maven),
+            ("hex:", Ecosystem::Hex),
+            ("nuget:", Ecosystem::Nuget),
+        ];
+        prefixes
+            .into_iter()
+            .find_map(|(prefix, ecosystem)| Some((ecosystem, pattern.strip_prefix(prefix)?)))
+            .unwrap_or((Ecosystem::Npm, pattern))
+    }
+}
+
+/// Зависимость, объявленная в манифесте
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Declared {
+    pub ecosystem: Ecosystem,
+    /// Для Python — нормализованное имя (PEP 503): "Flask_Login" → "flask-login"
+    pub name: String,
+    /// Как объявлена: "^14.0.0", "0.7", ">=0.100"; пусто — без версии
+    pub range: String,
+    /// Где найдена: "package.json: dependencies.next"
+    pub evidence: String,
+}
+
+/// Установленная версия и откуда она
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Installed {
+    pub version: String,
+    pub source: String,
+}
+
+/// "Flask_Login" → "flask-login"
+pub fn normalize_python(name: &str) -> String {
+    let lower = name.to_lowercase();
+    let parts: Vec<&str> = lower
+        .split(['-', '_', '.'])
+        .filter(|p| !p.is_empty())
+        .collect();
+    parts.join("-")
+}
+
+/// Все зависимости из манифестов в папке
+pub fn declared(dir: &Path) -> Vec<Declared> {
+    let read = |name: &str| fs::read_to_string(dir.join(name)).ok();
+    let mut deps = Vec::new();
+    if let Some(json) = read("package.json").and_then(|t| serde_json::from_str(&t).ok()) {
+        deps.extend(npm_declared(&json));
+    }
+    if let Some(text) = read("Cargo.toml") {
+        deps.extend(cargo_declared(&text));
+    }
+    if let Some(text) = read("go.mod") {
+        deps.extend(
+            go_requires(&text)
+                .into_iter()
+                .map(|(name, version)| Declared {
+                    ecosystem: Ecosystem::Go,
+                    evidence: format!("go.mod: require {}", name),
+                    name,
+                    range: version,
+                }),
+        );
+    }
+    if let Some(text) = read("requirements.txt") {
+        deps.extend(
+            requirements(&text)
+                .into_iter()
+                .map(|(name, range)| Declared {
+                    ecosystem: Ecosystem::Python,
+                    evidence: format!("requirements.txt: {}", name),
+                    name,
+                    range,
+                }),
+        );
+    }
+    if let Some(text) = read("pyproject.toml") {
+        deps.extend(pyproject_declared(&text));
+    }
+    if let Some(text) = read("Gemfile") {
+        deps.extend(gemfile_declared(&text));
+    }
+    if let Some(json) = read("composer.json").and_then(|t| serde_json::from_str(&t).ok()) {
+        deps.extend(composer_declared(&json));
+    }
+    if let Some(text) = read("pom.xml") {
+        deps.extend(pom_declared(&text));
+    }
+    for file in ["build.gradle", "build.gradle.kts"] {
+        if let Some(text) = read(file) {
+            deps.extend(gradle_declared(&text, file));
+        }
+    }
+    if let Some(text) = read("mix.exs") {
+        deps.extend(mix_declared(&text));
+    }
+    for file in files_with_extension(dir, "csproj") {
+        if let Some(text) = read(&file) {
+            deps.extend(csproj_declared(&text, &file));
+        }
+    }
+    deps
+}
+
+/// Имена файлов папки с расширением, по алфавиту
+pub fn files_with_extension(dir: &Path, extension: &str) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<String> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_file() && p.extension().is_some_and(|e| e == extension))
+        .filter_map(|p| Some(p.file_name()?.to_str()?.to_string()))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Строковые литералы строки в кавычках: `gem "rails", '~> 7.1'` → [rails, ~> 7.1]
+fn quoted(line: &str) -> Vec<&str> {
+    let mut values = Vec::new();
+    let mut rest = line;
+    while let Some(start) = rest.find(['"', '\'']) {
+        let quote = &rest[start..start + 1];
+        let after = &rest[start + 1..];
+        let Some(end) = after.find(quote) else {
+            break;
+        };
+        values.push(&after[..end]);
+        rest = &after[end + 1..];
+    }
+    values
+}
+
+/// `gem "rails", "~> 7.1", ">= 7.1.2"`; версий может не быть
+fn gemfile_declared(text: &str) -> Vec<Declared> {
+    text.lines()
+        .filter_map(|line| {
+            let rest = line.trim().strip_prefix("gem ")?;
+            let values = quoted(rest.split('#').next()?);
+            let (name, ranges) = values.split_first()?;
+            Some(Declared {
+                ecosystem: Ecosystem::Ruby,
+                name: name.to_string(),
+                range: ranges.join(", "),
+                evidence: format!("Gemfile: gem {}", name),
+            })
+        })
+        .collect()
+}
+
+fn composer_declared(json: &Value) -> Vec<Declared> {
+    let mut deps = Vec::new();
+    for field in ["require", "require-dev"] {
+        let Some(map) = json.get(field).and_then(Value::as_object) else {
+            continue;
+        };
+        for (name, range) in map {
+            deps.push(Declared {
+                ecosystem: Ecosystem::Composer,
+                name: name.clone(),
+                range: range.as_str().unwrap_or("").to_string(),
+                evidence: format!("composer.json: {}.{}", field, name),
+            });
+        }
+    }
+    deps
+}
+
+/// Текст первого `<tag>...</tag>` во фрагменте XML
+fn xml_value<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let start = xml.find(&format!("<{}>", tag))? + tag.len() + 2;
+    let end = xml[start..].find(&format!("</{}>", tag))?;
+    Some(xml[start..start + end].trim())
+}
+
+/// Блоки `<tag>...</tag>` по порядку
+fn xml_blocks<'a>(xml: &'a str, tag: &str) -> Vec<&'a str> {
+    let (open, close) = (format!("<{}>", tag), format!("</{}>", tag));
+    let mut blocks = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find(&open) {
+        let after = &rest[start + open.len()..];
+        let Some(end) = after.find(&close) else {
+            break;
+        };
+        blocks.push(&after[..end]);
+        rest = &after[end + close.len()..];
+    }
+    blocks
+}
+
+/// `<parent>` (spring-boot-starter-parent) и `<dependency>`: artifactId и version
+fn pom_declared(text: &str) -> Vec<Declared> {
+    let mut deps = Vec::new();
+    for tag in ["parent", "dependency"] {
+        for block in xml_blocks(text, tag) {
+            let Some(artifact) = xml_value(block, "artifactId") else {
+                continue;
+            };
+            deps.push(Declared {
+                ecosystem: Ecosystem::Maven,
+                name: artifact.to_string(),
+                // "${spring.version}" — не версия
+                range: xml_value(block, "version")
+                    .filter(|v| !v.starts_with('$'))
+                    .unwrap_or("")
+                    .to_string(),
+                evidence: format!("pom.xml: {} {}", tag, artifact),
+            });
+        }
+    }
+    deps
+}
+
+/// Groovy и Kotlin DSL: `id 'org.springframework.boot' version '3.2.0'`,
+/// `implementation("group:artifact:version")`
+fn gradle_declared(text: &str, file: &str) -> Vec<Declared> {
+    let mut deps = Vec::new();
+    for line in text.lines().map(str::trim) {
+        let values = quoted(line);
+        if line.starts_with("id ") || line.starts_with("id(") {
+            if let Some(plugin) = values.first() {
+                deps.push(Declared {
+                    ecosystem: Ecosystem::Maven,
+                    name: plugin.to_string(),
+                    range: values.get(1).unwrap_or(&"").to_string(),
+                    evidence: format!("{}: plugin {}", file, plugin),
+                });
+            }
+            continue;
+        }
+        for coordinate in values {
+            let parts: Vec<&str> = coordinate.split(':').collect();
+            // Не координата: "https://repo.spring.io", "$kotlinVersion"
+            if parts.len() < 2
+                || parts
+                    .iter()
+                    .any(|p| p.is_empty() || p.contains([' ', '/', '$']))
+            {
+                continue;
+            }
+            deps.push(Declared {
+                ecosystem: Ecosystem::Maven,
+                name: parts[1].to_string(),
+                range: parts.get(2).unwrap_or(&"").to_string(),
+                evidence: format!("{}: {}", file, parts[1]),
+            });
+        }
+    }
+    deps
+}
+
+/// `{:phoenix, "~> 1.7.10"}`; `{:dep, git: ...}` — без версии
+fn mix_declared(text: &str) -> Vec<Declared> {
+    let mut deps = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{:") {
+        rest = &rest[start + 2..];
+        let end = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        let name = &rest[..end];
+        let after = rest[end..].trim_start();
+        if name.is_empty() || !after.starts_with(',') {
+            continue;
+        }
+        let range = after[1..]
+            .trim_start()
+            .strip_prefix('"')
+            .and_then(|r| r.split('"').next())
+            .unwrap_or("");
+        deps.push(Declared {
+            ecosystem: Ecosystem::Hex,
+            name: name.to_string(),
+            range: range.to_string(),
+            evidence: format!("mix.exs: {}", name),
+        });
+    }
+    deps
+}
+
+/// Значение атрибута XML: `Include="Serilog"` → Serilog
+fn xml_attribute<'a>(element: &'a str, name: &str) -> Option<&'a str> {
+    let start = element.find(&format!("{}=\"", name))? + name.len() + 2;
+    let end = element[start..].find('"')?;
+    Some(&element[start..start + end])
+}
+
+/// `<Project Sdk="Microsoft.NET.Sdk.Web">` — SDK как зависимость без версии;
+/// `<PackageReference Include="..." Version="..." />`
+fn csproj_declared(text: &str, file: &str) -> Vec<Declared> {
+    let mut deps = Vec::new();
+    for element in text.split('<').skip(1) {
+        let (name, range, what) = if element.starts_with("Project ") {
+            let Some(sdk) = xml_attribute(element, "Sdk") else {
+                continue;
+            };
+            (sdk, "", "Sdk")
+        } else if element.starts_with("PackageReference ") {
+            let Some(package) = xml_attribute(element, "Include") else {
+                continue;
+            };
+            let version = xml_attribute(element, "Version").unwrap_or("");
+            (package, version, "PackageReference")
+        } else {
+            continue;
+        };
+        deps.push(Declared {
+            ecosystem: Ecosystem::Nuget,
+            name: name.to_string(),
+            range: range.to_string(),
+            evidence: format!("{}: {} {}", file, what, name),
+        });
+    }
+    deps
+}
+
+fn npm_declared(json: &Value) -> Vec<Declared> {
+    let mut deps = Vec::new();
+    for field in ["dependencies", "devDependencies"] {
+        let Some(map) = json.get(field).and_then(Value::as_object) else {
+            continue;
+        };
+        for (name, range) in map {
+            deps.push(Declared {
+                ecosystem: Ecosystem::Npm,
+                name: name.clone(),
+                range: range.as_str().unwrap_or("").to_string(),
+                evidence: format!("package.json: {}.{}", field, name),
+            });
+        }
+    }
+    deps
+}
+
+/// `axum = "0.7"` или `axum = { version = "0.7", features = [...] }`
+fn toml_version(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(version) => version.clone(),
+        other => other
+            .get("version")
+            .and_then(toml::Value::as_str)
+            .unwrap_or("")
+            .to_string(),
+    }
+}
+
+fn cargo_declared(text: &str) -> Vec<Declared> {
+    let Ok(manifest) = text.parse::<toml::Table>() else {
+        return Vec::new();
+    };
+    let mut deps = Vec::new();
+    for field in ["dependencies", "dev-dependencies"] {
+        let Some(table) = manifest.get(field).and_then(toml::Value::as_table) else {
+            continue;
+        };
+        for (name, value) in table {
+            deps.push(Declared {
+                ecosystem: Ecosystem::Cargo,
+                name: name.clone(),
+                range: toml_version(value),
+                evidence: format!("Cargo.toml: {}.{}", field, name),
+            });
+        }
+    }
+    deps
+}
+
+/// `require x v1` и блок `require ( ... )`; `// indirect` тоже — версия точная
+fn go_requires(text: &str) -> Vec<(String, String)> {
+    let mut requires = Vec::new();
+    let mut in_block = false;
+    for line in text.lines() {
+        let line = line.split("//").next().unwrap_or("").trim();
+        let entry = if in_block {
+            if line == ")" {
+                in_block = false;
+                continue;
+            }
+            line
+        } else if let Some(rest) = line.strip_prefix("require") {
+            let rest = rest.trim();
+            if rest == "(" {
+                in_block = true;
+                continue;
+            }
+            rest
+        } else {
+            continue;
+        };
+        let mut parts = entry.split_whitespace();
+        if let (Some(module), Some(version)) = (parts.next(), parts.next()) {
+            requires.push((module.to_string(), version.to_string()));
+        }
+    }
+    requires
+}
+
+/// Строка PEP 508 "fastapi[all]>=0.100; python_version>'3.8'" → (имя, диапазон)
+fn pep508(spec: &str) -> Option<(String, String)> {
+    let spec = spec.split(';').next()?.trim();
+    let end = spec
+        .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+        .unwrap_or(spec.len());
+    if end == 0 {
+        return None;
+    }
+    let rest = spec[end..].trim_start();
+    // Extras "[all]" к версии не относятся
+    let range = match rest.strip_prefix('[') {
+        Some(after) => after.split_once(']').map_or("", |(_, r)| r),
+        None => rest,
+    };
+    Some((normalize_python(&spec[..end]), range.trim().to_string()))
+}
+
+/// Пакеты requirements.txt; опции (`-r`, `-e`, `--index-url`) пропускаются
+fn requirements(text: &str) -> Vec<(String, String)> {
+    text.lines()
+        .map(|line| line.split(" #").next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty() && !line.starts_with(['#', '-']))
+        .filter_map(pep508)
+        .collect()
+}
+
+/// PEP 621 `[project] dependencies` и `[tool.poetry.dependencies]`
+fn pyproject_declared(text: &str) -> Vec<Declared> {
+    let Ok(manifest) = text.parse::<toml::Table>() else {
+        return Vec::new();
+    };
+    let mut deps = Vec::new();
+    let project = manifest
+        .get("project")
+        .and_then(|p| p.get("dependencies"))
+        .and_then(toml::Value::as_array);
+    for spec in project
+        .into_iter()
+        .flatten()
+        .filter_map(toml::Value::as_str)
+    {
+        if let Some((name, range)) = pep508(spec) {
+            deps.push(Declared {
+                ecosystem: Ecosystem::Python,
+                evidence: format!("pyproject.toml: project.dependencies.{}", name),
+                name,
+                range,
+            });
+        }
+    }
+    let poetry = manifest
+        .get("tool")
+        .and_then(|t| t.get("poetry"))
+        .and_then(|p| p.get("dependencies"))
+        .and_then(toml::Value::as_table);
+    for (name, value) in poetry.into_iter().flatten() {
+        if name == "python" {
+            continue;
+        }
+        let name = normalize_python(name);
+        deps.push(Declared {
+            ecosystem: Ecosystem::Python,
+            evidence: format!("pyproject.toml: tool.poetry.dependencies.{}", name),
+            name,
+            range: toml_version(value),
+        });
+    }
+    deps
+}
+
+/// Версия пакета из текста lock-файла
+type LockParser = fn(&str, &str) -> Option<String>;
+
+/// Установленная версия пакета: node_modules и lock-файлы по очереди
+pub fn installed(dir: &Path, ecosystem: Ecosystem, name: &str) -> Option<Installed> {
+    let read = |file: &str| fs::read_to_string(dir.join(file)).ok();
+    let found = |version: String, source: &str| Installed {
+        version,
+        source: source.to_string(),
+    };
+    match ecosystem {
+        Ecosystem::Npm => {
+            let module = format!("node_modules/{}/package.json", name);
+            if let Some(version) = read(&module)
+                .and_then(|t| serde_json::from_str::<Value>(&t).ok())
+                .and_then(|json| Some(json.get("version")?.as_str()?.to_string()))
+            {
+                return Some(found(version, &module));
+            }
+            let lockfiles: [(&str, LockParser); 4] = [
+                ("package-lock.json", from_package_lock),
+                ("pnpm-lock.yaml", from_pnpm_lock),
+                ("yarn.lock", from_yarn_lock),
+                ("bun.lock", from_bun_lock),
+            ];
+            lockfiles
+                .into_iter()
+                .find_map(|(file, parse)| Some(found(parse(&read(file)?, name)?, file)))
+        }
+        Ecosystem::Cargo => {
+            let version = from_toml_lock(&read("Cargo.lock")?, name, |n| n.to_string())?;
+            Some(found(version, "Cargo.lock"))
+        }
+        Ecosystem::Go => {
+            let (_, version) = go_requires(&read("go.mod")?)
+                .into_iter()
+                .find(|(module, _)| module == name)?;
+            Some(found(version, "go.mod"))
+        }
+        Ecosystem::Python => {
+            if let Some(version) =
+                read("poetry.lock").and_then(|t| from_toml_lock(&t, name, normalize_python))
+            {
+                return Some(found(version, "poetry.lock"));
+            }
+            // Закреплённая версия "fastapi==0.110.0" — это и есть установленная
+            let (_, range) = requirements(&read("requirements.txt")?)
+                .into_iter()
+                .find(|(package, _)| package == name)?;
+            let pinned = range.strip_prefix("==")?.trim();
+            (!pinned.contains('*')).then(|| found(pinned.to_string(), "requirements.txt"))
+        }
+        Ecosystem::Ruby => {
+            let version = from_gemfile_lock(&read("Gemfile.lock")?, name)?;
+            Some(found(version, "Gemfile.lock"))
+        }
+        Ecosystem::Composer => {
+            let version = from_composer_lock(&read("composer.lock")?, name)?;
+            Some(found(version, "composer.lock"))
+        }
+        Ecosystem::Hex => {
+            let version = from_mix_lock(&read("mix.lock")?, name)?;
+            Some(found(version, "mix.lock"))
+        }
+        // Lock-файлов нет: только объявленная версия
+        Ecosystem::Maven | Ecosystem::Nuget => None,
+    }
+}
+
+/// v2/v3: `packages["node_modules/next"].version`; v1: `dependencies.next.version`
+fn from_package_lock(text: &str, name: &str) -> Option<String> {
+    let lock: Value = serde_json::from_str(text).ok()?;
+    let package = lock
+        .get("packages")
+        .and_then(|p| p.get(format!("node_modules/{}", name)))
+        .or_else(|| lock.get("dependencies")?.get(name))?;
+    Some(package.get("version")?.as_str()?.to_string())
+}
+
+/// "14.1.0(react@18.2.0)" / "13.4.0_react@18.2.0" → "14.1.0"; не версия — None
+fn pnpm_version(raw: &str) -> Option<String> {
+    let raw = raw.trim().trim_matches(['\'', '"']);
+    let version = raw.split(['(', '_']).next()?;
+    version
+        .starts_with(|c: char| c.is_ascii_digit())
+        .then(|| version.to_string())
+}
+
+/// Импортёр (`next:` + вложенный `version:`; в v5 — `next: 13.4.0`),
+/// иначе ключ раздела packages: `/next@14.1.0:`, `next@14.1.0:`, `/next/13.4.0:`
+fn from_pnpm_lock(text: &str, name: &str) -> Option<String> {
+    let lines: Vec<&str> = text.lines().collect();
+    let keys = [
+        format!("{}:", name),
+        format!("'{}':", name),
+        format!("\"{}\":", name),
+    ];
+    for (i, line) in lines.iter().enumerate() {
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+        let Some(rest) = keys
+            .iter()
+            .find_map(|key| trimmed.strip_prefix(key.as_str()))
+        else {
+            continue;
+        };
+        if !rest.trim().is_empty() {
+            if let Some(version) = pnpm_version(rest) {
+                return Some(version);
+            }
+            continue;
+        }
+        for nested in &lines[i + 1..] {
+            let inner = nested.trim_start();
+            if nested.len() - inner.len() <= indent {
+                break;
+            }
+            if let Some(version) = inner.strip_prefix("version:").and_then(pnpm_version) {
+                return Some(version);
+            }
+        }
+    }
+    lines.iter().find_map(|line| {
+        let key = line.trim().strip_suffix(':')?;
+        let key = key.trim_matches(['\'', '"']).trim_start_matches('/');
+        let rest = key.strip_prefix(name)?;
+        pnpm_version(rest.strip_prefix('@').or_else(|| rest.strip_prefix('/'))?)
+    })
+}
+
+/// Заголовок записи: `next@^14.0.0, next@^14.1.0:` (v1) или
+/// `"next@npm:^14.0.0":` (berry); версия — в строке `version` под ним
+fn from_yarn_lock(text: &str, name: &str) -> Option<String> {
+    let mut matched = false;
+    for line in text.lines() {
+        if !line.starts_with(' ') {
+            let Some(header) = line.strip_suffix(':') else {
+                matched = false;
+                continue;
+            };
+            matched = header.split(", ").any(|entry| {
+                let entry = entry.trim_matches('"');
+                // "@scope/pkg@^1" — @ в начале часть имени
+                entry
+                    .char_indices()
+                    .skip(1)
+                    .find(|&(_, c)| c == '@')
+                    .is_some_and(|(at, _)| &entry[..at] == name)
+            });
+            continue;
+        }
+        if !matched {
+            continue;
+        }
+        let line = line.trim();
+        if let Some(version) = line.strip_prefix("version") {
+            let version = version.trim_start_matches(':').trim().trim_matches('"');
+            return Some(version.to_string());
+        }
+    }
+    None
+}
+
+/// Текстовый bun.lock (JSONC): `"next": ["next@14.1.0", ...]`
+fn from_bun_lock(text: &str, name: &str) -> Option<String> {
+    let prefix = format!("\"{}\": [\"{}@", name, name);
+    text.lines().find_map(|line| {
+        let rest = line.trim_start().strip_prefix(prefix.as_str())?;
+        let version = rest.split('"').next()?;
+        (!version.is_empty()).then(|| version.to_string())
+    })
+}
+
+/// Раздел specs: `    rails (7.1.3)` — ровно четыре пробела (глубже — зависимости гема)
+fn from_gemfile_lock(text: &str, name: &str) -> Option<String> {
+    let prefix = format!("    {} (", name);
+    text.lines().find_map(|line| {
+        let rest = line.strip_prefix(prefix.as_str())?;
+        // "1.16.2-x86_64-linux" — версия с платформой
+        let version = rest.strip_suffix(')')?.split('-').next()?;
+        Some(version.to_string())
+    })
+}
+
+/// `packages[]` и `packages-dev[]`: "v6.4.3" → "6.4.3"
+fn from_composer_lock(text: &str, name: &str) -> Option<String> {
+    let lock: Value = serde_json::from_str(text).ok()?;
+    ["packages", "packages-dev"]
+        .iter()
+        .filter_map(|field| lock.get(field)?.as_array())
+        .flatten()
+        .find(|package| package.get("name").and_then(Value::as_str) == Some(name))
+        .and_then(|package| package.get("version")?.as_str())
+        .map(|version| version.trim_start_matches('v').to_string())
+}
+
+/// `"phoenix": {:hex, :phoenix, "1.7.10", ...}`
+fn from_mix_lock(text: &str, name: &str) -> Option<String> {
+    let prefix = format!("\"{}\": {{:hex, :{}, \"", name, name);
+    text.lines().find_map(|line| {
+        let rest = line.trim_start().strip_prefix(prefix.as_str())?;
+        Some(rest.split('"').next()?.to_string())
+    })
+}
+
+/// Cargo.lock и poetry.lock: `[[package]]` с `name` и `version`
+fn from_toml_lock(text: &str, name: &str, normalize: fn(&str) -> String) -> Option<String> {
+    let lock = text.parse::<toml::Table>().ok()?;
+    let packages = lock.get("package")?.as_array()?;
+    packages.iter().find_map(|package| {
+        let package_name = package.get("name")?.as_str()?;
+        if normalize(package_name) != name {
+            return None;
+        }
+        Some(package.get("version")?.as_str()?.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_npm_lockfiles() {
+        let package_lock = r#"{
+            "lockfileVersion": 3,
+            "packages": {
+                "": { "dependencies": { "next": "^14.0.0" } },
+                "node_modules/next": { "version": "14.1.0" }
+            }
+        }"#;
+        assert_eq!(
+            from_package_lock(package_lock, "next").as_deref(),
+            Some("14.1.0")
+        );
+        let v1 = r#"{ "dependencies": { "vite": { "version": "4.5.2" } } }"#;
+        assert_eq!(from_package_lock(v1, "vite").as_deref(), Some("4.5.2"));
+
+        let pnpm_v9 = "lockfileVersion: '9.0'\n\nimporters:\n\n  .:\n    dependencies:\n      next:\n        specifier: ^14.0.0\n        version: 14.1.0(react-dom@18.2.0(react@18.2.0))(react@18.2.0)\n      '@remix-run/dev':\n        specifier: 2.8.0\n        version: 2.8.1\n";
+        assert_eq!(from_pnpm_lock(pnpm_v9, "next").as_deref(), Some("14.1.0"));
+        assert_eq!(
+            from_pnpm_lock(pnpm_v9, "@remix-run/dev").as_deref(),
+            Some("2.8.1")
+        );
+        let pnpm_v5 = "lockfileVersion: 5.4\n\nspecifiers:\n  vite: ^4.0.0\n\ndevDependencies:\n  vite: 4.5.2_@types+node@20.1.0\n";
+        assert_eq!(from_pnpm_lock(pnpm_v5, "vite").as_deref(), Some("4.5.2"));
+        let packages_only = "packages:\n\n  /astro@4.4.0:\n    resolution: {integrity: sha512-x}\n";
+        assert_eq!(
+            from_pnpm_lock(packages_only, "astro").as_deref(),
+            Some("4.4.0")
+        );
+
+        let yarn_v1 = "# yarn lockfile v1\n\n\"@vitejs/plugin-react@^4.0.0\":\n  version \"4.2.1\"\n\nvite@^5.0.0, vite@^5.0.10:\n  version \"5.1.4\"\n  resolved \"https://registry.yarnpkg.com/vite/-/vite-5.1.4.tgz\"\n";
+        assert_eq!(from_yarn_lock(yarn_v1, "vite").as_deref(), Some("5.1.4"));
+        assert_eq!(
+            from_yarn_lock(yarn_v1, "@vitejs/plugin-react").as_deref(),
+            Some("4.2.1")
+        );
+        let berry = "__metadata:\n  version: 8\n\n\"next@npm:^13.5.0\":\n  version: 13.5.6\n  resolution: \"next@npm:13.5.6\"\n";
+        assert_eq!(from_yarn_lock(berry, "next").as_deref(), Some("13.5.6"));
+        assert_eq!(from_yarn_lock(berry, "nex"), None);
+
+        let bun = "{\n  \"lockfileVersion\": 1,\n  \"packages\": {\n    \"next\": [\"next@14.2.3\", \"\", {}, \"sha512-x\"],\n  },\n}\n";
+        assert_eq!(from_bun_lock(bun, "next").as_deref(), Some("14.2.3"));
+    }
+
+    #[test]
+    fn test_installed_prefers_node_modules() {
+        let dir = tempfile::tempdir().unwrap();
+        let next = dir.path().join("node_modules/next");
+        fs::create_dir_all(&next).unwrap();
+        fs::write(next.join("package.json"), r#"{ "version": "14.2.0" }"#).unwrap();
+        fs::write(
+            dir.path().join("yarn.lock"),
+            "next@^14.0.0:\n  version \"14.1.0\"\nvite@^5.0.0:\n  version \"5.1.4\"\n",
+        )
+        .unwrap();
+
+        let next = installed(dir.path(), Ecosystem::Npm, "next").unwrap();
+        assert_eq!(next.version, "14.2.0");
+        assert_eq!(next.source, "node_modules/next/package.json");
+        let vite = installed(dir.path(), Ecosystem::Npm, "vite").unwrap();
+        assert_eq!(
+            (vite.version.as_str(), vite.source.as_str()),
+            ("5.1.4", "yarn.lock")
+        );
+        assert_eq!(installed(dir.path(), Ecosystem::Npm, "astro"), None);
+    }
+
+    #[test]
+    fn test_other_ecosystems() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |name: &str, text: &str| fs::write(dir.path().join(name), text).unwrap();
+        write(
+            "Cargo.toml",
+            "[package]\nname = \"api\"\n\n[dependencies]\naxum = \"0.7\"\ntokio = { version = \"1\", features = [\"full\"] }\n",
+        );
+        write(
+            "Cargo.lock",
+            "version = 3\n\n[[package]]\nname = \"axum\"\nversion = \"0.7.4\"\n",
+        );
+        write(
+            "go.mod",
+            "module example.com/api\n\ngo 1.22\n\nrequire github.com/labstack/echo/v4 v4.11.4\n\nrequire (\n\tgithub.com/gin-gonic/gin v1.9.1 // indirect\n)\n",
+        );
+        write(
+            "requirements.txt",
+            "# web\n-r base.txt\nFastAPI[all]==0.110.0\nuvicorn>=0.27 ; python_version > '3.8'\n",
+        );
+        write(
+            "pyproject.toml",
+            "[tool.poetry.dependencies]\npython = \"^3.11\"\nDjango = \"^5.0\"\n",
+        );
+        write(
+            "poetry.lock",
+            "[[package]]\nname = \"django\"\nversion = \"5.0.3\"\n",
+        );
+
+        let deps = declared(dir.path());
+        let range = |eco: Ecosystem, name: &str| {
+            deps.iter()
+                .find(|d| d.ecosystem == eco && d.name == name)
+                .map(|d| d.range.as_str())
+        };
+        assert_eq!(range(Ecosystem::Cargo, "tokio"), Some("1"));
+        assert_eq!(
+            range(Ecosystem::Go, "github.com/gin-gonic/gin"),
+            Some("v1.9.1")
+        );
+        assert_eq!(range(Ecosystem::Python, "fastapi"), Some("==0.110.0"));
+        assert_eq!(range(Ecosystem::Python, "uvicorn"), Some(">=0.27"));
+        assert_eq!(range(Ecosystem::Python, "django"), Some("^5.0"));
+        assert_eq!(range(Ecosystem::Python, "python"), None);
+
+        let version =
+            |eco: Ecosystem, name: &str| installed(dir.path(), eco, name).map(|i| i.version);
+        assert_eq!(version(Ecosystem::Cargo, "axum").as_deref(), Some("0.7.4"));
+        assert_eq!(version(Ecosystem::Cargo, "tokio"), None);
+        assert_eq!(
+            version(Ecosystem::Go, "github.com/labstack/echo/v4").as_deref(),
+            Some("v4.11.4")
+        );
+        assert_eq!(
+            version(Ecosystem::Python, "fastapi").as_deref(),
+            Some("0.110.0")
+        );
+        // Только диапазон — установленная неизвестна
+        assert_eq!(version(Ecosystem::Python, "uvicorn"), None);
+        assert_eq!(
+            version(Ecosystem::Python, "django").as_deref(),
+            Some("5.0.3")
+        );
+    }
+
+    #[test]
+    fn test_jvm_ruby_php_elixir_dotnet() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |name: &str, text: &str| fs::write(dir.path().join(name), text).unwrap();
+        write(
+            "Gemfile",
+            "source \"https://rubygems.org\"\ngem \"rails\", \"~> 7.1\"\ngem 'puma' # сервер\n",
+        );
+        write(
+            "Gemfile.lock",
+            "GEM\n  specs:\n    actionpack (7.1.3)\n      rack (>= 2.2.4)\n    nokogiri (1.16.2-x86_64-linux)\n    rails (7.1.3)\n",
+        );
+        write(
+            "composer.json",
+            r#"{ "require": { "php": "^8.2", "laravel/framework": "^11.0" } }"#,
+        );
+        write(
+            "composer.lock",
+            r#"{ "packages": [{ "name": "laravel/framework", "version": "v11.0.7" }] }"#,
+        );
+        write(
+            "pom.xml",
+            "<project>\n  <parent>\n    <groupId>org.springframework.boot</groupId>\n    <artifactId>spring-boot-starter-parent</artifactId>\n    <version>3.2.2</version>\n  </parent>\n  <dependencies>\n    <dependency>\n      <groupId>org.springframework.boot</groupId>\n      <artifactId>spring-boot-starter-web</artifactId>\n    </dependency>\n  </dependencies>\n</project>\n",
+        );
+        write(
+            "build.gradle.kts",
+            "plugins {\n    id(\"org.springframework.boot\") version \"3.2.0\"\n}\ndependencies {\n    implementation(\"org.springframework.boot:spring-boot-starter-webflux\")\n}\n",
+        );
+        write(
+            "mix.exs",
+            "  defp deps do\n    [\n      {:phoenix, \"~> 1.7.10\"},\n      {:jason, \">= 0.0.0\"}\n    ]\n  end\n",
+        );
+        write(
+            "mix.lock",
+            "%{\n  \"phoenix\": {:hex, :phoenix, \"1.7.11\", \"abc\", [:mix], [], \"hexpm\", \"def\"},\n}\n",
+        );
+        write(
+            "Api.csproj",
+            "<Project Sdk=\"Microsoft.NET.Sdk.Web\">\n  <ItemGroup>\n    <PackageReference Include=\"Swashbuckle.AspNetCore\" Version=\"6.5.0\" />\n  </ItemGroup>\n</Project>\n",
+        );
+
+        let deps = declared(dir.path());
+        let range = |eco: Ecosystem, name: &str| {
+            deps.iter()
+                .find(|d| d.ecosystem == eco && d.name == name)
+                .map(|d| d.range.as_str())
+        };
+        assert_eq!(range(Ecosystem::Ruby, "rails"), Some("~> 7.1"));
+        assert_eq!(range(Ecosystem::Ruby, "puma"), Some(""));
+        assert_eq!(
+            range(Ecosystem::Composer, "laravel/framework"),
+            Some("^11.0")
+        );
+        assert_eq!(
+            range(Ecosystem::Maven, "spring-boot-starter-parent"),
+            Some("3.2.2")
+        );
+        assert_eq!(range(Ecosystem::Maven, "spring-boot-starter-web"), Some(""));
+        assert_eq!(
+            range(Ecosystem::Maven, "org.springframework.boot"),
+            Some("3.2.0")
+        );
+        assert_eq!(
+            range(Ecosystem::Maven, "spring-boot-starter-webflux"),
+            Some("")
+        );
+        assert_eq!(range(Ecosystem::Hex, "phoenix"), Some("~> 1.7.10"));
+        assert_eq!(range(Ecosystem::Nuget, "Microsoft.NET.Sdk.Web"), Some(""));
+        assert_eq!(
+            range(Ecosystem::Nuget, "Swashbuckle.AspNetCore"),
+            Some("6.5.0")
+        );
+
+        let version =
+            |eco: Ecosystem, name: &str| installed(dir.path(), eco, name).map(|i| i.version);
+        assert_eq!(version(Ecosystem::Ruby, "rails").as_deref(), Some("7.1.3"));
+        assert_eq!(
+            version(Ecosystem::Ruby, "nokogiri").as_deref(),
+            Some("1.16.2")
+        );
+        // Зависимость гема, а не сам гем
+        assert_eq!(version(Ecosystem::Ruby, "rack"), None);
+        assert_eq!(
+            version(Ecosystem::Composer, "laravel/framework").as_deref(),
+            Some("11.0.7")
+        );
+        assert_eq!(
+            version(Ecosystem::Hex, "phoenix").as_deref(),
+            Some("1.7.11")
+        );
+        assert_eq!(version(Ecosystem::Maven, "spring-boot-starter-web"), None);
+    }
+
+    #[test]
+    fn test_split_pattern() {
+        assert_eq!(Ecosystem::split("next"), (Ecosystem::Npm, "next"));
+        assert_eq!(Ecosystem::split("cargo:axum"), (Ecosystem::Cargo, "axum"));
+        assert_eq!(
+            Ecosystem::split("pypi:fastapi"),
+            (Ecosystem::Python, "fastapi")
+        );
+        assert_eq!(Ecosystem::split("gem:rails"), (Ecosystem::Ruby, "rails"));
+        assert_eq!(
+            Ecosystem::split("composer:laravel/framework"),
+            (Ecosystem::Composer, "laravel/framework")
+        );
+        assert_eq!(normalize_python("Flask_Login"), "flask-login");
+    }
+}
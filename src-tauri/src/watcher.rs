@@ -0,0 +1,244 @@
+//! Фоновый наблюдатель: хранит последний снимок серверов и считает diff
+//! между сканами. Изменения уходят в UI событиями `server-added`,
+//! `server-removed`, `server-changed`.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+use tauri::{AppHandle, Emitter};
+
+use crate::health::Health;
+use crate::scanner::Protocol;
+use crate::Server;
+
+/// Один сервер в снимке — процесс + порт + протокол
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ServerKey {
+    pub pid: u32,
+    pub port: u16,
+    pub protocol: Protocol,
+}
+
+impl ServerKey {
+    pub fn of(server: &Server) -> Self {
+        ServerKey {
+            pid: server.pid,
+            port: server.port,
+            protocol: server.protocol,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Diff {
+    pub added: Vec<Server>,
+    /// Последнее известное состояние исчезнувших серверов
+    pub removed: Vec<Server>,
+    pub changed: Vec<Server>,
+}
+
+impl Diff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// uptime растёт каждый скан, задержка проверки меняется каждый раз —
+/// изменением это не считаем
+fn same_state(a: &Server, b: &Server) -> bool {
+    Server {
+        uptime_seconds: 0,
+        health: a.health.as_ref().map(Health::state_only),
+        ..a.clone()
+    } == Server {
+        uptime_seconds: 0,
+        health: b.health.as_ref().map(Health::state_only),
+        ..b.clone()
+    }
+}
+
+/// Сравнить два снимка; порядок результатов — как в `new` (для removed — как в `old`)
+pub fn diff(old: &[Server], new: &[Server]) -> Diff {
+    let old_by_key: HashMap<ServerKey, &Server> =
+        old.iter().map(|s| (ServerKey::of(s), s)).collect();
+    let new_by_key: HashMap<ServerKey, &Server> =
+        new.iter().map(|s| (ServerKey::of(s), s)).collect();
+
+    let mut result = Diff::default();
+    for server in new {
+        match old_by_key.get(&ServerKey::of(server)) {
+            None => result.added.push(server.clone()),
+            Some(prev) if !same_state(prev, server) => result.changed.push(server.clone()),
+            Some(_) => {}
+        }
+    }
+    result.removed = old
+        .iter()
+        .filter(|s| !new_by_key.contains_key(&ServerKey::of(s)))
+        .cloned()
+        .collect();
+    result
+}
+
+/// Снимок в managed state Tauri. `None` — первый скан ещё не завершился.
+#[derive(Default)]
+pub struct Watcher {
+    snapshot: Mutex<Option<Vec<Server>>>,
+    /// Фоновый и ручной скан не должны перемешать снимки
+    scan: Mutex<()>,
+}
+
+impl Watcher {
+    pub fn snapshot(&self) -> Option<Vec<Server>> {
+        self.snapshot.lock().ok()?.clone()
+    }
+
+    /// Держать на время скан + `update`
+    pub fn lock_scan(&self) -> MutexGuard<'_, ()> {
+        self.scan.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Запомнить новый снимок, вернуть отличия от предыдущего.
+    /// Первый снимок — без событий: UI получает его через `get_servers`.
+    pub fn update(&self, servers: Vec<Server>) -> Diff {
+        let mut snapshot = self.snapshot.lock().unwrap_or_else(|e| e.into_inner());
+        let result = match snapshot.as_deref() {
+            Some(old) => diff(old, &servers),
+            None => Diff::default(),
+        };
+        *snapshot = Some(servers);
+        result
+    }
+}
+
+/// Разослать diff всем окнам
+pub fn emit(app: &AppHandle, diff: &Diff) {
+    if diff.is_empty() {
+        return;
+    }
+    for server in &diff.added {
+        let _ = app.emit("server-added", server);
+    }
+    for server in &diff.removed {
+        let _ = app.emit("server-removed", server);
+    }
+    for server in &diff.changed {
+        let _ = app.emit("server-changed", server);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detector::Framework;
+    use crate::health::HealthStatus;
+
+    fn framework(name: &str) -> Framework {
+        Framework {
+            name: name.to_string(),
+            version: None,
+            confidence: 0.9,
+            evidence: Vec::new(),
+        }
+    }
+
+    fn server(pid: u32, port: u16, protocol: Protocol) -> Server {
+        Server {
+            protocol,
+            project_name: "shop".to_string(),
+            framework: Some(framework("Vite")),
+            uptime_seconds: 10,
+            description: "Node.js".to_string(),
+            ..Server::sample(pid, port)
+        }
+    }
+
+    fn keys(servers: &[Server]) -> Vec<(u32, u16)> {
+        servers.iter().map(|s| (s.pid, s.port)).collect()
+    }
+
+    #[test]
+    fn test_diff_added_and_removed() {
+        let old = vec![
+            server(1, 3000, Protocol::Tcp),
+            server(2, 5173, Protocol::Tcp),
+        ];
+        let new = vec![
+            server(2, 5173, Protocol::Tcp),
+            server(3, 8080, Protocol::Tcp),
+        ];
+        let d = diff(&old, &new);
+        assert_eq!(keys(&d.added), vec![(3, 8080)]);
+        assert_eq!(keys(&d.removed), vec![(1, 3000)]);
+        assert!(d.changed.is_empty());
+    }
+
+    #[test]
+    fn test_diff_ignores_uptime() {
+        let old = vec![server(1, 3000, Protocol::Tcp)];
+        let mut new = old.clone();
+        new[0].uptime_seconds = 13;
+        assert!(diff(&old, &new).is_empty());
+    }
+
+    #[test]
+    fn test_diff_ignores_health_latency() {
+        let up = |latency_ms| Health {
+            status: HealthStatus::Up,
+            code: Some(200),
+            tls: false,
+            latency_ms: Some(latency_ms),
+            history: vec![latency_ms],
+        };
+        let mut old = vec![server(1, 3000, Protocol::Tcp)];
+        old[0].health = Some(up(12));
+        let mut new = old.clone();
+        new[0].health = Some(up(40));
+        assert!(diff(&old, &new).is_empty());
+
+        new[0].health.as_mut().unwrap().status = HealthStatus::Degraded;
+        assert_eq!(diff(&old, &new).changed.len(), 1);
+    }
+
+    #[test]
+    fn test_diff_changed() {
+        let old = vec![server(1, 3000, Protocol::Tcp)];
+        let mut new = old.clone();
+        new[0].framework = Some(framework("Next.js"));
+        let d = diff(&old, &new);
+        assert_eq!(keys(&d.changed), vec![(1, 3000)]);
+        assert!(d.added.is_empty() && d.removed.is_empty());
+    }
+
+    #[test]
+    fn test_diff_key_includes_protocol() {
+        let old = vec![server(1, 4433, Protocol::Tcp)];
+        let new = vec![server(1, 4433, Protocol::Udp)];
+        let d = diff(&old, &new);
+        assert_eq!(d.added[0].protocol, Protocol::Udp);
+        assert_eq!(d.removed[0].protocol, Protocol::Tcp);
+    }
+
+    #[test]
+    fn test_pid_reuse_on_same_port_is_add_and_remove() {
+        // Перезапуск dev-сервера: новый PID на том же порту
+        let old = vec![server(1, 3000, Protocol::Tcp)];
+        let new = vec![server(7, 3000, Protocol::Tcp)];
+        let d = diff(&old, &new);
+        assert_eq!(keys(&d.added), vec![(7, 3000)]);
+        assert_eq!(keys(&d.removed), vec![(1, 3000)]);
+    }
+
+    #[test]
+    fn test_watcher_first_snapshot_has_no_events() {
+        let watcher = Watcher::default();
+        assert!(watcher.snapshot().is_none());
+        assert!(watcher
+            .update(vec![server(1, 3000, Protocol::Tcp)])
+            .is_empty());
+
+        let d = watcher.update(Vec::new());
+        assert_eq!(keys(&d.removed), vec![(1, 3000)]);
+        assert_eq!(watcher.snapshot(), Some(Vec::new()));
+    }
+}
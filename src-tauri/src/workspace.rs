@@ -0,0 +1,604 @@
+//! Монорепозитории: npm/yarn/pnpm workspaces, Turborepo, Nx, Lerna и Cargo
+//! workspaces. `turbo dev` или `nx serve web` из корня дают процессы с CWD
+//! корня — имя и фреймворк были бы корневыми. Здесь находим корень
+//! workspace над CWD, его пакеты и пакет, к которому относится процесс:
+//! по CWD внутри пакета, по путям в argv (скрипт, `apps/web/node_modules/.bin/next`)
+//! или по имени пакета в аргументах (`--filter web`, `nx serve web`, `cargo run -p api`).
+
+use globset::Glob;
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::cache::DirCache;
+use crate::detector;
+
+/// Глубина `**` в шаблонах пакетов
+const MAX_GLOB_DEPTH: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkspaceKind {
+    Npm,
+    Yarn,
+    Pnpm,
+    /// Пакеты — из workspaces менеджера пакетов, запуск — через turbo
+    Turborepo,
+    Nx,
+    Lerna,
+    Cargo,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Package {
+    pub name: String,
+    pub dir: PathBuf,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Workspace {
+    pub kind: WorkspaceKind,
+    pub root: PathBuf,
+    pub packages: Vec<Package>,
+}
+
+/// Пакет workspace, к которому отнесён процесс (`Server.workspace`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Membership {
+    pub kind: WorkspaceKind,
+    /// Корень монорепозитория
+    pub root: String,
+    pub package: String,
+    /// Папка пакета — по ней определяются имя проекта и фреймворк
+    pub dir: String,
+}
+
+fn read_json(path: &Path) -> Option<Value> {
+    serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+fn read_toml(path: &Path) -> Option<toml::Table> {
+    fs::read_to_string(path).ok()?.parse().ok()
+}
+
+fn strings(value: Option<&Value>) -> Vec<String> {
+    value
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|v| Some(v.as_str()?.to_string()))
+        .collect()
+}
+
+/// Список `packages:` из pnpm-workspace.yaml
+fn pnpm_patterns(text: &str) -> Vec<String> {
+    let mut patterns = Vec::new();
+    let mut in_packages = false;
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if !line.starts_with([' ', '-']) {
+            in_packages = trimmed == "packages:";
+            continue;
+        }
+        if let Some(item) = trimmed.strip_prefix('-').filter(|_| in_packages) {
+            patterns.push(item.trim().trim_matches(['\'', '"']).to_string());
+        }
+    }
+    patterns
+}
+
+/// Шаблоны пакетов JS-монорепозитория и его вид
+fn js_patterns(root: &Path) -> Option<(WorkspaceKind, Vec<String>)> {
+    let package = read_json(&root.join("package.json"));
+    let (mut kind, mut patterns) =
+        if let Ok(text) = fs::read_to_string(root.join("pnpm-workspace.yaml")) {
+            (WorkspaceKind::Pnpm, pnpm_patterns(&text))
+        } else if let Some(workspaces) = package.as_ref().and_then(|p| p.get("workspaces")) {
+            // Массив или `{ "packages": [...] }` (yarn 1)
+            let list = workspaces.get("packages").unwrap_or(workspaces);
+            let kind = if root.join("yarn.lock").exists() {
+                WorkspaceKind::Yarn
+            } else {
+                WorkspaceKind::Npm
+            };
+            (kind, strings(Some(list)))
+        } else if let Some(lerna) = read_json(&root.join("lerna.json")) {
+            let mut patterns = strings(lerna.get("packages"));
+            if patterns.is_empty() {
+                patterns.push("packages/*".to_string());
+            }
+            (WorkspaceKind::Lerna, patterns)
+        } else if root.join("nx.json").exists() {
+            let defaults = ["apps/*", "libs/*", "packages/*"];
+            (WorkspaceKind::Nx, defaults.map(String::from).to_vec())
+        } else {
+            return None;
+        };
+    // Оркестратор поверх workspaces менеджера пакетов
+    if root.join("turbo.json").exists() {
+        kind = WorkspaceKind::Turborepo;
+    } else if root.join("nx.json").exists() {
+        kind = WorkspaceKind::Nx;
+    }
+    patterns.retain(|p| !p.is_empty());
+    Some((kind, patterns))
+}
+
+/// Имя пакета: project.json (Nx), package.json, иначе имя папки
+fn js_package(dir: &Path) -> Option<Package> {
+    let project = read_json(&dir.join("project.json"));
+    let package = read_json(&dir.join("package.json"));
+    if project.is_none() && package.is_none() {
+        return None;
+    }
+    let name = [project, package]
+        .iter()
+        .flatten()
+        .find_map(|json| Some(json.get("name")?.as_str()?.to_string()))
+        .or_else(|| Some(dir.file_name()?.to_str()?.to_string()))?;
+    Some(Package {
+        name,
+        dir: dir.to_path_buf(),
+    })
+}
+
+fn cargo_package(dir: &Path) -> Option<Package> {
+    let manifest = read_toml(&dir.join("Cargo.toml"))?;
+    let name = manifest.get("package")?.get("name")?.as_str()?.to_string();
+    Some(Package {
+        name,
+        dir: dir.to_path_buf(),
+    })
+}
+
+/// Подпапки, в которые не спускаемся при раскрытии шаблонов
+fn skipped(name: &str) -> bool {
+    name.starts_with('.') || matches!(name, "node_modules" | "target" | "dist" | "build")
+}
+
+fn subdirs(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .filter(|e| !skipped(&e.file_name().to_string_lossy()))
+        .map(|e| e.path())
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+/// Раскрыть шаблон "apps/*", "packages/**", "tools/cli" в папки
+fn expand(root: &Path, pattern: &str) -> Vec<PathBuf> {
+    let mut current = vec![root.to_path_buf()];
+    let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+    for segment in pattern.split('/').filter(|s| !s.is_empty()) {
+        current = match segment {
+            "**" => {
+                let mut all = current.clone();
+                let mut level = current;
+                for _ in 0..MAX_GLOB_DEPTH {
+                    level = level.iter().flat_map(|d| subdirs(d)).collect();
+                    all.extend(level.iter().cloned());
+                }
+                all
+            }
+            _ if segment.contains(['*', '?', '[', '{']) => {
+                let Ok(glob) = Glob::new(segment) else {
+                    return Vec::new();
+                };
+                let matcher = glob.compile_matcher();
+                current
+                    .iter()
+                    .flat_map(|d| subdirs(d))
+                    .filter(|d| d.file_name().is_some_and(|n| matcher.is_match(n)))
+                    .collect()
+            }
+            literal => current
+                .iter()
+                .map(|d| d.join(literal))
+                .filter(|d| d.is_dir())
+                .collect(),
+        };
+    }
+    current
+}
+
+/// Пакеты по шаблонам; "!шаблон" исключает
+fn collect(
+    root: &Path,
+    patterns: &[String],
+    package: fn(&Path) -> Option<Package>,
+) -> Vec<Package> {
+    let excluded: Vec<PathBuf> = patterns
+        .iter()
+        .filter_map(|p| p.strip_prefix('!'))
+        .flat_map(|p| expand(root, p))
+        .collect();
+    let mut packages: Vec<Package> = patterns
+        .iter()
+        .filter(|p| !p.starts_with('!'))
+        .flat_map(|p| expand(root, p))
+        .filter(|dir| dir != root && !excluded.contains(dir))
+        .filter_map(|dir| package(&dir))
+        .collect();
+    packages.sort_by(|a, b| a.dir.cmp(&b.dir));
+    packages.dedup_by(|a, b| a.dir == b.dir);
+    packages
+}
+
+/// От этих файлов зависит `Workspace::open`; папки — чтобы заметить новый
+/// пакет в apps/ или packages/ (mtime папки меняется при добавлении)
+const WORKSPACE_KEYS: &[&str] = &[
+    "package.json",
+    "pnpm-workspace.yaml",
+    "yarn.lock",
+    "lerna.json",
+    "nx.json",
+    "turbo.json",
+    "Cargo.toml",
+    "apps",
+    "packages",
+    "libs",
+    "crates",
+    "services",
+];
+
+static OPENED: OnceLock<DirCache<Option<Workspace>>> = OnceLock::new();
+
+/// Запускалки, чьи флаги выбирают пакет; у остальных `-p 3000` — порт
+const ORCHESTRATORS: &[&str] = &["pnpm", "yarn", "npm", "turbo", "nx", "lerna", "cargo"];
+
+impl Workspace {
+    /// Workspace с корнем ровно в `root`
+    pub fn open(root: &Path) -> Option<Workspace> {
+        if let Some((kind, patterns)) = js_patterns(root) {
+            return Some(Workspace {
+                kind,
+                root: root.to_path_buf(),
+                packages: collect(root, &patterns, js_package),
+            });
+        }
+        let manifest = read_toml(&root.join("Cargo.toml"))?;
+        let workspace = manifest.get("workspace")?;
+        let list = |key: &str| -> Vec<String> {
+            workspace
+                .get(key)
+                .and_then(toml::Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(|v| Some(v.as_str()?.to_string()))
+                .collect()
+        };
+        let mut patterns = list("members");
+        patterns.extend(list("exclude").into_iter().map(|p| format!("!{}", p)));
+        Some(Workspace {
+            kind: WorkspaceKind::Cargo,
+            root: root.to_path_buf(),
+            packages: collect(root, &patterns, cargo_package),
+        })
+    }
+
+    /// Ближайший workspace над `cwd` (включая её). Выше репозитория
+    /// (папки с `.git`) и до домашней папки не поднимаемся — как и
+    /// `detector::project_root`. Разбор каждой папки кэшируется, пока
+    /// не изменились её манифесты и обычные папки пакетов.
+    pub fn find(cwd: &Path) -> Option<Workspace> {
+        Workspace::find_below(cwd, detector::home_dir().as_deref())
+    }
+
+    fn find_below(cwd: &Path, home: Option<&Path>) -> Option<Workspace> {
+        let cache = OPENED.get_or_init(DirCache::default);
+        detector::search_dirs(cwd, home)
+            .find_map(|dir| cache.get(dir, WORKSPACE_KEYS, || Workspace::open(dir)))
+    }
+
+    /// Самый глубокий пакет, внутри которого лежит `path`
+    fn containing(&self, path: &Path) -> Option<&Package> {
+        self.packages
+            .iter()
+            .filter(|p| path.starts_with(&p.dir))
+            .max_by_key(|p| p.dir.components().count())
+    }
+
+    /// Пакет по имени из аргумента: "@acme/web", "web" (без scope), имя папки,
+    /// путь от корня ("./apps/web", "{apps/web}"); "web..." (pnpm) — тоже web
+    fn named(&self, arg: &str) -> Option<&Package> {
+        let arg = arg
+            .trim_matches(['{', '}'])
+            .trim_end_matches("...")
+            .trim_start_matches("...^")
+            .trim_start_matches("...");
+        if arg.is_empty() {
+            return None;
+        }
+        if arg.contains('/') && !arg.starts_with('@') {
+            let dir = self.root.join(arg.trim_start_matches("./"));
+            return self.packages.iter().find(|p| p.dir == dir);
+        }
+        self.packages.iter().find(|p| {
+            p.name == arg
+                || p.name.rsplit_once('/').is_some_and(|(_, bare)| bare == arg)
+                || p.dir.file_name().is_some_and(|n| n == arg)
+        })
+    }
+
+    /// Пакет процесса: CWD внутри пакета, путь в argv, имя пакета в аргументах
+    pub fn attribute(&self, cwd: &Path, argv: &[String]) -> Option<&Package> {
+        if let Some(package) = self.containing(cwd) {
+            return Some(package);
+        }
+        // Скрипт или бинарник пакета: "apps/api/server.js", ".../apps/web/node_modules/.bin/next"
+        let by_path = argv.iter().find_map(|arg| {
+            let value = arg.split_once('=').map_or(arg.as_str(), |(_, v)| v);
+            let path = Path::new(value);
+            if !(path.is_absolute() || value.contains('/') || value.contains('\\')) {
+                return None;
+            }
+            self.containing(&cwd.join(path))
+        });
+        if by_path.is_some() {
+            return by_path;
+        }
+        self.by_argument(argv)
+    }
+
+    /// `--filter web`, `-F web`, `--scope web`, `--workspace web`, `-w web`,
+    /// `--project web`, `-p api` / `--package api`, `yarn workspace web dev`,
+    /// `nx serve web` / `nx run web:serve`
+    fn by_argument(&self, argv: &[String]) -> Option<&Package> {
+        // Флаги читаем только у менеджера пакетов или оркестратора
+        // (сам или через node): `next dev -p 3000`, `tsc -w` — не про пакеты
+        let Some(orchestrator) = argv
+            .iter()
+            .take(2)
+            .map(|a| crate::catalog::arg_token(a))
+            .find(|t| ORCHESTRATORS.contains(&t.as_str()))
+        else {
+            return self.by_nx_target(argv);
+        };
+        let mut flags = vec!["--filter", "-F", "--scope", "--project", "-p", "--package"];
+        // `-w` у pnpm — это `--workspace-root`, а не выбор пакета
+        if orchestrator == "npm" {
+            flags.extend(["--workspace", "-w"]);
+        }
+        for (i, arg) in argv.iter().enumerate() {
+            let next = argv.get(i + 1).map(String::as_str);
+            let value = match arg.split_once('=') {
+                Some((flag, value)) if flags.contains(&flag) => Some(value),
+                _ if flags.contains(&arg.as_str()) => next,
+                _ if arg == "workspace" => next,
+                _ => None,
+            };
+            if let Some(package) = value.and_then(|v| self.named(v)) {
+                return Some(package);
+            }
+        }
+        self.by_nx_target(argv)
+    }
+
+    /// nx <target> <project> / nx run <project>:<target>
+    fn by_nx_target(&self, argv: &[String]) -> Option<&Package> {
+        let nx = argv
+            .iter()
+            .position(|a| crate::catalog::arg_token(a) == "nx")?;
+        argv[nx + 1..]
+            .iter()
+            .filter(|a| !a.starts_with('-'))
+            .find_map(|a| self.named(a.split(':').next().unwrap_or(a)))
+    }
+
+    /// Пакет процесса с корнем этого workspace — для `Server.workspace`
+    pub fn membership(&self, cwd: &Path, argv: &[String]) -> Option<Membership> {
+        let package = self.attribute(cwd, argv)?;
+        Some(Membership {
+            kind: self.kind,
+            root: self.root.to_string_lossy().into_owned(),
+            package: package.name.clone(),
+            dir: package.dir.to_string_lossy().into_owned(),
+        })
+    }
+}
+
+/// Пакет монорепозитория, к которому относится процесс; None — не монорепо
+/// или пакет не определить (тогда имя и фреймворк — по CWD)
+pub fn attribute(cwd: &str, argv: &[String]) -> Option<Membership> {
+    let cwd = Path::new(cwd);
+    Workspace::find(cwd)?.membership(cwd, argv)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(root: &Path, file: &str, text: &str) {
+        let path = root.join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, text).unwrap();
+    }
+
+    fn argv(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    /// apps/web (Next.js), apps/docs, packages/ui, а node_modules — не пакет
+    fn js_monorepo(root: &Path) {
+        write(
+            root,
+            "package.json",
+            r#"{ "name": "acme", "private": true }"#,
+        );
+        write(root, "apps/web/package.json", r#"{ "name": "@acme/web" }"#);
+        write(root, "apps/docs/package.json", r#"{ "name": "docs" }"#);
+        write(
+            root,
+            "packages/ui/package.json",
+            r#"{ "name": "@acme/ui" }"#,
+        );
+        write(
+            root,
+            "node_modules/left-pad/package.json",
+            r#"{ "name": "left-pad" }"#,
+        );
+    }
+
+    fn names(workspace: &Workspace) -> Vec<&str> {
+        workspace.packages.iter().map(|p| p.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_workspace_kinds() {
+        let pnpm = tempfile::tempdir().unwrap();
+        js_monorepo(pnpm.path());
+        write(
+            pnpm.path(),
+            "pnpm-workspace.yaml",
+            "packages:\n  - 'apps/*'\n  - \"packages/**\"\n  - '!apps/docs'\n",
+        );
+        let workspace = Workspace::open(pnpm.path()).unwrap();
+        assert_eq!(workspace.kind, WorkspaceKind::Pnpm);
+        assert_eq!(names(&workspace), vec!["@acme/web", "@acme/ui"]);
+        write(pnpm.path(), "turbo.json", "{}");
+        assert_eq!(
+            Workspace::open(pnpm.path()).unwrap().kind,
+            WorkspaceKind::Turborepo
+        );
+
+        let yarn = tempfile::tempdir().unwrap();
+        js_monorepo(yarn.path());
+        write(
+            yarn.path(),
+            "package.json",
+            r#"{ "workspaces": { "packages": ["apps/*"] } }"#,
+        );
+        write(yarn.path(), "yarn.lock", "");
+        let workspace = Workspace::open(yarn.path()).unwrap();
+        assert_eq!(workspace.kind, WorkspaceKind::Yarn);
+        assert_eq!(names(&workspace), vec!["docs", "@acme/web"]);
+
+        let lerna = tempfile::tempdir().unwrap();
+        js_monorepo(lerna.path());
+        write(
+            lerna.path(),
+            "lerna.json",
+            r#"{ "version": "independent" }"#,
+        );
+        assert_eq!(
+            names(&Workspace::open(lerna.path()).unwrap()),
+            vec!["@acme/ui"]
+        );
+
+        let nx = tempfile::tempdir().unwrap();
+        write(nx.path(), "nx.json", "{}");
+        write(nx.path(), "apps/api/project.json", r#"{ "name": "api" }"#);
+        write(nx.path(), "libs/shared/README.md", "");
+        let workspace = Workspace::open(nx.path()).unwrap();
+        assert_eq!(workspace.kind, WorkspaceKind::Nx);
+        assert_eq!(names(&workspace), vec!["api"]);
+
+        let cargo = tempfile::tempdir().unwrap();
+        write(
+            cargo.path(),
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"crates/*\"]\nexclude = [\"crates/old\"]\n",
+        );
+        write(
+            cargo.path(),
+            "crates/api/Cargo.toml",
+            "[package]\nname = \"api-server\"\n",
+        );
+        write(
+            cargo.path(),
+            "crates/old/Cargo.toml",
+            "[package]\nname = \"old\"\n",
+        );
+        let workspace = Workspace::open(cargo.path()).unwrap();
+        assert_eq!(workspace.kind, WorkspaceKind::Cargo);
+        assert_eq!(names(&workspace), vec!["api-server"]);
+
+        // Обычный проект — не workspace
+        let plain = tempfile::tempdir().unwrap();
+        write(plain.path(), "package.json", r#"{ "name": "solo" }"#);
+        assert_eq!(Workspace::open(plain.path()), None);
+    }
+
+    #[test]
+    fn test_attribute_process_to_package() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        js_monorepo(root);
+        write(
+            root,
+            "package.json",
+            r#"{ "workspaces": ["apps/*", "packages/*"] }"#,
+        );
+        write(root, ".git/HEAD", "");
+        let root_str = root.to_str().unwrap();
+        let web = root.join("apps/web");
+
+        // CWD внутри пакета (turbo/pnpm запускают скрипт в папке пакета)
+        let found = attribute(web.join("src").to_str().unwrap(), &[]).unwrap();
+        assert_eq!(found.package, "@acme/web");
+        assert_eq!(found.kind, WorkspaceKind::Npm);
+        assert_eq!(found.root, root_str);
+
+        // CWD — корень: по пути бинарника или скрипта в argv
+        let next = format!("node {}/node_modules/.bin/next dev", web.display());
+        assert_eq!(
+            attribute(root_str, &argv(&next)).unwrap().package,
+            "@acme/web"
+        );
+        let script = attribute(root_str, &argv("node packages/ui/server.js")).unwrap();
+        assert_eq!(script.package, "@acme/ui");
+
+        // По имени пакета в аргументах
+        let filter = argv("pnpm --filter=web dev");
+        assert_eq!(attribute(root_str, &filter).unwrap().package, "@acme/web");
+        let yarn = argv("yarn workspace docs dev");
+        assert_eq!(attribute(root_str, &yarn).unwrap().package, "docs");
+        let nx = argv("node /usr/lib/node_modules/nx/bin/nx.js run docs:serve");
+        assert_eq!(attribute(root_str, &nx).unwrap().package, "docs");
+
+        // -p / -w не у менеджера пакетов — порт и watch, а не пакет
+        let port = argv("node node_modules/.bin/next dev -p web");
+        assert_eq!(attribute(root_str, &port), None);
+        assert_eq!(attribute(root_str, &argv("tsc -w docs")), None);
+        let cargo = argv("cargo run -p ui");
+        assert_eq!(attribute(root_str, &cargo).unwrap().package, "@acme/ui");
+        // -w у pnpm — `--workspace-root`, у npm — выбор пакета
+        assert_eq!(attribute(root_str, &argv("pnpm -w docs")), None);
+        let npm = argv("npm run dev -w docs");
+        assert_eq!(attribute(root_str, &npm).unwrap().package, "docs");
+
+        // Корневой node_modules — не пакет, и подсказок нет
+        let vite = argv("node node_modules/.bin/vite");
+        assert_eq!(attribute(root_str, &vite), None);
+    }
+
+    #[test]
+    fn test_find_stops_at_repository() {
+        let dir = tempfile::tempdir().unwrap();
+        js_monorepo(dir.path());
+        write(
+            dir.path(),
+            "package.json",
+            r#"{ "workspaces": ["apps/*"] }"#,
+        );
+        // Вложенный репозиторий со своим package.json — чужой workspace не берём
+        let nested = dir.path().join("vendor/tool");
+        write(&nested, "package.json", r#"{ "name": "tool" }"#);
+        write(&nested, ".git/HEAD", "");
+        assert_eq!(Workspace::find(&nested), None);
+        let web = dir.path().join("apps/web");
+        assert_eq!(Workspace::find(&web).unwrap().root, dir.path());
+        // Как и корень проекта: в домашнюю папку не поднимаемся
+        assert_eq!(Workspace::find_below(&web, Some(dir.path())), None);
+    }
+}
@@ -2,13 +2,30 @@ import { useState, useEffect, useCallback } from "react";
 import { Search } from "lucide-react";
 import { TitleBar } from "./components/TitleBar";
 import { ServerList } from "./components/ServerList";
+import { ProjectList } from "./components/ProjectList";
 import { StatusBar } from "./components/StatusBar";
 import { useServers } from "./hooks/useServers";
+import { useProjects } from "./hooks/useProjects";
+import { useSettings } from "./hooks/useSettings";
+import type { Server } from "./types";
 
 export default function App() {
-  const { servers, loading, error, lastUpdate, refresh, killServer, openInBrowser } =
-    useServers();
+  const { settings } = useSettings();
+  const {
+    servers,
+    loading,
+    error,
+    lastUpdate,
+    refresh,
+    killServer,
+    previewKillTree,
+    restartServer,
+    openInBrowser,
+  } = useServers();
+  const { projects, addProject, updateProject, removeProject, startProject, stopProject } =
+    useProjects();
   const [filter, setFilter] = useState("");
+  const [protocol, setProtocol] = useState<"all" | Server["protocol"]>("all");
 
   const handleKeyDown = useCallback((e: KeyboardEvent) => {
     if ((e.ctrlKey || e.metaKey) && e.key === "k") {
@@ -26,22 +43,25 @@ export default function App() {
     return () => document.removeEventListener("keydown", handleKeyDown);
   }, [handleKeyDown]);
 
+  const byProtocol =
+    protocol === "all" ? servers : servers.filter((s) => s.protocol === protocol);
+
   const filtered = filter
-    ? servers.filter(
+    ? byProtocol.filter(
         (s) =>
           s.project_name.toLowerCase().includes(filter.toLowerCase()) ||
           s.process_name.toLowerCase().includes(filter.toLowerCase()) ||
           String(s.port).includes(filter)
       )
-    : servers;
+    : byProtocol;
 
   return (
     <div className="h-screen flex flex-col bg-dock-bg rounded-lg overflow-hidden">
       <TitleBar serverCount={servers.length} />
 
       {servers.length > 0 && (
-        <div className="px-3 pb-1">
-          <div className="relative">
+        <div className="px-3 pb-1 flex items-center gap-1.5">
+          <div className="relative flex-1">
             <Search
               size={12}
               className="absolute left-2.5 top-1/2 -translate-y-1/2 text-dock-muted"
@@ -55,15 +75,43 @@ export default function App() {
               className="w-full bg-white/[0.03] border border-white/[0.06] rounded-lg pl-8 pr-2 py-1.5 text-[11px] text-dock-text placeholder:text-dock-muted/40 focus:outline-none focus:border-dock-accent/40 transition-colors"
             />
           </div>
+          <div className="flex items-center rounded-lg border border-white/[0.06] overflow-hidden">
+            {(["all", "tcp", "udp"] as const).map((p) => (
+              <button
+                key={p}
+                onClick={() => setProtocol(p)}
+                className={`px-2 py-1.5 text-[10px] uppercase cursor-pointer transition-colors ${
+                  protocol === p
+                    ? "bg-dock-accent/15 text-dock-accent"
+                    : "text-dock-muted hover:text-dock-text"
+                }`}
+              >
+                {p === "all" ? "Всі" : p}
+              </button>
+            ))}
+          </div>
         </div>
       )}
 
+      <ProjectList
+        projects={projects}
+        servers={servers}
+        onAdd={addProject}
+        onUpdate={updateProject}
+        onRemove={removeProject}
+        onStart={startProject}
+        onStop={stopProject}
+      />
+
       <ServerList
         servers={filtered}
         loading={loading}
         error={error}
         onKill={killServer}
+        onPreviewTree={previewKillTree}
+        onRestart={restartServer}
         onOpen={openInBrowser}
+        defaultCollapsed={settings?.collapsed_categories}
       />
       <StatusBar lastUpdate={lastUpdate} onRefresh={refresh} />
     </div>
@@ -0,0 +1,36 @@
+import { useEffect, useRef } from "react";
+import { useLogs } from "../hooks/useLogs";
+
+/** Хвост вывода процесса; stderr подсвечен, прокрутка держится внизу */
+export function LogView({ pid }: { pid: number }) {
+  const lines = useLogs(pid);
+  const bottomRef = useRef<HTMLDivElement>(null);
+
+  useEffect(() => {
+    bottomRef.current?.scrollIntoView({ block: "end" });
+  }, [lines]);
+
+  return (
+    <div
+      className="mt-1.5 max-h-40 overflow-y-auto rounded bg-black/30 px-2 py-1 text-[10px] leading-snug"
+      style={{ fontFamily: "'JetBrains Mono', ui-monospace, monospace" }}
+    >
+      {lines.length === 0 ? (
+        <p className="text-dock-muted/60">Порожньо</p>
+      ) : (
+        lines.map((line) => (
+          <p
+            key={line.seq}
+            className={`whitespace-pre-wrap break-all ${
+              line.stream === "stderr" ? "text-dock-red/80" : "text-dock-muted"
+            }`}
+            title={new Date(line.timestamp_ms).toLocaleTimeString()}
+          >
+            {line.text}
+          </p>
+        ))
+      )}
+      <div ref={bottomRef} />
+    </div>
+  );
+}
@@ -0,0 +1,251 @@
+import { useState } from "react";
+import {
+  ChevronRight,
+  FolderPlus,
+  Play,
+  Square,
+  Trash2,
+  Loader2,
+  ScrollText,
+  RotateCw,
+} from "lucide-react";
+import type {
+  Project,
+  ProjectState,
+  RestartEvent,
+  RestartPolicy,
+  Server,
+  StopOutcome,
+} from "../types";
+import { stopError } from "./ServerCard";
+import { LogView } from "./LogView";
+
+interface ProjectListProps {
+  projects: ProjectState[];
+  /** Для портов запущенных проектов (Server.project_id) */
+  servers: Server[];
+  onAdd: (path: string) => Promise<unknown>;
+  onUpdate: (project: Project) => Promise<Project>;
+  onRemove: (id: string) => Promise<void>;
+  onStart: (id: string) => Promise<number>;
+  onStop: (id: string) => Promise<StopOutcome>;
+}
+
+export function ProjectList({
+  projects,
+  servers,
+  onAdd,
+  onUpdate,
+  onRemove,
+  onStart,
+  onStop,
+}: ProjectListProps) {
+  const [open, setOpen] = useState(false);
+  const [path, setPath] = useState("");
+  const [busy, setBusy] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  /** Проект, чей вывод раскрыт */
+  const [logsFor, setLogsFor] = useState<string | null>(null);
+
+  const run = async (id: string, action: () => Promise<string | null>) => {
+    setBusy(id);
+    setError(null);
+    try {
+      setError(await action());
+    } catch (err) {
+      setError(String(err));
+    }
+    setBusy(null);
+  };
+
+  const handleAdd = () =>
+    run("", async () => {
+      await onAdd(path);
+      setPath("");
+      return null;
+    });
+
+  return (
+    <div className="px-3 pb-1">
+      <button
+        onClick={() => setOpen(!open)}
+        className="flex items-center gap-1 text-[10px] uppercase tracking-wider text-dock-muted hover:text-dock-text cursor-pointer transition-colors"
+      >
+        <ChevronRight size={10} className={`transition-transform ${open ? "rotate-90" : ""}`} />
+        Проєкти
+        {projects.length > 0 && <span className="opacity-60">({projects.length})</span>}
+      </button>
+
+      {open && (
+        <div className="mt-1.5 space-y-1 animate-fade-in">
+          {projects.map((project) => {
+            const ports = servers
+              .filter((s) => s.project_id === project.id)
+              .map((s) => `:${s.port}`);
+            const running = project.pid !== null;
+            const lastRestart = project.restarts[project.restarts.length - 1];
+            return (
+              <div key={project.id} className="glass-card rounded-lg px-2.5 py-1.5">
+                <div className="flex items-center justify-between gap-2">
+                  <div className="min-w-0">
+                    <p className="text-[11px] text-dock-text truncate flex items-center gap-1.5">
+                      <span
+                        className={`w-1.5 h-1.5 rounded-full shrink-0 ${
+                          running ? "bg-dock-green" : "bg-dock-muted"
+                        }`}
+                      />
+                      {project.name}
+                      <span className="text-dock-accent">
+                        {ports.length > 0 ? ports.join(" ") : project.port ? `:${project.port}` : ""}
+                      </span>
+                    </p>
+                    <p
+                      className="text-[10px] text-dock-muted truncate"
+                      style={{ fontFamily: "'JetBrains Mono', ui-monospace, monospace" }}
+                      title={project.path}
+                    >
+                      {project.command || "Команду не задано"}
+                    </p>
+                  </div>
+                  <div className="flex items-center gap-1 shrink-0">
+                    {lastRestart && (
+                      <span
+                        className={`flex items-center gap-0.5 text-[10px] ${
+                          lastRestart.delay_ms === null ? "text-dock-red/80" : "text-amber-500/80"
+                        }`}
+                        title={project.restarts.map(formatRestart).join("\n")}
+                      >
+                        <RotateCw
+                          size={10}
+                          className={project.restart_pending ? "animate-spin" : ""}
+                        />
+                        {project.restarts.length}
+                      </span>
+                    )}
+                    <select
+                      value={project.restart.mode}
+                      onChange={(e) => {
+                        const mode = e.target.value as RestartPolicy["mode"];
+                        run(project.id, async () => {
+                          await onUpdate({
+                            ...toProject(project),
+                            restart: { ...project.restart, mode },
+                          });
+                          return null;
+                        });
+                      }}
+                      className="bg-transparent text-[10px] text-dock-muted hover:text-dock-text cursor-pointer focus:outline-none"
+                      title="Автоперезапуск"
+                      aria-label="Автоперезапуск"
+                    >
+                      <option value="never">Без перезапуску</option>
+                      <option value="on_failure">При збої</option>
+                      <option value="always">Завжди</option>
+                    </select>
+                    {project.log_pid !== null && (
+                      <button
+                        onClick={() => setLogsFor(logsFor === project.id ? null : project.id)}
+                        className={`p-1 rounded-lg cursor-pointer transition-all duration-150 ${
+                          logsFor === project.id
+                            ? "text-dock-accent bg-dock-accent/10"
+                            : "text-dock-muted hover:text-dock-text hover:bg-white/[0.06]"
+                        }`}
+                        aria-label="Вивід процесу"
+                      >
+                        <ScrollText size={11} />
+                      </button>
+                    )}
+                    {busy === project.id ? (
+                      <Loader2 size={12} className="animate-spin text-dock-muted" />
+                    ) : running ? (
+                      <button
+                        onClick={() =>
+                          run(project.id, async () => stopError(await onStop(project.id)))
+                        }
+                        className="p-1 rounded-lg text-dock-muted hover:text-dock-red hover:bg-dock-red/10 cursor-pointer transition-all duration-150"
+                        aria-label="Зупинити проєкт"
+                      >
+                        <Square size={11} />
+                      </button>
+                    ) : (
+                      <button
+                        onClick={() =>
+                          run(project.id, async () => {
+                            await onStart(project.id);
+                            return null;
+                          })
+                        }
+                        disabled={!project.command}
+                        className="p-1 rounded-lg text-dock-green/70 hover:text-dock-green hover:bg-dock-green/10 cursor-pointer transition-all duration-150 disabled:opacity-40 disabled:cursor-not-allowed"
+                        aria-label="Запустити проєкт"
+                      >
+                        <Play size={11} />
+                      </button>
+                    )}
+                    <button
+                      onClick={() =>
+                        run(project.id, async () => {
+                          await onRemove(project.id);
+                          return null;
+                        })
+                      }
+                      className="p-1 rounded-lg text-dock-muted hover:text-dock-red hover:bg-dock-red/10 cursor-pointer transition-all duration-150"
+                      title="Прибрати зі списку (процес не зупиняється)"
+                      aria-label="Прибрати проєкт"
+                    >
+                      <Trash2 size={11} />
+                    </button>
+                  </div>
+                </div>
+                {logsFor === project.id && project.log_pid !== null && (
+                  <LogView pid={project.log_pid} />
+                )}
+              </div>
+            );
+          })}
+
+          <div className="flex items-center gap-1.5">
+            <input
+              type="text"
+              value={path}
+              onChange={(e) => setPath(e.target.value)}
+              onKeyDown={(e) => e.key === "Enter" && path.trim() && handleAdd()}
+              placeholder="Шлях до папки проєкту"
+              className="flex-1 bg-white/[0.03] border border-white/[0.06] rounded-lg px-2 py-1 text-[11px] text-dock-text placeholder:text-dock-muted/40 focus:outline-none focus:border-dock-accent/40 transition-colors"
+            />
+            <button
+              onClick={handleAdd}
+              disabled={!path.trim() || busy !== null}
+              className="p-1.5 rounded-lg text-dock-muted hover:text-dock-accent hover:bg-dock-accent/10 cursor-pointer transition-all duration-150 disabled:opacity-40 disabled:cursor-not-allowed"
+              aria-label="Додати проєкт"
+            >
+              <FolderPlus size={12} />
+            </button>
+          </div>
+          {error && <p className="text-[10px] text-dock-red">{error}</p>}
+        </div>
+      )}
+    </div>
+  );
+}
+
+/** Только сохраняемые поля — без состояния процесса */
+function toProject(state: ProjectState): Project {
+  const { id, name, path, command, port, env, restart, health_path } = state;
+  return { id, name, path, command, port, env, restart, health_path };
+}
+
+function formatRestart(event: RestartEvent): string {
+  const time = new Date(event.timestamp_ms).toLocaleTimeString();
+  const reason =
+    event.reason.kind === "unhealthy"
+      ? "порт не відповідав"
+      : event.reason.code === null
+        ? "завершено сигналом"
+        : `код виходу ${event.reason.code}`;
+  const next =
+    event.delay_ms === null
+      ? "ліміт перезапусків вичерпано"
+      : `перезапуск через ${Math.round(event.delay_ms / 1000)} с`;
+  return `${time}: ${reason}, ${next}`;
+}
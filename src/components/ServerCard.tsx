@@ -1,22 +1,53 @@
 import { useState } from "react";
-import { ExternalLink, Square, Loader2, AlertTriangle } from "lucide-react";
-import type { Server } from "../types";
+import { ExternalLink, Square, Loader2, AlertTriangle, ListTree, RotateCw } from "lucide-react";
+import type {
+  BindAddress,
+  Fingerprint,
+  Framework,
+  Health,
+  RestartOutcome,
+  Server,
+  StopOutcome,
+  TreeProcess,
+  WorkspaceMember,
+} from "../types";
 
 interface ServerCardProps {
   server: Server;
-  onKill: (pid: number) => Promise<boolean>;
-  onOpen: (port: number) => void;
+  onKill: (pid: number, tree?: boolean) => Promise<StopOutcome>;
+  /** Превью «Стоп дерево» — показываем перед подтверждением */
+  onPreviewTree: (pid: number) => Promise<TreeProcess[]>;
+  onRestart: (pid: number, port: number) => Promise<RestartOutcome>;
+  onOpen: (pid: number, port: number) => void;
+  /** Текущее время (ms) для uptime */
+  now: number;
 }
 
+const healthColor: Record<Health["status"], string> = {
+  up: "bg-dock-green/10 text-dock-green/80",
+  degraded: "bg-amber-500/10 text-amber-500/80",
+  down: "bg-dock-red/10 text-dock-red/80",
+  not_http: "",
+};
+
 const dotColor: Record<string, string> = {
   dev: "bg-dock-green",
   app: "bg-dock-accent",
   system: "bg-dock-muted",
 };
 
-export function ServerCard({ server, onKill, onOpen }: ServerCardProps) {
+export function ServerCard({
+  server,
+  onKill,
+  onPreviewTree,
+  onRestart,
+  onOpen,
+  now,
+}: ServerCardProps) {
   const [confirming, setConfirming] = useState(false);
+  const [tree, setTree] = useState<TreeProcess[] | null>(null);
   const [killing, setKilling] = useState(false);
+  const [restarting, setRestarting] = useState(false);
   const [killError, setKillError] = useState<string | null>(null);
 
   const handleKill = async () => {
@@ -27,11 +58,42 @@ export function ServerCard({ server, onKill, onOpen }: ServerCardProps) {
     await doKill();
   };
 
-  const doKill = async () => {
+  const handleTreePreview = async () => {
+    setKillError(null);
+    try {
+      const processes = await onPreviewTree(server.pid);
+      if (processes.length > 0) {
+        setTree(processes);
+      } else {
+        setKillError("Не вдалося зупинити");
+      }
+    } catch {
+      setKillError("Не вдалося зупинити");
+    }
+  };
+
+  const handleRestart = async () => {
+    setRestarting(true);
+    setKillError(null);
+    try {
+      const outcome = await onRestart(server.pid, server.port);
+      setKillError(restartError(outcome));
+    } catch {
+      setKillError("Не вдалося перезапустити");
+    }
+    setRestarting(false);
+  };
+
+  const doKill = async (wholeTree = false) => {
     setKilling(true);
     setKillError(null);
     try {
-      await onKill(server.pid);
+      const outcome = await onKill(server.pid, wholeTree);
+      const error = stopError(outcome);
+      if (error) {
+        setKillError(error);
+        setKilling(false);
+      }
     } catch {
       setKillError("Не вдалося зупинити");
       setKilling(false);
@@ -68,18 +130,90 @@ export function ServerCard({ server, onKill, onOpen }: ServerCardProps) {
         </div>
       )}
 
+      {tree && (
+        <div className="absolute inset-0 bg-dock-bg/95 backdrop-blur-sm rounded-xl flex flex-col z-10 p-3 animate-fade-in-scale">
+          <p className="text-[11px] text-dock-text mb-1.5">
+            Зупинити {tree.length} {tree.length === 1 ? "процес" : "процеси"}?
+          </p>
+          <ul className="flex-1 overflow-y-auto mb-2 space-y-0.5">
+            {tree.map((p) => (
+              <li
+                key={p.pid}
+                className="text-[10px] text-dock-muted flex justify-between gap-2"
+                style={{ fontFamily: "'JetBrains Mono', ui-monospace, monospace" }}
+              >
+                <span className="truncate">
+                  {p.name || "?"} <span className="opacity-60">PID {p.pid}</span>
+                </span>
+                <span className="text-dock-accent shrink-0">
+                  {p.ports.map((port) => `:${port}`).join(" ")}
+                </span>
+              </li>
+            ))}
+          </ul>
+          <div className="flex items-center justify-end gap-2">
+            <button
+              onClick={() => setTree(null)}
+              className="px-3 py-1 rounded-lg text-[11px] text-dock-muted hover:text-dock-text hover:bg-white/[0.06] transition-colors cursor-pointer"
+            >
+              Ні
+            </button>
+            <button
+              onClick={() => {
+                setTree(null);
+                doKill(true);
+              }}
+              className="px-3 py-1 rounded-lg text-[11px] text-dock-red bg-dock-red/10 hover:bg-dock-red/20 transition-colors cursor-pointer"
+            >
+              Зупинити всі
+            </button>
+          </div>
+        </div>
+      )}
+
       {/* Row 1: name + port */}
       <div className="flex items-start justify-between mb-1">
         <div className="flex items-center gap-2 min-w-0">
           <span
             className={`w-2 h-2 rounded-full shrink-0 ${dotColor[server.category] ?? "bg-dock-muted"}`}
           />
-          <span className="text-sm font-medium truncate">
+          <span
+            className="text-sm font-medium truncate"
+            title={[server.command_line.join(" "), server.project_root].filter(Boolean).join("\n") || undefined}
+          >
+            {server.icon && <span className="mr-1">{server.icon}</span>}
             {server.project_name}
           </span>
+          {server.workspace && (
+            <span
+              className="text-[9px] px-1 rounded bg-dock-accent/10 text-dock-accent/80 shrink-0"
+              title={formatWorkspace(server.workspace)}
+            >
+              {workspaceNames[server.workspace.kind]}
+            </span>
+          )}
         </div>
-        <span className="text-sm font-mono text-dock-accent shrink-0 ml-2" style={{ fontFamily: "'JetBrains Mono', ui-monospace, monospace" }}>
-          :{server.port}
+        <span
+          className="text-sm font-mono text-dock-accent shrink-0 ml-2 flex items-center gap-1"
+          style={{ fontFamily: "'JetBrains Mono', ui-monospace, monospace" }}
+          title={server.addresses.map(formatAddress).join("\n")}
+        >
+          {server.protocol === "udp" && (
+            <span className="text-[9px] px-1 rounded bg-dock-accent/10 text-dock-accent/80">UDP</span>
+          )}
+          {server.warnings.length > 0 && (
+            <span
+              className="text-amber-500/80"
+              title={server.warnings.map((w) => w.message).join("\n")}
+              aria-label="Конфлікт порту"
+            >
+              <AlertTriangle size={12} />
+            </span>
+          )}
+          {server.addresses.some((a) => a.scope !== "loopback") && (
+            <span className="text-[9px] px-1 rounded bg-amber-500/10 text-amber-500/80">LAN</span>
+          )}
+          {displayHost(server)}:{server.port}
         </span>
       </div>
 
@@ -88,7 +222,11 @@ export function ServerCard({ server, onKill, onOpen }: ServerCardProps) {
         {killError ? (
           <p className="text-[11px] text-dock-red">{killError}</p>
         ) : server.description ? (
-          <p className="text-[11px] text-dock-muted">{server.description}</p>
+          <p className="text-[11px] text-dock-muted" title={server.fingerprint?.detail ?? undefined}>
+            {server.description}
+          </p>
+        ) : server.fingerprint ? (
+          <p className="text-[11px] text-dock-muted">{describeFingerprint(server.fingerprint)}</p>
         ) : (
           <p className="text-[11px] text-amber-500/70 flex items-center gap-1">
             <AlertTriangle size={11} />
@@ -100,18 +238,50 @@ export function ServerCard({ server, onKill, onOpen }: ServerCardProps) {
       {/* Row 3: uptime + action buttons */}
       <div className="flex items-center justify-between ml-4">
         <span className="text-[11px] text-dock-muted" style={{ fontFamily: "'JetBrains Mono', ui-monospace, monospace" }}>
-          {server.framework ? `${server.framework} · ` : ""}
-          {formatUptime(server.uptime_seconds)}
+          {server.framework && (
+            <span title={formatFramework(server.framework)}>{server.framework.name} · </span>
+          )}
+          {formatUptime(liveUptime(server, now))}
+          {server.health && server.health.status !== "not_http" && (
+            <span
+              className={`ml-1.5 text-[9px] px-1 rounded ${healthColor[server.health.status]}`}
+              title={formatHealth(server.health)}
+            >
+              {healthLabel(server.health)}
+            </span>
+          )}
         </span>
 
         <div className="flex items-center gap-1">
+          {server.protocol === "tcp" && (
+            <button
+              onClick={() => onOpen(server.pid, server.port)}
+              className="flex items-center gap-1 px-2 py-1 rounded-lg text-[11px] text-dock-green/70 hover:text-dock-green hover:bg-dock-green/10 cursor-pointer transition-all duration-150"
+              aria-label="Відкрити в браузері"
+            >
+              <ExternalLink size={12} />
+              Відкрити
+            </button>
+          )}
+          {server.category === "dev" && (
+            <button
+              onClick={handleRestart}
+              disabled={killing || restarting}
+              className="flex items-center px-1.5 py-1 rounded-lg text-dock-muted hover:text-dock-accent hover:bg-dock-accent/10 cursor-pointer transition-all duration-150 disabled:opacity-60 disabled:cursor-wait"
+              title="Перезапустити тією ж командою в тій же папці"
+              aria-label="Перезапустити сервер"
+            >
+              <RotateCw size={12} className={restarting ? "animate-spin" : ""} />
+            </button>
+          )}
           <button
-            onClick={() => onOpen(server.port)}
-            className="flex items-center gap-1 px-2 py-1 rounded-lg text-[11px] text-dock-green/70 hover:text-dock-green hover:bg-dock-green/10 cursor-pointer transition-all duration-150"
-            aria-label="Відкрити в браузері"
+            onClick={handleTreePreview}
+            disabled={killing || restarting}
+            className="flex items-center px-1.5 py-1 rounded-lg text-dock-muted hover:text-dock-red hover:bg-dock-red/10 cursor-pointer transition-all duration-150 disabled:opacity-60 disabled:cursor-wait"
+            title="Зупинити разом із запускачем і дочірніми процесами"
+            aria-label="Зупинити дерево процесів"
           >
-            <ExternalLink size={12} />
-            Відкрити
+            <ListTree size={12} />
           </button>
           <button
             onClick={handleKill}
@@ -136,6 +306,112 @@ export function ServerCard({ server, onKill, onOpen }: ServerCardProps) {
   );
 }
 
+export function stopError(outcome: StopOutcome): string | null {
+  switch (outcome) {
+    case "failed":
+      return "Не вдалося зупинити";
+    case "access_denied":
+      return "Немає прав, щоб зупинити";
+    default:
+      return null;
+  }
+}
+
+function restartError(outcome: RestartOutcome): string | null {
+  switch (outcome.status) {
+    case "restarted":
+      return null;
+    case "timeout":
+      return "Запущено, але порт не відкрився";
+    case "not_recorded":
+      return "Невідома команда запуску";
+    case "stop_failed":
+      return stopError(outcome.outcome) ?? "Не вдалося зупинити";
+    case "spawn_failed":
+      return `Не вдалося запустити: ${outcome.error}`;
+  }
+}
+
+/** Подсказка: версия, уверенность и что сработало */
+function formatFramework(framework: Framework): string {
+  const lines = [
+    framework.version ? `${framework.name} ${framework.version}` : framework.name,
+    `Впевненість: ${Math.round(framework.confidence * 100)}%`,
+    ...framework.evidence,
+  ];
+  return lines.join("\n");
+}
+
+const workspaceNames: Record<WorkspaceMember["kind"], string> = {
+  npm: "npm",
+  yarn: "Yarn",
+  pnpm: "pnpm",
+  turborepo: "Turborepo",
+  nx: "Nx",
+  lerna: "Lerna",
+  cargo: "Cargo",
+};
+
+/** Подсказка: какой пакет какого монорепо */
+function formatWorkspace(workspace: WorkspaceMember): string {
+  return [`Пакет ${workspace.package} у монорепо`, workspace.root, workspace.dir].join("\n");
+}
+
+const serviceNames: Record<Fingerprint["service"], string> = {
+  http: "HTTP-сервер",
+  tls: "TLS-сервіс",
+  http2: "HTTP/2 (ймовірно gRPC)",
+  postgres: "PostgreSQL",
+  mysql: "MySQL",
+  redis: "Redis",
+  mongodb: "MongoDB",
+  ssh: "SSH-сервер",
+  amqp: "AMQP-брокер",
+};
+
+/** Для процессов не из каталога: "Схоже на PostgreSQL · 16.2" */
+function describeFingerprint(fingerprint: Fingerprint): string {
+  const name = `Схоже на ${serviceNames[fingerprint.service]}`;
+  return fingerprint.detail ? `${name} · ${fingerprint.detail}` : name;
+}
+
+/** "200 · 12мс", "TLS · 8мс", "нема відповіді" */
+function healthLabel(health: Health): string {
+  if (health.status === "down") return "не відповідає";
+  if (health.latency_ms === null) return "нема відповіді";
+  const what = health.code ?? (health.tls ? "TLS" : "");
+  return `${what} · ${health.latency_ms}мс`;
+}
+
+function formatHealth(health: Health): string {
+  const lines = [
+    health.code !== null ? `HTTP ${health.code}` : health.tls ? "Код прихований TLS" : "Без відповіді",
+    health.tls ? "З'єднання: TLS" : "З'єднання: без шифрування",
+  ];
+  if (health.history.length > 0) {
+    lines.push(`Затримка, мс: ${health.history.join(" ")}`);
+  }
+  return lines.join("\n");
+}
+
+/** Хост показываем только если сервер слушает конкретный интерфейс */
+function displayHost(server: Server): string {
+  if (server.addresses.some((a) => a.scope !== "specific")) return "";
+  const first = server.addresses[0];
+  if (!first) return "";
+  return first.family === "ipv6" ? `[${first.ip}]` : first.ip;
+}
+
+function formatAddress(a: BindAddress): string {
+  const host = a.family === "ipv6" ? `[${a.ip}]` : a.ip;
+  return `${host} (${a.family}, ${a.scope})`;
+}
+
+function liveUptime(server: Server, now: number): number {
+  const elapsed = server.seen_at ? Math.floor((now - server.seen_at) / 1000) : 0;
+  return server.uptime_seconds + Math.max(0, elapsed);
+}
+
 function formatUptime(seconds: number): string {
   if (seconds < 60) return `${seconds}с`;
   if (seconds < 3600) return `${Math.floor(seconds / 60)}хв`;
@@ -1,14 +1,19 @@
-import { useState } from "react";
+import { useState, useEffect } from "react";
 import { ChevronRight } from "lucide-react";
-import type { Server } from "../types";
+import type { RestartOutcome, Server, StopOutcome, TreeProcess } from "../types";
 import { ServerCard } from "./ServerCard";
+import { serverKey } from "../hooks/useServers";
 
 interface ServerListProps {
   servers: Server[];
   loading: boolean;
   error: string | null;
-  onKill: (pid: number) => Promise<boolean>;
-  onOpen: (port: number) => void;
+  onKill: (pid: number, tree?: boolean) => Promise<StopOutcome>;
+  onPreviewTree: (pid: number) => Promise<TreeProcess[]>;
+  onRestart: (pid: number, port: number) => Promise<RestartOutcome>;
+  onOpen: (pid: number, port: number) => void;
+  /** Свёрнутые при запуске категории (из настроек) */
+  defaultCollapsed?: string[];
 }
 
 const categoryLabel: Record<string, string> = {
@@ -30,10 +35,25 @@ export function ServerList({
   loading,
   error,
   onKill,
+  onPreviewTree,
+  onRestart,
   onOpen,
+  defaultCollapsed,
 }: ServerListProps) {
   const [collapsed, setCollapsed] = useState<Set<string>>(new Set(["system"]));
 
+  const [now, setNow] = useState(Date.now());
+
+  useEffect(() => {
+    if (defaultCollapsed) setCollapsed(new Set(defaultCollapsed));
+  }, [defaultCollapsed]);
+
+  // Uptime досчитывается локально — бэкенд шлёт события только при изменениях
+  useEffect(() => {
+    const tick = setInterval(() => setNow(Date.now()), 1000);
+    return () => clearInterval(tick);
+  }, []);
+
   const toggleGroup = (cat: string) => {
     setCollapsed((prev) => {
       const next = new Set(prev);
@@ -81,7 +101,13 @@ export function ServerList({
     );
   }
 
-  const grouped = categoryOrder
+  // Пользовательские категории из каталога — между додатками и системними
+  const custom = [...new Set(servers.map((s) => s.category))]
+    .filter((cat) => !categoryOrder.includes(cat))
+    .sort();
+  const order = [...categoryOrder.slice(0, -1), ...custom, "system"];
+
+  const grouped = order
     .map((cat) => ({
       category: cat,
       label: categoryLabel[cat] ?? cat,
@@ -110,9 +136,12 @@ export function ServerList({
               <div className="py-0.5">
                 {group.items.map((server) => (
                   <ServerCard
-                    key={`${server.pid}-${server.port}`}
+                    key={serverKey(server)}
                     server={server}
+                    now={now}
                     onKill={onKill}
+                    onPreviewTree={onPreviewTree}
+                    onRestart={onRestart}
                     onOpen={onOpen}
                   />
                 ))}
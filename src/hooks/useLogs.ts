@@ -0,0 +1,39 @@
+import { useState, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import type { LogLine } from "../types";
+
+/** Сколько строк держать в UI — буфер бэкенда может быть больше */
+const MAX_LINES = 500;
+
+/** Вывод процесса: снимок get_logs + поток событий log-line */
+export function useLogs(pid: number | null) {
+  const [lines, setLines] = useState<LogLine[]>([]);
+
+  useEffect(() => {
+    setLines([]);
+    if (pid === null) return;
+
+    // Событие может прийти раньше снимка — склеиваем по seq без повторов
+    const append = (incoming: LogLine[]) =>
+      setLines((prev) => {
+        const seen = new Set(prev.map((l) => l.seq));
+        return [...prev, ...incoming.filter((l) => !seen.has(l.seq))]
+          .sort((a, b) => a.seq - b.seq)
+          .slice(-MAX_LINES);
+      });
+
+    invoke<LogLine[]>("get_logs", { pid, since: null })
+      .then(append)
+      .catch((err) => console.error("Failed to get logs:", err));
+
+    const unlisten = listen<LogLine>("log-line", (e) => {
+      if (e.payload.pid === pid) append([e.payload]);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [pid]);
+
+  return lines;
+}
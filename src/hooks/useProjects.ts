@@ -0,0 +1,59 @@
+import { useState, useEffect, useCallback } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import type { Project, ProjectState, StopOutcome } from "../types";
+
+/** Управляемые проекты; перечитываются по событию projects-changed */
+export function useProjects() {
+  const [projects, setProjects] = useState<ProjectState[]>([]);
+
+  const load = useCallback(() => {
+    invoke<ProjectState[]>("get_projects")
+      .then(setProjects)
+      .catch((err) => console.error("Failed to get projects:", err));
+  }, []);
+
+  useEffect(() => {
+    load();
+    const unlisten = listen("projects-changed", load);
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [load]);
+
+  /** Команда и порт подставляются по фреймворку папки */
+  const addProject = useCallback(
+    (path: string) => invoke<Project>("add_project", { path }),
+    []
+  );
+
+  const updateProject = useCallback(
+    (project: Project) => invoke<Project>("update_project", { project }),
+    []
+  );
+
+  const removeProject = useCallback(
+    (id: string) => invoke<void>("remove_project", { id }),
+    []
+  );
+
+  const startProject = useCallback(
+    (id: string) => invoke<number>("start_project", { id }),
+    []
+  );
+
+  const stopProject = useCallback(async (id: string) => {
+    const outcome = await invoke<StopOutcome>("stop_project", { id });
+    load();
+    return outcome;
+  }, [load]);
+
+  return {
+    projects,
+    addProject,
+    updateProject,
+    removeProject,
+    startProject,
+    stopProject,
+  };
+}
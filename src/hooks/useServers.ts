@@ -1,16 +1,32 @@
 import { useState, useEffect, useCallback, useRef } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import type { Server } from "../types";
+import { listen } from "@tauri-apps/api/event";
+import type {
+  HealthUpdate,
+  RestartOutcome,
+  Server,
+  StopOutcome,
+  TreeProcess,
+} from "../types";
 
-const POLL_INTERVAL = 3000;
 const RETRY_DELAY = 1000;
 const MAX_RETRIES = 3;
 
-/** Быстрое сравнение списка серверов по ключевым полям */
-function serversKey(list: Server[]): string {
-  return list
-    .map((s) => `${s.pid}:${s.port}:${s.uptime_seconds}`)
-    .join("|");
+/** Ключ сервера — как у бэкенда: процесс + порт + протокол */
+export function serverKey(s: Server): string {
+  return `${s.pid}-${s.port}-${s.protocol}`;
+}
+
+/** Внутри категории — по порту; группировку делает ServerList */
+function sortServers(list: Server[]): Server[] {
+  return [...list].sort(
+    (a, b) => a.port - b.port || a.protocol.localeCompare(b.protocol)
+  );
+}
+
+/** Отметка времени получения — от неё UI досчитывает uptime без опроса */
+function stamp(s: Server): Server {
+  return { ...s, seen_at: Date.now() };
 }
 
 export function useServers() {
@@ -20,84 +36,85 @@ export function useServers() {
   const [lastUpdate, setLastUpdate] = useState<Date>(new Date());
   const retriesRef = useRef(0);
   const retryTimeoutRef = useRef<number | null>(null);
-  const isRefreshingRef = useRef(false);
 
-  const refresh = useCallback(async () => {
-    if (isRefreshingRef.current) return;
-    isRefreshingRef.current = true;
-    try {
-      const result = await invoke<Server[]>("get_servers");
-      // Обновляем стейт только если данные реально изменились
-      setServers((prev) => {
-        if (serversKey(prev) === serversKey(result)) return prev;
-        return result;
-      });
-      setLastUpdate(new Date());
-      setError(null);
-      retriesRef.current = 0;
-      setLoading(false);
-    } catch (err) {
-      console.error("Failed to get servers:", err);
-      if (retriesRef.current < MAX_RETRIES) {
-        retriesRef.current++;
-        retryTimeoutRef.current = window.setTimeout(refresh, RETRY_DELAY);
-        return;
+  const load = useCallback(
+    async (command: "get_servers" | "refresh_servers") => {
+      try {
+        const result = await invoke<Server[]>(command);
+        setServers(sortServers(result.map(stamp)));
+        setLastUpdate(new Date());
+        setError(null);
+        retriesRef.current = 0;
+        setLoading(false);
+      } catch (err) {
+        console.error("Failed to get servers:", err);
+        if (retriesRef.current < MAX_RETRIES) {
+          retriesRef.current++;
+          retryTimeoutRef.current = window.setTimeout(
+            () => load(command),
+            RETRY_DELAY
+          );
+          return;
+        }
+        setError(String(err));
+        setLoading(false);
       }
-      setError(String(err));
-      setLoading(false);
-    } finally {
-      isRefreshingRef.current = false;
-    }
-  }, []);
+    },
+    []
+  );
 
-  // Пауза polling при скрытом окне
-  useEffect(() => {
-    refresh();
+  const refresh = useCallback(() => load("refresh_servers"), [load]);
 
-    let interval: number | null = null;
+  // Снимок один раз, дальше — только события фонового наблюдателя
+  useEffect(() => {
+    load("get_servers");
 
-    const startPolling = () => {
-      if (interval) return;
-      interval = window.setInterval(() => {
-        if (retriesRef.current === 0) {
-          refresh();
-        }
-      }, POLL_INTERVAL);
+    const upsert = (server: Server) => {
+      const key = serverKey(server);
+      setServers((prev) =>
+        sortServers([...prev.filter((s) => serverKey(s) !== key), stamp(server)])
+      );
+      setLastUpdate(new Date());
     };
-
-    const stopPolling = () => {
-      if (interval) {
-        clearInterval(interval);
-        interval = null;
-      }
+    const remove = (server: Server) => {
+      const key = serverKey(server);
+      setServers((prev) => prev.filter((s) => serverKey(s) !== key));
+      setLastUpdate(new Date());
     };
 
-    const handleVisibility = () => {
-      if (document.hidden) {
-        stopPolling();
-      } else {
-        refresh(); // сразу обновить при возврате
-        startPolling();
-      }
+    // Проверки здоровья идут в фоне и приходят отдельно от скана
+    const updateHealth = (updates: HealthUpdate[]) => {
+      setServers((prev) =>
+        prev.map((s) => {
+          const update = updates.find(
+            (u) => u.pid === s.pid && u.port === s.port && s.protocol === "tcp"
+          );
+          return update ? { ...s, health: update.health } : s;
+        })
+      );
     };
 
-    startPolling();
-    document.addEventListener("visibilitychange", handleVisibility);
+    const unlisten = [
+      listen<Server>("server-added", (e) => upsert(e.payload)),
+      listen<Server>("server-changed", (e) => upsert(e.payload)),
+      listen<Server>("server-removed", (e) => remove(e.payload)),
+      listen<HealthUpdate[]>("server-health", (e) => updateHealth(e.payload)),
+    ];
 
     return () => {
-      stopPolling();
-      document.removeEventListener("visibilitychange", handleVisibility);
+      unlisten.forEach((p) => p.then((fn) => fn()));
       if (retryTimeoutRef.current) {
         clearTimeout(retryTimeoutRef.current);
       }
     };
-  }, [refresh]);
+  }, [load]);
 
   const killServer = useCallback(
-    async (pid: number): Promise<boolean> => {
+    async (pid: number, tree = false): Promise<StopOutcome> => {
       try {
-        const result = await invoke<boolean>("kill_server", { pid });
-        setTimeout(refresh, 500);
+        // Бэкенд возвращается, когда порт уже освобождён (или по таймауту)
+        const result = await invoke<StopOutcome>("kill_server", { pid, tree });
+        refresh();
         return result;
       } catch (err) {
         console.error("Failed to kill server:", err);
@@ -107,9 +124,21 @@ export function useServers() {
     [refresh]
   );
 
-  const openInBrowser = useCallback(async (port: number) => {
+  /** Остановить и запустить той же командой; ждёт, пока порт снова откроется */
+  const restartServer = useCallback(
+    (pid: number, port: number) => invoke<RestartOutcome>("restart_server", { pid, port }),
+    []
+  );
+
+  /** Что остановит «Стоп дерево»: запускалка (npm run dev) + все потомки */
+  const previewKillTree = useCallback(
+    (pid: number) => invoke<TreeProcess[]>("preview_kill_tree", { pid }),
+    []
+  );
+
+  const openInBrowser = useCallback(async (pid: number, port: number) => {
     try {
-      await invoke("open_in_browser", { port });
+      await invoke("open_in_browser", { pid, port });
     } catch (err) {
       console.error("Failed to open browser:", err);
     }
@@ -122,6 +151,8 @@ export function useServers() {
     lastUpdate,
     refresh,
     killServer,
+    previewKillTree,
+    restartServer,
     openInBrowser,
   };
 }
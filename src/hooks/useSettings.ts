@@ -0,0 +1,33 @@
+import { useState, useEffect, useCallback } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import type { Settings } from "../types";
+
+/** Настройки из бэкенда; обновляются по событию settings-changed из любого окна */
+export function useSettings() {
+  const [settings, setSettings] = useState<Settings | null>(null);
+
+  useEffect(() => {
+    invoke<Settings>("get_settings")
+      .then(setSettings)
+      .catch((err) => console.error("Failed to get settings:", err));
+
+    const unlisten = listen<Settings>("settings-changed", (event) => {
+      setSettings(event.payload);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  const updateSettings = useCallback(async (next: Settings) => {
+    try {
+      return await invoke<Settings>("update_settings", { settings: next });
+    } catch (err) {
+      console.error("Failed to update settings:", err);
+      throw err;
+    }
+  }, []);
+
+  return { settings, updateSettings };
+}
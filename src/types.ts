@@ -1,10 +1,218 @@
+export interface BindAddress {
+  ip: string;
+  family: "ipv4" | "ipv6";
+  scope: "loopback" | "any" | "specific";
+}
+
+export interface PortWarning {
+  kind: "shared_port" | "port_fallback" | "unexpected_owner";
+  message: string;
+}
+
 export interface Server {
   pid: number;
   port: number;
+  protocol: "tcp" | "udp";
   project_name: string;
-  framework: string;
+  /** Папка с манифестом выше CWD; null — не dev или не нашлась */
+  project_root: string | null;
+  /** Фреймворк dev-сервера; null — не dev или не определён */
+  framework: Framework | null;
   uptime_seconds: number;
   process_name: string;
-  category: "dev" | "app" | "system";
+  /** dev / app / system или пользовательская категория из каталога процессов */
+  category: string;
   description: string;
+  icon: string | null;
+  /** argv процесса; пустой, если ОС не дала его прочитать */
+  command_line: string[];
+  addresses: BindAddress[];
+  /** Конфликты портов, найденные бэкендом */
+  warnings: PortWarning[];
+  /** Управляемый проект, из которого запущен сервер */
+  project_id: string | null;
+  /** Последняя HTTP-проверка; null — ещё не проверяли или UDP */
+  health: Health | null;
+  /** Протокол порта по баннеру или рукопожатию */
+  fingerprint: Fingerprint | null;
+  /** Пакет монорепозитория; null — не dev или обычный проект */
+  workspace: WorkspaceMember | null;
+  /** Только на клиенте: когда получен (ms), от него тикает uptime */
+  seen_at?: number;
+}
+
+/** up — ответ < 500 быстрее секунды; not_http — порт говорит не по HTTP */
+export type HealthStatus = "up" | "degraded" | "down" | "not_http";
+
+export interface Health {
+  status: HealthStatus;
+  /** HTTP-код; null для TLS и ошибок */
+  code: number | null;
+  tls: boolean;
+  latency_ms: number | null;
+  /** Задержки последних проверок, старые первыми */
+  history: number[];
+}
+
+export interface Framework {
+  name: string;
+  /** Как объявлена в манифесте, например "^14.0.0" */
+  version: string | null;
+  /** 0..1 */
+  confidence: number;
+  /** Что сработало: "package.json: devDependencies.vite", "manage.py" */
+  evidence: string[];
+}
+
+export type Service =
+  | "http"
+  | "tls"
+  | "http2"
+  | "postgres"
+  | "mysql"
+  | "redis"
+  | "mongodb"
+  | "ssh"
+  | "amqp";
+
+export interface Fingerprint {
+  service: Service;
+  /** Server / X-Powered-By, версия SSH или MySQL, продукт AMQP */
+  detail: string | null;
+}
+
+export type WorkspaceKind = "npm" | "yarn" | "pnpm" | "turborepo" | "nx" | "lerna" | "cargo";
+
+export interface WorkspaceMember {
+  kind: WorkspaceKind;
+  /** Корень монорепозитория */
+  root: string;
+  package: string;
+  /** Папка пакета */
+  dir: string;
+}
+
+/** Событие server-health */
+export interface HealthUpdate {
+  pid: number;
+  port: number;
+  health: Health;
+}
+
+/** Результат kill_server: мягко, принудительно, не вышло, нет прав */
+export type StopOutcome = "graceful" | "forced" | "failed" | "access_denied";
+
+/** Результат restart_server */
+export type RestartOutcome =
+  | { status: "restarted"; pid: number }
+  | { status: "timeout" }
+  | { status: "not_recorded" }
+  | { status: "stop_failed"; outcome: StopOutcome }
+  | { status: "spawn_failed"; error: string };
+
+/** Процесс из превью «Стоп дерево» */
+export interface TreeProcess {
+  pid: number;
+  name: string;
+  ports: number[];
+}
+
+/** Управляемый проект (projects.json) + PID запущенной команды */
+export interface Project {
+  id: string;
+  name: string;
+  path: string;
+  /** Команда для оболочки, например "npm run dev" */
+  command: string;
+  port: number | null;
+  env: Record<string, string>;
+  restart: RestartPolicy;
+  /** Путь HTTP-проверки; null — "/" */
+  health_path: string | null;
+}
+
+/** Политика перезапуска управляемого проекта */
+export interface RestartPolicy {
+  mode: "never" | "on_failure" | "always";
+  /** Лимит перезапусков за window_secs */
+  max_restarts: number;
+  window_secs: number;
+  /** Первая задержка; дальше вдвое дольше, до max_backoff_ms */
+  backoff_ms: number;
+  max_backoff_ms: number;
+  /** Ожидаемый порт молчит дольше — перезапуск; null — не проверять */
+  health_timeout_secs: number | null;
+}
+
+export interface RestartEvent {
+  timestamp_ms: number;
+  reason: { kind: "exited"; code: number | null } | { kind: "unhealthy" };
+  /** null — лимит исчерпан, больше не перезапускаем */
+  delay_ms: number | null;
+}
+
+export interface ProjectState extends Project {
+  /** PID оболочки, если проект запущен из LocalDock */
+  pid: number | null;
+  /** PID последнего запуска с сохранённым выводом (в том числе упавшего) */
+  log_pid: number | null;
+  /** Перезапуски по политике, старые первыми */
+  restarts: RestartEvent[];
+  /** Процесс вышел, перезапуск запланирован */
+  restart_pending: boolean;
+}
+
+/** Строка вывода процесса, запущенного из LocalDock */
+export interface LogLine {
+  pid: number;
+  /** Сквозной номер; get_logs(pid, since) отдаёт строки после него */
+  seq: number;
+  timestamp_ms: number;
+  stream: "stdout" | "stderr";
+  text: string;
+}
+
+export interface PortRange {
+  start: number;
+  end: number;
+}
+
+export interface PortFilter {
+  include: PortRange[];
+  exclude: PortRange[];
+  ignored_processes: string[];
+}
+
+export interface NotificationRule {
+  category: string | null;
+  ports: PortRange[];
+  project: string | null;
+  started: boolean;
+  stopped: boolean;
+  crashed: boolean;
+}
+
+export interface LogSettings {
+  /** Строк в памяти на процесс */
+  buffer_lines: number;
+  /** Дублировать в logs/<проект>.log */
+  to_disk: boolean;
+  max_file_bytes: number;
+}
+
+export interface NotificationSettings {
+  enabled: boolean;
+  rules: NotificationRule[];
+}
+
+export interface Settings {
+  version: number;
+  poll_interval_ms: number;
+  close_to_tray: boolean;
+  /** Сколько ждать мягкого завершения перед принудительным (ms) */
+  stop_timeout_ms: number;
+  collapsed_categories: string[];
+  port_filter: PortFilter;
+  notifications: NotificationSettings;
+  logs: LogSettings;
 }